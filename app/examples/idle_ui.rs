@@ -0,0 +1,74 @@
+//! Runs the user interface plugins without any widget and prints how long the frame schedules take,
+//! so the widget systems can be checked to cost nothing while no UI exists, like during gameplay.
+//! Build with `--features trace_chrome` to also record a span for every system.
+
+use bevy::{app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*, utils::Instant};
+
+use game_loading::*;
+use game_preferences::*;
+use game_vfx::*;
+use game_widgets::*;
+
+
+/// Frames measured once the assets are loaded
+const FRAMES: u32 = 600;
+
+fn main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins(BevyPlugins(Args { skip_intro: true, lowram: false, powersaver: false, record: false }));
+    app.add_plugins((AssetCachePlugin, VFXPlugin, WidgetsPlugin));
+    app.init_resource::<ScheduleProfile>();
+    app.add_systems(Startup, |mut commands: Commands| { commands.spawn(Camera2d); });
+
+    // Time everything from PreUpdate to PostUpdate, where the widget systems run
+    app.init_schedule(ProfileStart);
+    app.init_schedule(ProfileEnd);
+    let mut order = app.world_mut().resource_mut::<MainScheduleOrder>();
+    order.insert_after(First, ProfileStart);
+    order.insert_after(PostUpdate, ProfileEnd);
+    app.add_systems(ProfileStart, ScheduleProfile::start_system);
+    app.add_systems(ProfileEnd, ScheduleProfile::end_system.run_if(not(in_state(UiAssetsState::Loading))));
+    app.run()
+}
+
+/// The schedule running before PreUpdate to start the timer
+#[derive(ScheduleLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct ProfileStart;
+
+/// The schedule running after PostUpdate to stop the timer
+#[derive(ScheduleLabel, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct ProfileEnd;
+
+/// The time spent in the timed schedules
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+struct ScheduleProfile {
+    started: Option<Instant>,
+    frames: u32,
+    total: f64,
+    worst: f64,
+}
+impl ScheduleProfile {
+    /// This system starts the timer of the frame.
+    fn start_system(mut profile: ResMut<ScheduleProfile>) {
+        profile.started = Some(Instant::now());
+    }
+    /// This system adds up the time of the frame and prints the result once enough frames are measured.
+    fn end_system(mut profile: ResMut<ScheduleProfile>, schedules: Res<Schedules>, mut exit: EventWriter<AppExit>) {
+        let Some(started) = profile.started.take() else { return; };
+        let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+        profile.frames += 1;
+        profile.total += elapsed;
+        profile.worst = profile.worst.max(elapsed);
+        if profile.frames < FRAMES { return; }
+
+        let systems: usize = [PreUpdate.intern(), Update.intern(), PostUpdate.intern()].into_iter()
+            .filter_map(|label| schedules.get(label))
+            .map(|schedule| schedule.systems_len())
+            .sum();
+        info!(
+            "PreUpdate to PostUpdate without widgets: average {:.3} ms, worst {:.3} ms over {} frames, {systems} systems",
+            profile.total / profile.frames as f64, profile.worst, profile.frames,
+        );
+        exit.send(AppExit::Success);
+    }
+}
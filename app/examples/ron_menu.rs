@@ -0,0 +1,30 @@
+//! Spawns the menu declared in `assets/menus/main.menu.ron` and prints the ids of the clicked buttons.
+//! Edit the file while this runs to see the menu patched in place, the focused button stays focused.
+
+use bevy::prelude::*;
+use bevy_lunex::*;
+
+use game_loading::*;
+use game_preferences::*;
+use game_vfx::*;
+use game_widgets::*;
+
+
+fn main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins(BevyPlugins(Args { skip_intro: true, lowram: false, powersaver: false, record: false }));
+    app.add_plugins((AssetCachePlugin, VFXPlugin, WidgetsPlugin));
+    app.add_systems(Startup, setup);
+    app.add_observer(|trigger: Trigger<MainButtonClicked>, ids: Query<&MenuNodeId>, mut exit: EventWriter<AppExit>| {
+        let Ok(MenuNodeId(id)) = ids.get(trigger.entity()) else { return; };
+        info!("Clicked '{id}'");
+        if id == "quit" { exit.send(AppExit::Success); }
+    });
+    app.run()
+}
+
+/// This system spawns the camera and requests the menu.
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, mut menus: EventWriter<SpawnMenu>) {
+    commands.spawn((Camera2d, UiSourceCamera::<0>, Transform::from_translation(Vec3::Z * 1000.0)));
+    menus.send(SpawnMenu(asset_server.load("menus/main.menu.ron")));
+}
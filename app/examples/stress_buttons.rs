@@ -0,0 +1,94 @@
+//! Spawns a 100×100 grid of compact main buttons and prints the frame stats.
+//! Run with `--test-perf` to exit with an error if the average frame time is above the target,
+//! the vsync is turned off then so the frame times are not capped by the display.
+
+use bevy::{diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin}, prelude::*, window::{PresentMode, PrimaryWindow}};
+use bevy_lunex::*;
+
+use game_loading::*;
+use game_preferences::*;
+use game_vfx::*;
+use game_widgets::*;
+
+
+/// Buttons per row and per column
+const GRID: usize = 100;
+/// The average frame time in milliseconds the perf test must stay under
+const TARGET_FRAME_TIME: f64 = 1000.0 / 60.0;
+/// Seconds before the measurement starts, so loading and building do not count
+const WARMUP: f32 = 5.0;
+/// Seconds the perf test measures for
+const MEASURE: f32 = 10.0;
+
+fn main() -> AppExit {
+    let mut app = App::new();
+    app.add_plugins(BevyPlugins(Args { skip_intro: true, lowram: false, powersaver: false, record: false }));
+    app.add_plugins((AssetCachePlugin, VFXPlugin, WidgetsPlugin, FrameTimeDiagnosticsPlugin));
+    let test = std::env::args().any(|arg| arg == "--test-perf");
+    app.insert_resource(PerfStats { test, ..default() });
+    app.add_systems(Startup, |mut commands: Commands| { commands.spawn((Camera2d, UiSourceCamera::<0>, Transform::from_translation(Vec3::Z * 1000.0))); });
+    if test { app.add_systems(Startup, |mut window: Single<&mut Window, With<PrimaryWindow>>| window.present_mode = PresentMode::AutoNoVsync); }
+    app.add_systems(OnEnter(UiAssetsState::Ready), spawn_grid);
+    app.add_systems(OnEnter(UiAssetsState::Failed), spawn_grid);
+    app.add_systems(Update, PerfStats::system);
+    app.run()
+}
+
+/// This system spawns the grid of buttons.
+fn spawn_grid(mut commands: Commands) {
+    commands.spawn((
+        UiLayoutRoot::new_2d(),
+        UiFetchFromCamera::<0>,
+    )).with_children(|ui| {
+        let size = 100.0 / GRID as f32;
+        for y in 0..GRID {
+            for x in 0..GRID {
+                ui.spawn((
+                    UiLayout::window().pos(Rl((x as f32 * size, y as f32 * size))).size(Rl((size, size))).pack(),
+                    MainButton::new(y * GRID + x).compact(true),
+                ));
+            }
+        }
+    });
+    info!("Spawned {} buttons", GRID * GRID);
+}
+
+/// The frame times measured after the warmup
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+struct PerfStats {
+    /// If to exit with the result once measured
+    test: bool,
+    elapsed: f32,
+    frames: u32,
+    total: f64,
+    worst: f64,
+}
+impl PerfStats {
+    /// This system collects the frame times, prints them every second and ends the perf test.
+    fn system(mut stats: ResMut<PerfStats>, time: Res<Time>, diagnostics: Res<DiagnosticsStore>, mut exit: EventWriter<AppExit>) {
+        stats.elapsed += time.delta_secs();
+        if stats.elapsed < WARMUP { return; }
+
+        let frame = time.delta_secs_f64() * 1000.0;
+        stats.frames += 1;
+        stats.total += frame;
+        stats.worst = stats.worst.max(frame);
+        let average = stats.total / stats.frames as f64;
+
+        let second = (stats.elapsed - time.delta_secs()).floor() != stats.elapsed.floor();
+        if second {
+            let fps = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS).and_then(|fps| fps.smoothed()).unwrap_or_default();
+            info!("{fps:.1} FPS, average {average:.2} ms, worst {:.2} ms over {} frames", stats.worst, stats.frames);
+        }
+
+        if stats.test && stats.elapsed >= WARMUP + MEASURE {
+            if average > TARGET_FRAME_TIME {
+                error!("Average frame time {average:.2} ms is above the target of {TARGET_FRAME_TIME:.2} ms");
+                exit.send(AppExit::error());
+            } else {
+                info!("Average frame time {average:.2} ms is within the target of {TARGET_FRAME_TIME:.2} ms");
+                exit.send(AppExit::Success);
+            }
+        }
+    }
+}
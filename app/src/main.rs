@@ -12,7 +12,9 @@ pub(crate) use game_cameras::*;
 pub(crate) use game_loading::*;
 pub(crate) use game_movies::*;
 pub(crate) use game_preferences::*;
+pub(crate) use game_saves::*;
 pub(crate) use game_vfx::*;
+pub(crate) use game_widgets::*;
 
 
 // #==========================#
@@ -23,6 +25,8 @@ pub(crate) use game_vfx::*;
 enum AppState {
     /// Player intro movie
     IntroMovie,
+    /// Waiting for the asset cache
+    Loading,
     /// The game main menu
     MainMenu,
     /// The game creation
@@ -43,7 +47,7 @@ fn main() -> AppExit {
     //app.add_plugins(UiLunexDebugPlugin::<1, 2>);
 
     // Set the correct app state
-    app.insert_state(if args.skip_intro { AppState::MainMenu } else { AppState::IntroMovie });
+    app.insert_state(if args.skip_intro { AppState::Loading } else { AppState::IntroMovie });
 
 
     // ----- PRIORITY ASSET LOADING  -----
@@ -65,12 +69,28 @@ fn main() -> AppExit {
     // ----- START THE APPLICATION -----
 
     app.add_systems(Startup, spawn_camera);
+    app.add_systems(Update, spawn_cursor.run_if(resource_added::<AssetCache>));
     app.add_systems(OnEnter(AppState::IntroMovie), IntroScene::spawn).add_systems(OnExit(AppState::IntroMovie), despawn_scene::<IntroScene>);
+    app.add_systems(OnEnter(AppState::Loading), LoadingScene::spawn.run_if(resource_exists::<AssetCache>)).add_systems(OnExit(AppState::Loading), despawn_scene::<LoadingScene>);
+    // Without the intro the loading screen is entered before the manifest file is loaded, it shows once the cache is built
+    app.add_systems(Update, LoadingScene::spawn.run_if(resource_added::<AssetCache>.and(in_state(AppState::Loading))));
+    app.add_systems(Update, LoadingScene::finish.run_if(in_state(AppState::Loading).and(ui_assets_ready.or(ui_assets_failed))));
     app.add_systems(OnEnter(AppState::MainMenu), MainMenuScene::spawn).add_systems(OnExit(AppState::MainMenu), despawn_scene::<MainMenuScene>);
     app.add_systems(OnEnter(AppState::NewGame), NewGameScene::spawn).add_systems(OnExit(AppState::NewGame), despawn_scene::<NewGameScene>);
     app.add_systems(OnEnter(AppState::Settings), SettingsScene::spawn).add_systems(OnExit(AppState::Settings), despawn_scene::<SettingsScene>);
 
-    app.add_plugins((VFXPlugin, ShowcaseCameraPlugin, MoviePlugin));
+    app.add_systems(Update, return_to_menu.run_if((in_state(AppState::NewGame).or(in_state(AppState::Settings))).and(on_event::<UiBack>)));
+    app.add_systems(Update, MainMenuScene::continue_system.run_if(in_state(AppState::MainMenu).and(resource_exists::<SaveInfo>)));
+    app.add_systems(Update, save_scan_busy);
+
+    // Record the input with F11 when launched with --record, replay a recording by launching with BEVYPUNK_REPLAY set to its file.
+    // Both animate in fixed steps, so the recorder is only added for them.
+    let replay = std::env::var("BEVYPUNK_REPLAY").ok();
+    if args.record || replay.is_some() { app.add_plugins(UiRecorderPlugin { replay, exit_after_replay: true }); }
+    app.add_plugins((AssetCachePlugin, VFXPlugin, ShowcaseCameraPlugin, MoviePlugin, SavePlugin, WidgetsPlugin));
+
+    // Push the camera towards the clicked button while the old screen fades out
+    app.init_resource::<CameraTransition>();
 
     app.run()
 }
@@ -81,24 +101,24 @@ fn main() -> AppExit {
 
 /// This system is run in PreStartup. It locks some assets from being freed when not used.
 fn preload(mut commands: Commands, asset_server: Res<AssetServer>) {
-    // Spawn an entity with these assets, so that Bevy does not unload these assets when nobody is using them
-    commands.spawn(AssetLock { assets: vec![
-        asset_server.load_folder("fonts").untyped(),
-        asset_server.load_folder("images/ui").untyped()
-    ]});
-    // This is good to reduce poping-in of important assets, such as UI, fonts, etc.
+    // The UI images and fonts are held by the AssetCache resource, so we only lock the audio here.
+    // This is good to reduce poping-in of important assets.
     commands.spawn(AssetLock { assets: vec![
         asset_server.load::<AudioSource>("audio/intro.ogg").untyped(),
         asset_server.load::<AudioSource>("audio/main_menu.ogg").untyped(),
     ]});
 }
 
-/// This system spawns & setups the basic camera with cursor
-fn spawn_camera(mut commands: Commands, asset_server: Res<AssetServer>, mut atlas_layout: ResMut<Assets<TextureAtlasLayout>>) {
-    // Spawn the camera
+/// This system spawns & setups the basic camera
+fn spawn_camera(mut commands: Commands) {
     commands.spawn((
         Camera2d, Camera { hdr: true, clear_color: ClearColorConfig::Custom(Color::srgba(0.0, 0.0, 0.0, 0.0)), ..default() }, RenderLayers::from_layers(&[0, 1]), Bloom::OLD_SCHOOL, VFXBloomFlicker, UiSourceCamera::<0>, Transform::from_translation(Vec3::Z * 1000.0),
-    )).with_children(|cam| {
+    ));
+}
+
+/// This system spawns the cursor under the camera once the asset cache is built from the manifest
+fn spawn_cursor(mut commands: Commands, camera: Single<Entity, (With<Camera2d>, With<UiSourceCamera<0>>)>, assets: Res<AssetCache>, theme: Res<UiTheme>, mut atlas_layout: ResMut<Assets<TextureAtlasLayout>>) {
+    commands.entity(*camera).with_children(|cam| {
 
         // Spawn cursor
         cam.spawn ((
@@ -112,24 +132,49 @@ fn spawn_camera(mut commands: Commands, asset_server: Res<AssetServer>, mut atla
 
             // Change the sprite
             Sprite {
-                image: asset_server.load("images/ui/cursor.png"),
+                image: assets.cursor.clone(),
                 texture_atlas: Some(TextureAtlas {
                     layout: atlas_layout.add(TextureAtlasLayout::from_grid(UVec2::splat(80), 3, 1, None, None)),
                     index: 0,
                 }),
-                color: Color::BEVYPUNK_YELLOW.with_alpha(1.0),
+                color: theme.primary_hover.with_alpha(1.0),
                 anchor: Anchor::TopLeft,
                 ..default()
             },
+
+            // Leave a trail behind, the settings can turn it off
+            CursorTrail::new(),
         ));
 
     });
 }
 
+/// This system returns to the main menu when the gamepad back button is pressed.
+fn return_to_menu(mut events: EventReader<UiBack>, mut next: ResMut<NextState<AppState>>) {
+    events.clear();
+    next.set(AppState::MainMenu);
+}
+
+/// This system spins the cursor until the first save scan is done.
+fn save_scan_busy(save: Option<Res<SaveInfo>>, mut busy: ResMut<CursorBusy>) {
+    let scanning = save.is_none();
+    if busy.0 != scanning { busy.0 = scanning; }
+}
+
+/// Returns the name of the rumble setting shown on its switch
+fn rumble_name(settings: &GameSettings) -> &'static str {
+    if !settings.haptics { "Off" } else if settings.haptics_intensity < 1.0 { "Low" } else { "Full" }
+}
+
 /// This is a generic system that will despawn all entities with attached component S.
-fn despawn_scene<S: Component>(mut commands: Commands, query: Query<Entity, With<S>>) {
-    for entity in &query {
-        commands.entity(entity).despawn_recursive();
+/// UI trees fade out first, so the screens do not vanish at once.
+fn despawn_scene<S: Component>(mut commands: Commands, query: Query<(Entity, Has<UiLayoutRoot>), (With<S>, Without<DespawnWithAnimation>)>) {
+    for (entity, is_ui) in &query {
+        if is_ui {
+            commands.entity(entity).insert(DespawnWithAnimation::default());
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
     }
 }
 
@@ -153,16 +198,64 @@ impl IntroScene {
                 Movie::play(priority_assets.video.get("intro").unwrap().clone(), asset_server.load("audio/intro.ogg")).playback(MoviePlayback::Stop)
 
             // Add observer that will change the state once the movie ends
-            )).observe(|_: Trigger<MovieEnded>, mut next: ResMut<NextState<AppState>>| next.set(AppState::MainMenu) );
+            )).observe(|_: Trigger<MovieEnded>, mut next: ResMut<NextState<AppState>>| next.set(AppState::Loading) );
+        });
+    }
+}
+
+
+#[derive(Component)]
+struct LoadingScene;
+impl LoadingScene {
+    fn spawn(mut commands: Commands, assets: Res<AssetCache>, style: Res<UiStyle>) {
+        // Create UI
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            // Make the UI synchronized with camera viewport size
+            UiFetchFromCamera::<0>,
+            // A scene marker for later mass scene despawn, not UI related
+            LoadingScene
+        )).with_children(|ui| {
+
+            // Spawn the progress text, it will show up once the font itself is loaded
+            ui.spawn((
+                UiLayout::window().pos(Rl((50.0, 50.0))).anchor(Anchor::Center).pack(),
+                ThemedColor::from(ThemeRole::Text),
+                UiTextSize::from(Rh(4.0)),
+                Text2d::new(""),
+                LoadingIndicator::default(),
+                Typography::mono(&assets, &style).text_font(),
+            ));
         });
     }
+    /// This system is run once the asset cache is ready and advances to the main menu.
+    fn finish(mut next: ResMut<NextState<AppState>>) {
+        next.set(AppState::MainMenu);
+    }
 }
 
 
 #[derive(Component)]
 struct MainMenuScene;
+
+/// Marker for the continue button that reflects the detected saves
+#[derive(Component)]
+struct ContinueButton;
+
 impl MainMenuScene {
-    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    /// This system enables the continue button once a save slot was found and shows its chapter as a hint.
+    fn continue_system(save: Res<SaveInfo>, mut query: Query<&mut MainButton, With<ContinueButton>>) {
+        for mut button in &mut query {
+            let disabled = save.latest.is_none();
+            let hint = save.latest.as_ref().map(|latest| latest.chapter.clone()).unwrap_or_default();
+            // Compare first to not trigger change detection every frame
+            if button.disabled != disabled || button.hint != hint {
+                button.disabled = disabled;
+                button.hint = hint;
+            }
+        }
+    }
+    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, assets: Res<AssetCache>, audio: Res<Audio>) {
         // Start playing the music
         if !audio.is_playing_sound() {
             audio.play(asset_server.load("audio/main_menu.ogg")).looped().fade_in(AudioTween::new(Duration::new(2, 0), AudioEasing::OutPowf(2.0)));
@@ -182,7 +275,8 @@ impl MainMenuScene {
                 // You can name your entites for easier debug
                 Name::new("Background"),
                 UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
-                Sprite::from_image(asset_server.load("images/ui/background.png")),
+                // Drawn by a shader in the theme colors, the image is shown where the shader can not run
+                ShaderBackground::new(ThemeRole::Primary, ThemeRole::Accent).speed(0.6).fallback(assets.background.clone()),
             ));
 
             // Add the panel boundary
@@ -194,7 +288,7 @@ impl MainMenuScene {
                 ui.spawn((
                     Name::new("Panel"),
                     UiLayout::window().x(Rl(50.0)).anchor(Anchor::TopCenter).size(Rl(105.0)).pack(),
-                    Sprite::from_image(asset_server.load("images/ui/panel_menu.png")),
+                    Sprite::from_image(assets.panel_menu.clone()),
                 ));
 
                 // Spawn the logo boundary
@@ -206,121 +300,68 @@ impl MainMenuScene {
                     ui.spawn((
                         Name::new("Logo"),
                         UiLayout::solid().size((1240.0, 381.0)).pack(),
-                        Sprite::from_image(asset_server.load("images/ui/title.png")),
+                        Sprite::from_image(assets.title.clone()),
+                        // Dips now and then like a failing neon sign
+                        NeonFlicker::default(),
                     ));
                 });
 
                 // Spawn button boundary
                 ui.spawn((
                     UiLayout::window().pos(Rl((22.0, 33.0))).size(Rl((55.0, 34.0))).pack(),
+                    // The buttons slide in one after another
+                    EntranceAnimation::new(EntranceKind::SlideLeft, 0.06),
                 )).with_children(|ui| {
 
                     // Spawn buttons
                     let gap = 3.0;
                     let size = 14.0;
                     let mut offset = 0.0;
-                    for button in ["Continue", "New Game", "Load Game", "Settings", "Additional Content", "Credits", "Quit Game"] {
+                    for (button, key) in [
+                        ("Continue", "menu.continue"), ("New Game", "menu.new_game"), ("Load Game", "menu.load_game"), ("Settings", "menu.settings"),
+                        ("Additional Content", "menu.additional_content"), ("Credits", "menu.credits"), ("Quit Game", "menu.quit_game"),
+                    ] {
 
                         // Spawn the button
                         let mut button_entity = ui.spawn((
                             Name::new(button),
                             UiLayout::window().y(Rl(offset)).size(Rl((100.0, size))).pack(),
-                            OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                            // The button internals are built by the widget plugin, continue stays disabled until saves are found
+                            MainButton::new(button).disabled(button == "Continue"),
+                            // The label follows the language
+                            LocalizedText::new(key),
                         ));
-                        button_entity.with_children(|ui| {
-                            // Spawn the image
-                            ui.spawn((
-                                // You can define layouts for multiple states
-                                UiLayout::new(vec![
-                                    (UiBase::id(), UiLayout::window().full()),
-                                    (UiHover::id(), UiLayout::window().x(Rl(10.0)).full())
-                                ]),
-                                // Like this you can enable a state
-                                UiHover::new().forward_speed(20.0).backward_speed(4.0),
-                                // You can specify colors for multiple states
-                                UiColor::new(vec![
-                                    (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.15)),
-                                    (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
-                                ]),
-                                Sprite {
-                                    image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
-                                    // Here we enable sprite slicing
-                                    image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
-                                    ..default()
-                                },
-                                // Make sure it does not cover the bounding zone of parent
-                                PickingBehavior::IGNORE,
-                            )).with_children(|ui| {
-
-                                // Spawn the text
-                                ui.spawn((
-                                    // For text always use window layout to position it
-                                    UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
-                                    UiColor::new(vec![
-                                        (UiBase::id(), Color::BEVYPUNK_RED),
-                                        (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
-                                    ]),
-                                    UiHover::new().forward_speed(20.0).backward_speed(4.0),
-                                    // You can control the size of the text
-                                    UiTextSize::from(Rh(60.0)),
-                                    // You can attach text like this
-                                    Text2d::default(),
-                                    TextAnimator::new(button.to_ascii_uppercase()).function(decryption_animation).duration(1.0),
-                                    TextFont {
-                                        font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
-                                        font_size: 64.0,
-                                        ..default()
-                                    },
-                                    // Make sure it does not cover the bounding zone of parent
-                                    PickingBehavior::IGNORE,
-                                ));
-
-                                // Spawn the fluff
-                                ui.spawn((
-                                    // For text always use window layout to position it
-                                    UiLayout::window().pos(Rl((90.0, 50.0))).anchor(Anchor::CenterRight).pack(),
-                                    UiColor::new(vec![
-                                        (UiBase::id(), Color::BEVYPUNK_BLUE.with_alpha(0.2)),
-                                        (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
-                                    ]),
-                                    UiHover::new().forward_speed(20.0).backward_speed(4.0),
-                                    // You can control the size of the text
-                                    UiTextSize::from(Rh(60.0)),
-                                    // You can attach text like this
-                                    Text2d::new("<-"),
-                                    TextFont {
-                                        font: asset_server.load("fonts/rajdhani/Rajdhani-Bold.ttf"),
-                                        font_size: 64.0,
-                                        ..default()
-                                    },
-                                ));
-                            });
-
-                        // Enable the transition on hover
-                        }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>);
 
                         // Assign a functionality to the buttons
                         match button {
+                            "Continue" => {
+                                button_entity.insert(ContinueButton);
+                                button_entity.observe(|_: Trigger<MainButtonClicked>, save: Res<SaveInfo>| {
+                                    if let Some(latest) = &save.latest { info!("Continue from slot: {}", latest.slot); }
+                                });
+                            },
                             "New Game" => {
-                                button_entity.observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<AppState>>| {
+                                button_entity.observe(|_: Trigger<MainButtonClicked>, mut next: ResMut<NextState<AppState>>| {
                                     // Change the state to settings
                                     next.set(AppState::NewGame);
                                 });
                             },
                             "Settings" => {
-                                button_entity.observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<AppState>>| {
+                                button_entity.observe(|_: Trigger<MainButtonClicked>, mut next: ResMut<NextState<AppState>>| {
                                     // Change the state to settings
                                     next.set(AppState::Settings);
                                 });
                             },
                             "Quit Game" => {
-                                button_entity.observe(|_: Trigger<Pointer<Click>>, mut exit: EventWriter<AppExit>| {
+                                // Styled by the danger rule of the style file, with the danger look
+                                button_entity.insert((UiClass::new("danger"), MainButtonVariant::Danger));
+                                button_entity.observe(|_: Trigger<MainButtonClicked>, mut exit: EventWriter<AppExit>| {
                                     // Close the app
                                     exit.send(AppExit::Success);
                                 });
                             },
                             _ => {
-                                button_entity.observe(|c_trigger: Trigger<Pointer<Click>>, c_button: Query<NameOrEntity, With<UiLayout>>| {
+                                button_entity.observe(|c_trigger: Trigger<MainButtonClicked>, c_button: Query<NameOrEntity, With<UiLayout>>| {
                                     info!("Clicked: {}", c_button.get(c_trigger.entity()).unwrap());
                                 });
                             }
@@ -335,14 +376,14 @@ impl MainMenuScene {
                     // For text always use window layout to position it
                     UiLayout::window().pos(Rl((87.0, 80.4))).anchor(Anchor::CenterRight).pack(),
                     UiDepth::Add(5.0),
-                    UiColor::from(Color::BEVYPUNK_RED.with_alpha(0.40)),
+                    ThemedColor::alpha(ThemeRole::Text, 0.40),
                     // You can control the size of the text
                     UiTextSize::from(Rw(3.5)),
                     // You can attach text like this
                     Text2d::new(""),
                     TextAnimator::new("BEVY 0.15.3").function(typing_animation).duration(3.0),
                     TextFont {
-                        font: asset_server.load("fonts/rajdhani/Rajdhani-Bold.ttf"),
+                        font: assets.font_bold.clone(),
                         font_size: 48.0,
                         ..default()
                     },
@@ -353,14 +394,14 @@ impl MainMenuScene {
                     // For text always use window layout to position it
                     UiLayout::window().pos(Rl((19.0, 83.5))).anchor(Anchor::CenterLeft).pack(),
                     UiDepth::Add(5.0),
-                    UiColor::from(Color::BEVYPUNK_RED.with_alpha(0.40)),
+                    ThemedColor::alpha(ThemeRole::Text, 0.40),
                     // You can control the size of the text
                     UiTextSize::from(Rw(5.5)),
                     // You can attach text like this
                     Text2d::new(""),
                     TextAnimator::new("v0.3.0").function(typing_animation).duration(3.0),
                     TextFont {
-                        font: asset_server.load("fonts/rajdhani/Rajdhani-Bold.ttf"),
+                        font: assets.font_bold.clone(),
                         font_size: 48.0,
                         ..default()
                     },
@@ -371,14 +412,14 @@ impl MainMenuScene {
                     // For text always use window layout to position it
                     UiLayout::window().pos(Rl((19.0, 87.0))).anchor(Anchor::CenterLeft).pack(),
                     UiDepth::Add(5.0),
-                    UiColor::from(Color::BEVYPUNK_RED.with_alpha(0.20)),
+                    ThemedColor::alpha(ThemeRole::Text, 0.20),
                     // You can control the size of the text
                     UiTextSize::from(Rh(3.0)),
                     // You can attach text like this
                     Text2d::new(""),
                     AnimatedTextSlider::new(">>>"),
                     TextFont {
-                        font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                        font: assets.font_semibold.clone(),
                         font_size: 48.0,
                         ..default()
                     },
@@ -389,14 +430,14 @@ impl MainMenuScene {
                     // For text always use window layout to position it
                     UiLayout::window().pos(Rl((19.0, 10.0))).anchor(Anchor::CenterLeft).pack(),
                     UiDepth::Add(5.0),
-                    UiColor::from(Color::BEVYPUNK_RED.with_alpha(0.10)),
+                    ThemedColor::alpha(ThemeRole::Text, 0.10),
                     // You can control the size of the text
                     UiTextSize::from(Rh(3.0)),
                     // You can attach text like this
                     Text2d::new(">>>"),
                     AnimatedTextSlider::new(">>>").step("-").duration(0.1).len(35),
                     TextFont {
-                        font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                        font: assets.font_semibold.clone(),
                         font_size: 48.0,
                         ..default()
                     },
@@ -413,6 +454,8 @@ impl NewGameScene {
     fn spawn(
         mut commands: Commands,
         asset_server: Res<AssetServer>,
+        assets: Res<AssetCache>,
+        theme: Res<UiTheme>,
         mut images: ResMut<Assets<Image>>,
         mut materials: ResMut<Assets<StandardMaterial>>,
     ) {
@@ -443,7 +486,7 @@ impl NewGameScene {
             PointLight {
                 intensity: 10000.0,
                 shadows_enabled: false,
-                color: Color::BEVYPUNK_RED.with_luminance(1.6),
+                color: theme.primary.with_luminance(1.6),
                 ..default()
             },
             // A scene marker for later mass scene despawn, not UI related
@@ -463,7 +506,7 @@ impl NewGameScene {
             ui.spawn((
                 Name::new("Background"),
                 UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
-                Sprite::from_image(asset_server.load("images/ui/background.png")),
+                Sprite::from_image(assets.background.clone()),
                 UiDepth::Set(0.0),
             ));
 
@@ -484,6 +527,7 @@ impl NewGameScene {
                 Name::new("Return"),
                 UiLayout::window().pos(Rl((2.0, 4.0))).size(Rl((16.0, 8.0))).pack(),
                 OnHoverSetCursor::new(bevy::window::SystemCursorIcon::Pointer),
+                Focusable::new(),
             )).with_children(|ui| {
                 // Spawn the image
                 ui.spawn((
@@ -495,12 +539,12 @@ impl NewGameScene {
                     // Like this you can enable a state
                     UiHover::new().forward_speed(20.0).backward_speed(4.0),
                     // You can specify colors for multiple states
-                    UiColor::new(vec![
-                        (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.15)),
-                        (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
+                    ThemedColor::new(vec![
+                        (UiBase::id(), ThemeRole::Primary, 0.15),
+                        (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
                     ]),
                     Sprite {
-                        image: asset_server.load("images/ui/components/button_sliced_bottom_right.png"),
+                        image: assets.button_bottom_right.clone(),
                         // Here we enable sprite slicing
                         image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
                         ..default()
@@ -513,9 +557,9 @@ impl NewGameScene {
                     ui.spawn((
                         // For text always use window layout to position it
                         UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
-                        UiColor::new(vec![
-                            (UiBase::id(), Color::BEVYPUNK_RED),
-                            (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
+                        ThemedColor::new(vec![
+                            (UiBase::id(), ThemeRole::Text, 1.0),
+                            (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
                         ]),
                         UiHover::new().forward_speed(20.0).backward_speed(4.0),
                         // You can control the size of the text
@@ -523,7 +567,7 @@ impl NewGameScene {
                         // You can attach text like this
                         Text2d::new("Return"),
                         TextFont {
-                            font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                            font: assets.font_medium.clone(),
                             font_size: 64.0,
                             ..default()
                         },
@@ -534,7 +578,8 @@ impl NewGameScene {
 
             // Enable the transition on hover
             }).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>)
-            .observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<AppState>>| next.set(AppState::MainMenu) );
+            .observe(|_: Trigger<Pointer<Click>>, mut next: ResMut<NextState<AppState>>| next.set(AppState::MainMenu) )
+            .observe(|_: Trigger<FocusActivated>, mut next: ResMut<NextState<AppState>>| next.set(AppState::MainMenu) );
 
             // Spawn panel boundary
             ui.spawn((
@@ -550,13 +595,13 @@ impl NewGameScene {
                     ui.spawn((
                         // For text always use window layout to position it
                         UiLayout::window().pos(Rl((53., 8.))).anchor(Anchor::TopCenter).pack(),
-                        UiColor::from(Color::BEVYPUNK_RED),
+                        ThemedColor::from(ThemeRole::Text),
                         // You can control the size of the text
                         UiTextSize::from(Rh(5.0)),
                         // You can attach text like this
                         Text2d::new("New Character"),
                         TextFont {
-                            font: asset_server.load("fonts/rajdhani/Rajdhani-SemiBold.ttf"),
+                            font: assets.font_semibold.clone(),
                             font_size: 64.0,
                             ..default()
                         },
@@ -595,12 +640,12 @@ impl NewGameScene {
                                         // Like this you can enable a state
                                         UiHover::new().forward_speed(20.0).backward_speed(4.0),
                                         // You can specify colors for multiple states
-                                        UiColor::new(vec![
-                                            (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.15)),
-                                            (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
+                                        ThemedColor::new(vec![
+                                            (UiBase::id(), ThemeRole::Primary, 0.15),
+                                            (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
                                         ]),
                                         Sprite {
-                                            image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                                            image: assets.button.clone(),
                                             // Here we enable sprite slicing
                                             image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
                                             ..default()
@@ -613,9 +658,9 @@ impl NewGameScene {
                                         ui.spawn((
                                             // For text always use window layout to position it
                                             UiLayout::window().pos((Rh(40.0), Rl(50.0))).anchor(Anchor::CenterLeft).pack(),
-                                            UiColor::new(vec![
-                                                (UiBase::id(), Color::BEVYPUNK_RED),
-                                                (UiHover::id(), Color::BEVYPUNK_YELLOW.with_alpha(1.2))
+                                            ThemedColor::new(vec![
+                                                (UiBase::id(), ThemeRole::Text, 1.0),
+                                                (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
                                             ]),
                                             UiHover::new().forward_speed(20.0).backward_speed(4.0),
                                             // You can control the size of the text
@@ -623,7 +668,7 @@ impl NewGameScene {
                                             // You can attach text like this
                                             Text2d::new(array.0.to_uppercase()),
                                             TextFont {
-                                                font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                                font: assets.font_medium.clone(),
                                                 font_size: 64.0,
                                                 ..default()
                                             },
@@ -640,12 +685,12 @@ impl NewGameScene {
                                     ui.spawn((
                                         UiLayout::window().full().pack(),
                                         UiHover::new().instant(true),
-                                        UiColor::new(vec![
-                                            (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.15)),
-                                            (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                                        ThemedColor::new(vec![
+                                            (UiBase::id(), ThemeRole::Primary, 0.15),
+                                            (UiHover::id(), ThemeRole::Accent, 1.2)
                                         ]),
                                         Sprite {
-                                            image: asset_server.load("images/ui/components/button_sliced_bottom_left.png"),
+                                            image: assets.button_bottom_left.clone(),
                                             image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
                                             ..default()
                                         },
@@ -654,11 +699,11 @@ impl NewGameScene {
                                         ui.spawn((
                                             Name::new("Chevron Left"),
                                             UiLayout::window().pos(Rl((50.0, 50.0))).anchor(Anchor::Center).size(Rh(65.0)).pack(),
-                                            Sprite::from_image(asset_server.load("images/ui/components/chevron_left.png")),
+                                            Sprite::from_image(assets.chevron_left.clone()),
                                             UiHover::new().forward_speed(20.0).backward_speed(20.0).curve(|v| v.round()),
-                                            UiColor::new(vec![
-                                                (UiBase::id(), Color::BEVYPUNK_RED),
-                                                (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                                            ThemedColor::new(vec![
+                                                (UiBase::id(), ThemeRole::Text, 1.0),
+                                                (UiHover::id(), ThemeRole::Accent, 1.2)
                                             ]),
                                         ));
                                     });
@@ -671,12 +716,12 @@ impl NewGameScene {
                                     ui.spawn((
                                         UiLayout::window().full().pack(),
                                         UiHover::new().instant(true),
-                                        UiColor::new(vec![
-                                            (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.15)),
-                                            (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                                        ThemedColor::new(vec![
+                                            (UiBase::id(), ThemeRole::Primary, 0.15),
+                                            (UiHover::id(), ThemeRole::Accent, 1.2)
                                         ]),
                                         Sprite {
-                                            image: asset_server.load("images/ui/components/button_sliced_bottom_right.png"),
+                                            image: assets.button_bottom_right.clone(),
                                             image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
                                             ..default()
                                         },
@@ -685,11 +730,11 @@ impl NewGameScene {
                                         ui.spawn((
                                             Name::new("Chevron Right"),
                                             UiLayout::window().pos(Rl((50.0, 50.0))).anchor(Anchor::Center).size(Rh(65.0)).pack(),
-                                            Sprite::from_image(asset_server.load("images/ui/components/chevron_right.png")),
+                                            Sprite::from_image(assets.chevron_right.clone()),
                                             UiHover::new().forward_speed(20.0).backward_speed(20.0).curve(|v| v.round()),
-                                            UiColor::new(vec![
-                                                (UiBase::id(), Color::BEVYPUNK_RED),
-                                                (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                                            ThemedColor::new(vec![
+                                                (UiBase::id(), ThemeRole::Text, 1.0),
+                                                (UiHover::id(), ThemeRole::Accent, 1.2)
                                             ]),
                                         ));
                                     });
@@ -739,7 +784,17 @@ impl NewGameScene {
 #[derive(Component)]
 struct SettingsScene;
 impl SettingsScene {
-    fn spawn(mut commands: Commands, asset_server: Res<AssetServer>, mut images: ResMut<Assets<Image>>) {
+    #[allow(clippy::too_many_arguments)]
+    fn spawn(
+        mut commands: Commands,
+        assets: Res<AssetCache>,
+        manifest: Res<AssetManifest>,
+        theme: Res<ActiveTheme>,
+        locale: Res<ActiveLocale>,
+        crossfade: Res<ThemeCrossfade>,
+        settings: Res<GameSettings>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
 
         // Create the transparent render texture
         let image_handle = images.add(Image::clear_render_texture());
@@ -766,7 +821,7 @@ impl SettingsScene {
             ui.spawn((
                 Name::new("Background"),
                 UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
-                Sprite::from_image(asset_server.load("images/ui/background.png")),
+                Sprite::from_image(assets.background.clone()),
             ));
 
             // Spawn the settings content
@@ -783,11 +838,11 @@ impl SettingsScene {
                     ui.spawn((
                         Name::new("Chevron Left"),
                         UiLayout::window().pos(Rl((5.0, 50.0))).anchor(Anchor::Center).size(Rh(35.0)).pack(),
-                        Sprite::from_image(asset_server.load("images/ui/components/chevron_left.png")),
+                        Sprite::from_image(assets.chevron_left.clone()),
                         UiHover::new().instant(true),
-                        UiColor::new(vec![
-                            (UiBase::id(), Color::BEVYPUNK_RED),
-                            (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                        ThemedColor::new(vec![
+                            (UiBase::id(), ThemeRole::Primary, 1.0),
+                            (UiHover::id(), ThemeRole::Accent, 1.2)
                         ]),
                     )).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>);
 
@@ -795,11 +850,11 @@ impl SettingsScene {
                     ui.spawn((
                         Name::new("Chevron Right"),
                         UiLayout::window().pos(Rl((95.0, 50.0))).anchor(Anchor::Center).size(Rh(35.0)).pack(),
-                        Sprite::from_image(asset_server.load("images/ui/components/chevron_right.png")),
+                        Sprite::from_image(assets.chevron_right.clone()),
                         UiHover::new().instant(true),
-                        UiColor::new(vec![
-                            (UiBase::id(), Color::BEVYPUNK_RED),
-                            (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                        ThemedColor::new(vec![
+                            (UiBase::id(), ThemeRole::Primary, 1.0),
+                            (UiHover::id(), ThemeRole::Accent, 1.2)
                         ]),
                     )).observe(hover_set::<Pointer<Over>, true>).observe(hover_set::<Pointer<Out>, false>);
 
@@ -822,12 +877,12 @@ impl SettingsScene {
                                 ui.spawn((
                                     UiLayout::window().full().y(Rl(10.0)).height(Rl(80.0)).pack(),
                                     UiHover::new().forward_speed(20.0).backward_speed(5.0),
-                                    UiColor::new(vec![
-                                        (UiBase::id(), Color::BEVYPUNK_RED.with_alpha(0.0)),
-                                        (UiHover::id(), Color::BEVYPUNK_RED.with_alpha(0.4))
+                                    ThemedColor::new(vec![
+                                        (UiBase::id(), ThemeRole::Primary, 0.0),
+                                        (UiHover::id(), ThemeRole::Primary, 0.4)
                                     ]),
                                     Sprite {
-                                        image: asset_server.load("images/ui/components/button_symetric_sliced.png"),
+                                        image: assets.button.clone(),
                                         image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
                                         ..default()
                                     },
@@ -837,15 +892,15 @@ impl SettingsScene {
                                     // Spawn the text
                                     ui.spawn((
                                         UiLayout::window().pos(Rl(50.0)).anchor(Anchor::Center).pack(),
-                                        UiColor::new(vec![
-                                            (UiBase::id(), Color::BEVYPUNK_RED),
-                                            (UiHover::id(), Color::BEVYPUNK_BLUE.with_alpha(1.2))
+                                        ThemedColor::new(vec![
+                                            (UiBase::id(), ThemeRole::Text, 1.0),
+                                            (UiHover::id(), ThemeRole::Accent, 1.2)
                                         ]),
                                         UiHover::new().instant(true),
                                         UiTextSize::from(Rh(50.0)),
                                         Text2d::new(category.to_ascii_uppercase()),
                                         TextFont {
-                                            font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                                            font: assets.font_medium.clone(),
                                             font_size: 64.0,
                                             ..default()
                                         },
@@ -869,6 +924,83 @@ impl SettingsScene {
                 ));
 
             });
+
+            // Spawn the skin switch, cycles through all themes with image variants
+            ui.spawn((
+                Name::new("Skin"),
+                UiLayout::window().pos(Rl((2.0, 90.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Skin").hint(&theme.0),
+            )).observe(|trigger: Trigger<MainButtonClicked>, assets: Res<AssetCache>, mut theme: ResMut<ActiveTheme>, mut query: Query<&mut MainButton>| {
+                let themes = assets.themes();
+                let index = themes.iter().position(|name| *name == theme.0).map(|i| (i + 1) % themes.len()).unwrap_or(0);
+                theme.0 = themes[index].clone();
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = theme.0.clone(); }
+            });
+
+            // Spawn the palette switch, toggles between the dark and light palette
+            ui.spawn((
+                Name::new("Palette"),
+                UiLayout::window().pos(Rl((24.0, 90.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Palette").hint(crossfade.current.name()),
+            )).observe(|trigger: Trigger<MainButtonClicked>, crossfade: Res<ThemeCrossfade>, mut events: EventWriter<SetTheme>, mut query: Query<&mut MainButton>| {
+                let id = crossfade.current.toggled();
+                events.send(SetTheme(id));
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = id.name().to_string(); }
+            });
+
+            // Spawn the color vision switch, cycles through the accessible presets
+            ui.spawn((
+                Name::new("Colors"),
+                UiLayout::window().pos(Rl((46.0, 90.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Colors").hint(crossfade.palette.name()),
+            )).observe(|trigger: Trigger<MainButtonClicked>, crossfade: Res<ThemeCrossfade>, mut events: EventWriter<SetPalette>, mut query: Query<&mut MainButton>| {
+                let preset = crossfade.palette.next();
+                events.send(SetPalette(preset));
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = preset.name().to_string(); }
+            });
+
+            // Spawn the cursor trail switch
+            ui.spawn((
+                Name::new("Trail"),
+                UiLayout::window().pos(Rl((68.0, 90.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Trail").hint(if settings.cursor_trail { "On" } else { "Off" }),
+            )).observe(|trigger: Trigger<MainButtonClicked>, mut settings: ResMut<GameSettings>, mut query: Query<&mut MainButton>| {
+                settings.cursor_trail = !settings.cursor_trail;
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = if settings.cursor_trail { "On" } else { "Off" }.to_string(); }
+            });
+
+            // Spawn the motion switch, reduced motion turns the decorative animations off
+            ui.spawn((
+                Name::new("Motion"),
+                UiLayout::window().pos(Rl((24.0, 83.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Motion").hint(if settings.reduced_motion { "Reduced" } else { "Full" }),
+            )).observe(|trigger: Trigger<MainButtonClicked>, mut settings: ResMut<GameSettings>, mut query: Query<&mut MainButton>| {
+                settings.reduced_motion = !settings.reduced_motion;
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = if settings.reduced_motion { "Reduced" } else { "Full" }.to_string(); }
+            });
+
+            // Spawn the language dropdown, its list shows the languages of the manifest
+            ui.spawn((
+                Name::new("Language"),
+                UiLayout::window().pos(Rl(LanguageDropdown::POSITION)).size(Rl(LanguageDropdown::SIZE)).pack(),
+                MainButton::new("Language").hint(manifest.locales.get(&locale.0).map(|definition| definition.name.as_str()).unwrap_or(&locale.0)),
+                LocalizedText::new("settings.language"),
+                LanguageDropdown,
+            )).observe(LanguageDropdown::toggle);
+
+            // Spawn the rumble switch, cycles through off, low and full intensity
+            ui.spawn((
+                Name::new("Rumble"),
+                UiLayout::window().pos(Rl((68.0, 83.0))).size(Rl((20.0, 6.0))).pack(),
+                MainButton::new("Rumble").hint(rumble_name(&settings)),
+            )).observe(|trigger: Trigger<MainButtonClicked>, mut settings: ResMut<GameSettings>, mut query: Query<&mut MainButton>| {
+                (settings.haptics, settings.haptics_intensity) = match (settings.haptics, settings.haptics_intensity < 1.0) {
+                    (false, _) => (true, 0.5),
+                    (true, true) => (true, 1.0),
+                    (true, false) => (false, settings.haptics_intensity),
+                };
+                if let Ok(mut button) = query.get_mut(trigger.entity()) { button.hint = rumble_name(&settings).to_string(); }
+            });
         });
 
         // The Bevy UI nodes must be here to work
@@ -890,7 +1022,7 @@ impl SettingsScene {
                 Text::new("This is a Bevy UI"),
                 TextFont {
                     font_size: 64.0,
-                    font: asset_server.load("fonts/rajdhani/Rajdhani-Medium.ttf"),
+                    font: assets.font_medium.clone(),
                     ..default()
                 },
                 TextColor::WHITE,
@@ -899,3 +1031,70 @@ impl SettingsScene {
 
     }
 }
+
+/// The language setting of the settings screen, clicking it opens the list of the manifest languages above it
+#[derive(Component)]
+struct LanguageDropdown;
+
+/// The open list of the [`LanguageDropdown`]
+#[derive(Component)]
+struct LanguageList;
+
+/// An entry of the [`LanguageList`] with the key of its locale
+#[derive(Component)]
+struct LanguageOption(String);
+
+impl LanguageDropdown {
+    /// Where the dropdown sits on the settings screen, the list opens upwards from there
+    const POSITION: (f32, f32) = (46.0, 83.0);
+    /// The size of the dropdown and of each entry of the list
+    const SIZE: (f32, f32) = (20.0, 6.0);
+    /// This observer opens the list of the languages, or closes it when it is open.
+    fn toggle(
+        trigger: Trigger<MainButtonClicked>,
+        mut commands: Commands,
+        manifest: Res<AssetManifest>,
+        locale: Res<ActiveLocale>,
+        parents: Query<&Parent>,
+        lists: Query<Entity, With<LanguageList>>,
+    ) {
+        if !lists.is_empty() {
+            for list in &lists { commands.entity(list).despawn_recursive(); }
+            return;
+        }
+        let Ok(root) = parents.get(trigger.entity()) else { return; };
+        let ((x, y), (width, height)) = (LanguageDropdown::POSITION, LanguageDropdown::SIZE);
+        let count = manifest.locales.len() as f32;
+        commands.entity(root.get()).with_children(|ui| {
+            ui.spawn((
+                Name::new("Language List"),
+                UiLayout::window().pos(Rl((x, y - height * count))).size(Rl((width, height * count))).pack(),
+                LanguageList,
+            )).with_children(|ui| {
+                for (i, (key, definition)) in manifest.locales.iter().enumerate() {
+                    ui.spawn((
+                        Name::new(definition.name.clone()),
+                        UiLayout::window().y(Rl(100.0 / count * i as f32)).size(Rl((100.0, 100.0 / count))).pack(),
+                        MainButton::new(&definition.name).hint(if *key == locale.0 { "Current" } else { "" }),
+                        LanguageOption(key.clone()),
+                    )).observe(LanguageDropdown::select);
+                }
+            });
+        });
+    }
+    /// This observer switches to the language of the clicked entry and closes the list.
+    fn select(
+        trigger: Trigger<MainButtonClicked>,
+        mut commands: Commands,
+        manifest: Res<AssetManifest>,
+        options: Query<&LanguageOption>,
+        lists: Query<Entity, With<LanguageList>>,
+        mut dropdowns: Query<&mut MainButton, With<LanguageDropdown>>,
+        mut events: EventWriter<SetLanguage>,
+    ) {
+        let Ok(option) = options.get(trigger.entity()) else { return; };
+        events.send(SetLanguage(option.0.clone()));
+        if let (Ok(mut button), Some(definition)) = (dropdowns.get_single_mut(), manifest.locales.get(&option.0)) { button.hint = definition.name.clone(); }
+        for list in &lists { commands.entity(list).despawn_recursive(); }
+    }
+}
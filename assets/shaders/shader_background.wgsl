@@ -0,0 +1,51 @@
+// Flowing neon lines over drifting noise, the procedural menu background
+
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+@group(2) @binding(0) var<uniform> color_a: vec4<f32>;
+@group(2) @binding(1) var<uniform> color_b: vec4<f32>;
+// x is the animation time, y the aspect ratio of the quad
+@group(2) @binding(2) var<uniform> params: vec4<f32>;
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(127.1, 311.7))) * 43758.5453);
+}
+
+fn noise(p: vec2<f32>) -> f32 {
+    let i = floor(p);
+    let f = fract(p);
+    let u = f * f * (3.0 - 2.0 * f);
+    return mix(
+        mix(hash(i), hash(i + vec2<f32>(1.0, 0.0)), u.x),
+        mix(hash(i + vec2<f32>(0.0, 1.0)), hash(i + vec2<f32>(1.0, 1.0)), u.x),
+        u.y,
+    );
+}
+
+fn fbm(p: vec2<f32>) -> f32 {
+    var value = 0.0;
+    var amplitude = 0.5;
+    var point = p;
+    for (var i = 0; i < 4; i++) {
+        value += amplitude * noise(point);
+        point *= 2.0;
+        amplitude *= 0.5;
+    }
+    return value;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let time = params.x;
+    let uv = vec2<f32>(in.uv.x * params.y, in.uv.y);
+
+    // The noise bends the lines and lights the haze behind them
+    let flow = fbm(uv * 2.5 + vec2<f32>(time * 0.08, time * 0.03));
+    let wave = sin((uv.y + flow * 0.4) * 24.0 - time * 1.2);
+    let line = smoothstep(0.96, 1.0, wave);
+    let glow = smoothstep(0.6, 1.0, wave) * 0.25;
+
+    let haze = color_b.rgb * (0.05 + 0.2 * flow);
+    let color = haze + color_a.rgb * (line + glow) * (0.4 + 0.6 * flow);
+    return vec4<f32>(color, 1.0);
+}
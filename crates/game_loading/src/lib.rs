@@ -1,6 +1,14 @@
-use bevy::{prelude::*, utils::HashMap};
+use std::collections::BTreeMap;
+
+use bevy::{asset::{io::Reader, AssetLoader, LoadContext, LoadState, RenderAssetUsages}, prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}, utils::{HashMap, HashSet}};
+use bevy_kira_audio::AudioSource;
+use bevy_lunex::*;
+use serde::Deserialize;
 use vleue_kinetoscope::*;
 
+mod localization;
+pub use localization::*;
+
 /// This struct can be spawned to hold handles you wish not
 /// to deallocate when all entities are despawned which use them.
 #[derive(Component)]
@@ -14,4 +22,790 @@ pub struct AssetLock {
 #[derive(Resource, Default)]
 pub struct PriorityAssets {
     pub video: HashMap<String, Handle<AnimatedImage>>,
-}
\ No newline at end of file
+}
+
+
+/// Logical names of the user interface assets mapped to their paths.
+/// The default one is loaded from `assets/ui.manifest.ron` and applied again when the file is edited,
+/// insert your own before [`AssetCachePlugin`] to override it.
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct AssetManifest {
+    /// Image keys and their paths
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+    /// Font keys and their paths
+    #[serde(default)]
+    pub fonts: BTreeMap<String, String>,
+    /// Sound keys and their paths
+    #[serde(default)]
+    pub sounds: BTreeMap<String, String>,
+    /// Optional sprite sheet layouts for images under the same key
+    #[serde(default)]
+    pub atlases: BTreeMap<String, AtlasDefinition>,
+    /// Named sets of images overriding the default ones when the [`ActiveTheme`] matches
+    #[serde(default)]
+    pub variants: BTreeMap<String, BTreeMap<String, String>>,
+    /// Languages the game can be displayed in and the fonts covering their scripts
+    #[serde(default)]
+    pub locales: BTreeMap<String, LocaleDefinition>,
+    /// Custom cursor sprites for the cursor icons requested by the widgets
+    #[serde(default)]
+    pub cursors: BTreeMap<String, CursorDefinition>,
+}
+
+/// A cursor sprite of the [`AssetManifest`]
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct CursorDefinition {
+    /// Key of the image under `images`
+    pub image: String,
+    /// The pixel of the image that points, from the top left corner
+    #[serde(default)]
+    pub hotspot: (f32, f32),
+}
+
+/// Grid layout of a sprite sheet defined in the [`AssetManifest`]
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct AtlasDefinition {
+    /// Size of a single frame in pixels
+    pub tile_size: (u32, u32),
+    /// Number of frame columns
+    pub columns: u32,
+    /// Number of frame rows
+    pub rows: u32,
+}
+
+/// A language entry of the [`AssetManifest`]
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub struct LocaleDefinition {
+    /// The name of the language in that language
+    pub name: String,
+    /// Font with glyphs for the script of the language, the default fonts are used if not set
+    #[serde(default)]
+    pub font: Option<String>,
+    /// The `.strings.ron` file with the translated strings
+    #[serde(default)]
+    pub strings: Option<String>,
+    /// If the language is written right to left
+    #[serde(default)]
+    pub rtl: bool,
+}
+impl AssetManifest {
+    /// The manifest file in the assets
+    const PATH: &str = "ui.manifest.ron";
+    /// Parses the manifest from a RON string
+    pub fn parse(source: &str) -> Result<Self, AssetManifestError> {
+        ron::from_str(source).map_err(|error| AssetManifestError::Parse(error.to_string()))
+    }
+    /// This system starts loading the manifest file unless the app inserted its own. The manifest stays empty
+    /// until the file is loaded, the cache is built from it then.
+    fn load_system(mut commands: Commands, asset_server: Res<AssetServer>, manifest: Option<Res<AssetManifest>>) {
+        if manifest.is_some() { return; }
+        commands.insert_resource(AssetManifest::default());
+        commands.insert_resource(AssetManifestHandle(asset_server.load(AssetManifest::PATH)));
+    }
+    /// This system applies the manifest file once it is loaded and each time it is edited on disk, and builds the cache from it.
+    #[allow(clippy::too_many_arguments)]
+    fn apply_system(
+        mut commands: Commands,
+        mut events: EventReader<AssetEvent<AssetManifest>>,
+        mut failures: EventReader<AssetLoadFailedEvent<AssetManifest>>,
+        handle: Res<AssetManifestHandle>,
+        manifests: Res<Assets<AssetManifest>>,
+        mut manifest: ResMut<AssetManifest>,
+        cache: Option<Res<AssetCache>>,
+        asset_server: Res<AssetServer>,
+        mut images: ResMut<Assets<Image>>,
+        mut fonts: ResMut<Assets<Font>>,
+        mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+        mut theme: ResMut<ActiveTheme>,
+        state: Res<State<UiAssetsState>>,
+        mut next: ResMut<NextState<UiAssetsState>>,
+    ) {
+        let reloaded = events.read().any(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id == handle.0.id(),
+            _ => false,
+        });
+        let mut failed = false;
+        for failure in failures.read().filter(|failure| failure.id == handle.0.id()) {
+            error!("UI manifest could not be loaded: {}", failure.error);
+            failed = true;
+        }
+        let loaded = match manifests.get(&handle.0) {
+            Some(loaded) if reloaded => loaded.clone(),
+            // An unreadable manifest leaves every key missing, so the cache falls back to the placeholders
+            _ if failed && cache.is_none() => AssetManifest::default(),
+            _ => return,
+        };
+        if cache.is_some() && *manifest == loaded { return; }
+        info!("Applied the UI manifest");
+        *manifest = loaded;
+        let built = AssetCache::build(&manifest, &asset_server, &mut images, &mut fonts, &mut layouts, &mut next);
+        // A fixed manifest loads again, the sprites of the previous cache follow their keys to the new paths
+        if built.failed.is_empty() && *state.get() == UiAssetsState::Failed { next.set(UiAssetsState::Loading); }
+        if cache.is_none() { commands.insert_resource(AssetCacheProgress { failed: built.failed.clone(), ..default() }); }
+        commands.insert_resource(built);
+        theme.set_changed();
+    }
+}
+
+/// Errors produced when the [`AssetManifest`] can not satisfy the [`AssetCache`]
+#[derive(Clone, PartialEq, Debug)]
+pub enum AssetManifestError {
+    /// The manifest file could not be read
+    Io(String),
+    /// The manifest is not valid RON
+    Parse(String),
+    /// The manifest does not define an image with this key
+    MissingImage(String),
+    /// The manifest does not define a font with this key
+    MissingFont(String),
+    /// The manifest does not define a sound with this key
+    MissingSound(String),
+}
+impl std::fmt::Display for AssetManifestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetManifestError::Io(error) => write!(f, "UI manifest could not be read: {error}"),
+            AssetManifestError::Parse(error) => write!(f, "UI manifest could not be parsed: {error}"),
+            AssetManifestError::MissingImage(key) => write!(f, "UI manifest does not define an image named '{key}'"),
+            AssetManifestError::MissingFont(key) => write!(f, "UI manifest does not define a font named '{key}'"),
+            AssetManifestError::MissingSound(key) => write!(f, "UI manifest does not define a sound named '{key}'"),
+        }
+    }
+}
+impl std::error::Error for AssetManifestError {}
+
+/// Handle to the watched manifest file
+#[derive(Resource)]
+struct AssetManifestHandle(Handle<AssetManifest>);
+
+/// Loads [`AssetManifest`] from RON files
+#[derive(Default)]
+struct AssetManifestLoader;
+impl AssetLoader for AssetManifestLoader {
+    type Asset = AssetManifest;
+    type Settings = ();
+    type Error = AssetManifestError;
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), _context: &mut LoadContext<'_>) -> Result<AssetManifest, AssetManifestError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(|error| AssetManifestError::Io(error.to_string()))?;
+        AssetManifest::parse(&String::from_utf8_lossy(&bytes))
+    }
+    fn extensions(&self) -> &[&str] {
+        &["manifest.ron"]
+    }
+}
+
+
+/// Handles to all assets used by the user interface. The loads are started
+/// in PreStartup, use [`ui_assets_ready`] before assuming they are usable.
+#[derive(Resource, Reflect, Clone, Debug)]
+#[reflect(Resource)]
+pub struct AssetCache {
+    pub background: Handle<Image>,
+    pub panel_menu: Handle<Image>,
+    pub title: Handle<Image>,
+    pub cursor: Handle<Image>,
+    pub button: Handle<Image>,
+    pub button_bottom_left: Handle<Image>,
+    pub button_bottom_right: Handle<Image>,
+    pub chevron_left: Handle<Image>,
+    pub chevron_right: Handle<Image>,
+    pub font_light: Handle<Font>,
+    pub font_medium: Handle<Font>,
+    pub font_semibold: Handle<Font>,
+    pub font_bold: Handle<Font>,
+    pub font_mono: Handle<Font>,
+    pub hover_sfx: Handle<AudioSource>,
+    pub click_sfx: Handle<AudioSource>,
+    pub error_sfx: Handle<AudioSource>,
+    pub open_sfx: Handle<AudioSource>,
+    pub close_sfx: Handle<AudioSource>,
+    /// Sprite sheet layout of the button image, if it has one
+    pub button_atlas: Option<Handle<TextureAtlasLayout>>,
+    images: HashMap<String, Handle<Image>>,
+    fonts: HashMap<String, Handle<Font>>,
+    sounds: HashMap<String, Handle<AudioSource>>,
+    variants: HashMap<String, HashMap<String, Handle<Image>>>,
+    locale_paths: HashMap<String, String>,
+    locale_fonts: HashMap<String, Handle<Font>>,
+    cursors: HashMap<String, (Handle<Image>, Vec2)>,
+    failed: Vec<String>,
+}
+impl AssetCache {
+    /// Starts loading all assets defined in the manifest. Fails if any of the required keys is missing.
+    pub fn from_manifest(manifest: &AssetManifest, asset_server: &AssetServer, layouts: &mut Assets<TextureAtlasLayout>) -> Result<Self, AssetManifestError> {
+        let images: HashMap<String, Handle<Image>> = manifest.images.iter().map(|(key, path)| (key.clone(), asset_server.load(path.clone()))).collect();
+        let fonts: HashMap<String, Handle<Font>> = manifest.fonts.iter().map(|(key, path)| (key.clone(), asset_server.load(path.clone()))).collect();
+        let sounds: HashMap<String, Handle<AudioSource>> = manifest.sounds.iter().map(|(key, path)| (key.clone(), asset_server.load(path.clone()))).collect();
+        let image = |key: &str| images.get(key).cloned().ok_or_else(|| AssetManifestError::MissingImage(key.to_string()));
+        let font = |key: &str| fonts.get(key).cloned().ok_or_else(|| AssetManifestError::MissingFont(key.to_string()));
+        let sound = |key: &str| sounds.get(key).cloned().ok_or_else(|| AssetManifestError::MissingSound(key.to_string()));
+        let variants = manifest.variants.iter().map(|(theme, images)| {
+            (theme.clone(), images.iter().map(|(key, path)| (key.clone(), asset_server.load(path.clone()))).collect())
+        }).collect();
+        let cursors = manifest.cursors.iter().map(|(icon, cursor)| {
+            Ok((icon.clone(), (image(&cursor.image)?, Vec2::from(cursor.hotspot))))
+        }).collect::<Result<_, AssetManifestError>>()?;
+
+        Ok(Self {
+            background: image("background")?,
+            panel_menu: image("panel_menu")?,
+            title: image("title")?,
+            cursor: image("cursor")?,
+            button: image("button")?,
+            button_bottom_left: image("button_bottom_left")?,
+            button_bottom_right: image("button_bottom_right")?,
+            chevron_left: image("chevron_left")?,
+            chevron_right: image("chevron_right")?,
+            font_light: font("font_light")?,
+            font_medium: font("font_medium")?,
+            font_semibold: font("font_semibold")?,
+            font_bold: font("font_bold")?,
+            font_mono: font("font_mono")?,
+            hover_sfx: sound("hover_sfx")?,
+            click_sfx: sound("click_sfx")?,
+            error_sfx: sound("error_sfx")?,
+            open_sfx: sound("open_sfx")?,
+            close_sfx: sound("close_sfx")?,
+            button_atlas: manifest.atlases.get("button").map(|atlas| layouts.add(TextureAtlasLayout::from_grid(UVec2::from(atlas.tile_size), atlas.columns, atlas.rows, None, None))),
+            images,
+            fonts,
+            sounds,
+            variants,
+            locale_paths: manifest.locales.iter().filter_map(|(locale, definition)| Some((locale.clone(), definition.font.clone()?))).collect(),
+            locale_fonts: HashMap::new(),
+            cursors,
+            failed: Vec::new(),
+        })
+    }
+    /// Creates the cache with every image and font replaced by a placeholder and silent sounds,
+    /// for tests and tools running without the asset files
+    pub fn placeholder(images: &mut Assets<Image>, fonts: &mut Assets<Font>) -> Self {
+        let AssetFallback { image, font } = AssetFallback::new(images, fonts);
+        Self {
+            background: image.clone(),
+            panel_menu: image.clone(),
+            title: image.clone(),
+            cursor: image.clone(),
+            button: image.clone(),
+            button_bottom_left: image.clone(),
+            button_bottom_right: image.clone(),
+            chevron_left: image.clone(),
+            chevron_right: image,
+            font_light: font.clone(),
+            font_medium: font.clone(),
+            font_semibold: font.clone(),
+            font_bold: font.clone(),
+            font_mono: font,
+            hover_sfx: Handle::default(),
+            click_sfx: Handle::default(),
+            error_sfx: Handle::default(),
+            open_sfx: Handle::default(),
+            close_sfx: Handle::default(),
+            button_atlas: None,
+            images: HashMap::new(),
+            fonts: HashMap::new(),
+            sounds: HashMap::new(),
+            variants: HashMap::new(),
+            locale_paths: HashMap::new(),
+            locale_fonts: HashMap::new(),
+            cursors: HashMap::new(),
+            failed: Vec::new(),
+        }
+    }
+    /// Returns the names of all themes with image variants, including the default one
+    pub fn themes(&self) -> Vec<String> {
+        let mut themes: Vec<String> = std::iter::once(ActiveTheme::default().0).chain(self.variants.keys().cloned()).collect();
+        themes.sort();
+        themes.dedup();
+        themes
+    }
+    /// Returns the image under this key for the provided theme, falling back to the default one
+    pub fn themed_image(&self, key: &str, theme: &str) -> Result<Handle<Image>, AssetManifestError> {
+        match self.variants.get(theme).and_then(|variant| variant.get(key)) {
+            Some(handle) => Ok(handle.clone()),
+            None => self.image(key),
+        }
+    }
+    /// Returns the key of the cached image with this id in any theme
+    pub fn image_key(&self, id: AssetId<Image>) -> Option<&str> {
+        self.images.iter().chain(self.variants.values().flatten())
+            .find(|(_, handle)| handle.id() == id)
+            .map(|(key, _)| key.as_str())
+    }
+    /// Points all named image handles to the variants of the provided theme
+    fn apply_theme(&mut self, theme: &str) {
+        let resolved: Vec<Handle<Image>> = NAMED_IMAGES.iter().map(|key| self.themed_image(key, theme).unwrap_or_default()).collect();
+        for (handle, themed) in self.named_images_mut().into_iter().zip(resolved) { *handle = themed; }
+    }
+    /// Returns the font of this locale if it has one and it finished loading
+    pub fn locale_font(&self, locale: &str, asset_server: &AssetServer) -> Option<Handle<Font>> {
+        self.locale_fonts.get(locale).filter(|handle| asset_server.is_loaded(handle.id())).cloned()
+    }
+    /// Returns the cursor sprite and its hotspot for the icon name, like `pointer`
+    pub fn cursor_sprite(&self, icon: &str) -> Option<(Handle<Image>, Vec2)> {
+        self.cursors.get(icon).cloned()
+    }
+    /// Returns the icon names with a custom cursor sprite
+    pub fn cursor_icons(&self) -> impl Iterator<Item = &str> {
+        self.cursors.keys().map(String::as_str)
+    }
+    /// Returns the image defined in the manifest under this key
+    pub fn image(&self, key: &str) -> Result<Handle<Image>, AssetManifestError> {
+        self.images.get(key).cloned().ok_or_else(|| AssetManifestError::MissingImage(key.to_string()))
+    }
+    /// Returns the font defined in the manifest under this key
+    pub fn font(&self, key: &str) -> Result<Handle<Font>, AssetManifestError> {
+        self.fonts.get(key).cloned().ok_or_else(|| AssetManifestError::MissingFont(key.to_string()))
+    }
+    /// Returns the sound defined in the manifest under this key
+    pub fn sound(&self, key: &str) -> Result<Handle<AudioSource>, AssetManifestError> {
+        self.sounds.get(key).cloned().ok_or_else(|| AssetManifestError::MissingSound(key.to_string()))
+    }
+    /// Returns all handles held by the cache
+    pub fn handles(&self) -> Vec<UntypedHandle> {
+        self.images.values().chain(self.variants.values().flat_map(|variant| variant.values())).map(|handle| handle.clone().untyped())
+            .chain(self.fonts.values().map(|handle| handle.clone().untyped()))
+            .chain(self.sounds.values().map(|handle| handle.clone().untyped()))
+            .collect()
+    }
+    /// Returns the paths of all assets that failed to load and were replaced by a placeholder
+    pub fn failed(&self) -> &[String] {
+        &self.failed
+    }
+    /// Returns how many assets finished loading out of the total.
+    pub fn progress(&self, asset_server: &AssetServer) -> AssetCacheProgress {
+        let handles = self.handles();
+        AssetCacheProgress {
+            // Placeholders are not tracked by the asset server, so they count as loaded
+            loaded: handles.iter().filter(|handle| matches!(asset_server.get_load_state(handle.id()), None | Some(LoadState::Loaded))).count(),
+            total: handles.len(),
+            failed: self.failed.clone(),
+        }
+    }
+    /// Returns mutable references to all named image handles, in the order of [`NAMED_IMAGES`]
+    fn named_images_mut(&mut self) -> [&mut Handle<Image>; 9] {
+        [
+            &mut self.background, &mut self.panel_menu, &mut self.title, &mut self.cursor, &mut self.button,
+            &mut self.button_bottom_left, &mut self.button_bottom_right, &mut self.chevron_left, &mut self.chevron_right,
+        ]
+    }
+    /// Returns mutable references to all named font handles
+    fn named_fonts_mut(&mut self) -> [&mut Handle<Font>; 5] {
+        [&mut self.font_light, &mut self.font_medium, &mut self.font_semibold, &mut self.font_bold, &mut self.font_mono]
+    }
+    /// Returns mutable references to all named sound handles
+    fn named_sounds_mut(&mut self) -> [&mut Handle<AudioSource>; 5] {
+        [&mut self.hover_sfx, &mut self.click_sfx, &mut self.error_sfx, &mut self.open_sfx, &mut self.close_sfx]
+    }
+    /// This system swaps every handle that failed to load with a placeholder, so widgets keep working, just visibly wrong.
+    /// It runs while loading, the progress is done once no handle is left loading or failed.
+    fn fallback_system(
+        mut cache: ResMut<AssetCache>,
+        fallback: Res<AssetFallback>,
+        asset_server: Res<AssetServer>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<&mut TextFont>,
+    ) {
+        let failed_images: Vec<AssetId<Image>> = cache.images.values().chain(cache.variants.values().flat_map(|variant| variant.values())).filter(|handle| matches!(asset_server.load_state(handle.id()), LoadState::Failed(_))).map(|handle| handle.id()).collect();
+        let failed_fonts: Vec<AssetId<Font>> = cache.fonts.values().filter(|handle| matches!(asset_server.load_state(handle.id()), LoadState::Failed(_))).map(|handle| handle.id()).collect();
+        let failed_sounds: Vec<AssetId<AudioSource>> = cache.sounds.values().filter(|handle| matches!(asset_server.load_state(handle.id()), LoadState::Failed(_))).map(|handle| handle.id()).collect();
+        if failed_images.is_empty() && failed_fonts.is_empty() && failed_sounds.is_empty() { return; }
+
+        for id in failed_images {
+            let path = asset_server.get_path(id).map(|path| path.to_string()).unwrap_or_default();
+            warn!("Failed to load image '{path}', using a placeholder instead");
+            cache.failed.push(path);
+            for handle in cache.images.values_mut() { if handle.id() == id { *handle = fallback.image.clone(); } }
+            for handle in cache.variants.values_mut().flat_map(|variant| variant.values_mut()) { if handle.id() == id { *handle = fallback.image.clone(); } }
+            for handle in cache.named_images_mut() { if handle.id() == id { *handle = fallback.image.clone(); } }
+            for (handle, _) in cache.cursors.values_mut() { if handle.id() == id { *handle = fallback.image.clone(); } }
+            for mut sprite in &mut sprites { if sprite.image.id() == id { sprite.image = fallback.image.clone(); } }
+        }
+        for id in failed_fonts {
+            let path = asset_server.get_path(id).map(|path| path.to_string()).unwrap_or_default();
+            warn!("Failed to load font '{path}', using a placeholder instead");
+            cache.failed.push(path);
+            for handle in cache.fonts.values_mut() { if handle.id() == id { *handle = fallback.font.clone(); } }
+            for handle in cache.named_fonts_mut() { if handle.id() == id { *handle = fallback.font.clone(); } }
+            for mut text in &mut texts { if text.font.id() == id { text.font = fallback.font.clone(); } }
+        }
+        for id in failed_sounds {
+            // Missing sounds are simply silent
+            let path = asset_server.get_path(id).map(|path| path.to_string()).unwrap_or_default();
+            warn!("Failed to load sound '{path}', it will be silent");
+            cache.failed.push(path);
+            for handle in cache.sounds.values_mut() { if handle.id() == id { *handle = Handle::default(); } }
+            for handle in cache.named_sounds_mut() { if handle.id() == id { *handle = Handle::default(); } }
+        }
+    }
+    /// Starts loading the manifest, a manifest missing a required key fails the loading and placeholders are used instead
+    fn build(
+        manifest: &AssetManifest,
+        asset_server: &AssetServer,
+        images: &mut Assets<Image>,
+        fonts: &mut Assets<Font>,
+        layouts: &mut Assets<TextureAtlasLayout>,
+        next: &mut NextState<UiAssetsState>,
+    ) -> Self {
+        AssetCache::from_manifest(manifest, asset_server, layouts).unwrap_or_else(|error| {
+            error!("Failed to build the asset cache, using placeholders instead: {error}");
+            let mut cache = AssetCache::placeholder(images, fonts);
+            cache.failed.push(error.to_string());
+            next.set(UiAssetsState::Failed);
+            cache
+        })
+    }
+    /// This system is run in PreStartup after the manifest load started. A manifest the app inserted is built
+    /// into the cache right away, the one of the file once it is loaded.
+    #[allow(clippy::too_many_arguments)]
+    fn load_system(
+        mut commands: Commands,
+        asset_server: Res<AssetServer>,
+        manifest: Res<AssetManifest>,
+        handle: Option<Res<AssetManifestHandle>>,
+        mut images: ResMut<Assets<Image>>,
+        mut fonts: ResMut<Assets<Font>>,
+        mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+        mut next: ResMut<NextState<UiAssetsState>>,
+    ) {
+        commands.insert_resource(AssetFallback::new(&mut images, &mut fonts));
+        if handle.is_some() { return; }
+        let cache = AssetCache::build(&manifest, &asset_server, &mut images, &mut fonts, &mut layouts, &mut next);
+        commands.insert_resource(AssetCacheProgress { failed: cache.failed.clone(), ..default() });
+        commands.insert_resource(cache);
+    }
+    /// This system re-touches all sprites and texts using cached assets that were modified on disk,
+    /// so that live-editing UI art works without restarting. Requires the `file_watcher` Bevy feature.
+    fn hot_reload_system(
+        mut commands: Commands,
+        cache: Res<AssetCache>,
+        mut image_events: EventReader<AssetEvent<Image>>,
+        mut font_events: EventReader<AssetEvent<Font>>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<(&TextFont, &mut Text2d)>,
+    ) {
+        let cached: HashSet<UntypedAssetId> = cache.handles().iter().map(|handle| handle.id()).collect();
+        let images: HashSet<AssetId<Image>> = image_events.read().filter_map(|event| match event {
+            AssetEvent::Modified { id } if cached.contains(&id.untyped()) => Some(*id),
+            _ => None,
+        }).collect();
+        let fonts: HashSet<AssetId<Font>> = font_events.read().filter_map(|event| match event {
+            AssetEvent::Modified { id } if cached.contains(&id.untyped()) => Some(*id),
+            _ => None,
+        }).collect();
+
+        if !images.is_empty() {
+            for mut sprite in &mut sprites {
+                // Marking the sprite changed makes the slices and the color get recomputed with new dimensions
+                if images.contains(&sprite.image.id()) { sprite.set_changed(); }
+            }
+        }
+        if !fonts.is_empty() {
+            for (font, mut text) in &mut texts {
+                if fonts.contains(&font.font.id()) { text.set_changed(); }
+            }
+        }
+        if !images.is_empty() || !fonts.is_empty() {
+            info!("Reloaded {} image(s) and {} font(s) from the asset cache", images.len(), fonts.len());
+            commands.trigger(RecomputeUiLayout);
+        }
+    }
+    /// This system marks newly spawned sprites that use a cached image, so they can follow theme switches.
+    fn provenance_system(mut commands: Commands, cache: Res<AssetCache>, query: Query<(Entity, &Sprite), (Added<Sprite>, Without<CachedImage>)>) {
+        for (entity, sprite) in &query {
+            if let Some(key) = cache.image_key(sprite.image.id()) {
+                commands.entity(entity).insert(CachedImage(key.to_string()));
+            }
+        }
+    }
+    /// This system swaps the images of all cached sprites when the [`ActiveTheme`] changes.
+    fn theme_system(mut cache: ResMut<AssetCache>, theme: Res<ActiveTheme>, mut query: Query<(&CachedImage, &mut Sprite)>) {
+        cache.apply_theme(&theme.0);
+        for (cached, mut sprite) in &mut query {
+            if let Ok(handle) = cache.themed_image(&cached.0, &theme.0) {
+                if sprite.image != handle { sprite.image = handle; }
+            }
+        }
+    }
+    /// This system starts loading the font of the [`ActiveLocale`] the first time it is selected.
+    fn locale_system(mut cache: ResMut<AssetCache>, locale: Res<ActiveLocale>, asset_server: Res<AssetServer>) {
+        if cache.locale_fonts.contains_key(&locale.0) { return; }
+        let Some(path) = cache.locale_paths.get(&locale.0).cloned() else { return; };
+        info!("Loading font '{path}' for locale '{}'", locale.0);
+        let handle = asset_server.load(path);
+        cache.locale_fonts.insert(locale.0.clone(), handle);
+    }
+    /// This system updates the progress resource until everything is loaded.
+    fn progress_system(
+        cache: Res<AssetCache>,
+        asset_server: Res<AssetServer>,
+        mut progress: ResMut<AssetCacheProgress>,
+        mut ready: EventWriter<AssetCacheReady>,
+        mut next: ResMut<NextState<UiAssetsState>>,
+    ) {
+        let current = cache.progress(&asset_server);
+        if *progress != current {
+            *progress = current;
+            if progress.is_done() {
+                ready.send(AssetCacheReady);
+                next.set(if progress.failed.is_empty() { UiAssetsState::Ready } else { UiAssetsState::Failed });
+            }
+        }
+    }
+}
+
+/// Keys of the images that have a named field in the [`AssetCache`]
+const NAMED_IMAGES: [&str; 9] = [
+    "background", "panel_menu", "title", "cursor", "button",
+    "button_bottom_left", "button_bottom_right", "chevron_left", "chevron_right",
+];
+
+/// The theme selecting which image variants of the [`AssetCache`] are used. Fonts are shared by all themes.
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct ActiveTheme(pub String);
+impl Default for ActiveTheme {
+    fn default() -> Self {
+        ActiveTheme("bevypunk".to_string())
+    }
+}
+
+/// The language the user interface is displayed in, must match a key of the manifest locales
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct ActiveLocale(pub String);
+impl Default for ActiveLocale {
+    fn default() -> Self {
+        ActiveLocale("en".to_string())
+    }
+}
+
+/// Marks a sprite that uses an image from the [`AssetCache`] under this key. Attached automatically.
+#[derive(Component, Clone, PartialEq, Eq, Debug)]
+pub struct CachedImage(pub String);
+
+/// Placeholder assets used in place of the ones that failed to load
+#[derive(Resource, Clone, Debug)]
+pub struct AssetFallback {
+    /// A 1x1 magenta image
+    pub image: Handle<Image>,
+    /// The regular weight of the bundled font
+    pub font: Handle<Font>,
+}
+impl AssetFallback {
+    /// Creates the placeholder assets
+    fn new(images: &mut Assets<Image>, fonts: &mut Assets<Font>) -> Self {
+        Self {
+            image: images.add(Image::new_fill(
+                Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+                TextureDimension::D2,
+                &[255, 0, 255, 255],
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            )),
+            font: fonts.add(Font::try_from_bytes(include_bytes!("../../../assets/fonts/rajdhani/Rajdhani-Regular.ttf").to_vec()).expect("Embedded fallback font is invalid")),
+        }
+    }
+}
+
+/// The loading progress of the [`AssetCache`]. Any loading screen can read this.
+#[derive(Resource, Clone, PartialEq, Eq, Debug, Default)]
+pub struct AssetCacheProgress {
+    /// Number of assets finished loading
+    pub loaded: usize,
+    /// Number of assets in total
+    pub total: usize,
+    /// Paths of the assets that failed to load
+    pub failed: Vec<String>,
+}
+impl AssetCacheProgress {
+    /// If all assets are loaded
+    pub fn is_done(&self) -> bool {
+        self.total != 0 && self.loaded == self.total
+    }
+    /// Returns the progress in range 0.0 to 1.0
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 { return 0.0; }
+        self.loaded as f32 / self.total as f32
+    }
+}
+
+/// This event is sent once when all assets of the [`AssetCache`] are loaded
+#[derive(Event, Clone, Copy, Debug)]
+pub struct AssetCacheReady;
+
+/// The lifecycle of the [`AssetCache`]
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum UiAssetsState {
+    /// Some assets are still loading
+    #[default]
+    Loading,
+    /// All assets are loaded
+    Ready,
+    /// Loading finished, but some assets failed and were replaced by placeholders
+    Failed,
+}
+
+/// Run condition that is true once all assets of the [`AssetCache`] are loaded
+pub fn ui_assets_ready(state: Option<Res<State<UiAssetsState>>>) -> bool {
+    state.is_some_and(|state| *state.get() == UiAssetsState::Ready)
+}
+
+/// Run condition that is true once loading finished, but some assets of the [`AssetCache`] were replaced by placeholders
+pub fn ui_assets_failed(state: Option<Res<State<UiAssetsState>>>) -> bool {
+    state.is_some_and(|state| *state.get() == UiAssetsState::Failed)
+}
+
+
+/// This component writes the [`AssetCacheProgress`] into attached [`Text2d`].
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+pub struct LoadingIndicator {
+    prefix: String,
+}
+impl Default for LoadingIndicator {
+    fn default() -> Self {
+        Self {
+            prefix: "LOADING".to_string(),
+        }
+    }
+}
+impl LoadingIndicator {
+    /// Creates new instance
+    pub fn new(prefix: impl std::fmt::Display) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+        }
+    }
+    /// This system takes care of updating the text with the current progress.
+    fn system(mut query: Query<(&mut Text2d, &LoadingIndicator)>, progress: Res<AssetCacheProgress>) {
+        for (mut text, indicator) in &mut query {
+            let mut string = format!("{} {}/{}", indicator.prefix, progress.loaded, progress.total);
+            for path in &progress.failed { string += &format!("\nFAILED: {path}"); }
+            if text.0 != string { text.0 = string; }
+        }
+    }
+}
+
+
+/// Plugin loading the [`AssetCache`] and reporting the progress
+pub struct AssetCachePlugin;
+impl Plugin for AssetCachePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<AssetManifest>();
+        app.register_asset_loader(AssetManifestLoader);
+        app.init_resource::<ActiveTheme>();
+        app.init_resource::<ActiveLocale>();
+        app.add_plugins(LocalizationPlugin);
+        app.add_event::<AssetCacheReady>();
+        app.init_resource::<AssetCacheProgress>();
+        app.init_state::<UiAssetsState>();
+        app.register_type::<AssetCache>();
+        app.add_systems(PreStartup, (AssetManifest::load_system, AssetCache::load_system).chain());
+        app.add_systems(PreUpdate, AssetManifest::apply_system.run_if(resource_exists::<AssetManifestHandle>.and(on_event::<AssetEvent<AssetManifest>>.or(on_event::<AssetLoadFailedEvent<AssetManifest>>))));
+        // Loads only fail while loading, a manifest edit starts the loading again
+        app.add_systems(Update, (
+            (AssetCache::fallback_system, AssetCache::progress_system).chain().run_if(resource_exists::<AssetCache>.and(in_state(UiAssetsState::Loading))),
+            LoadingIndicator::system,
+        ).chain());
+        app.add_systems(PostUpdate, (
+            AssetCache::provenance_system,
+            AssetCache::theme_system.run_if(resource_changed::<ActiveTheme>),
+            AssetCache::locale_system.run_if(resource_changed::<ActiveLocale>),
+        ).chain().run_if(resource_exists::<AssetCache>));
+        app.add_systems(Update, AssetCache::hot_reload_system.run_if(resource_exists::<AssetCache>.and(on_event::<AssetEvent<Image>>.or(on_event::<AssetEvent<Font>>))));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{ecs::component::Tick, prelude::*, state::app::StatesPlugin};
+
+    use crate::*;
+
+    /// An app running the hot reload with a cache holding one image and one font, returns them too
+    fn hot_reload_app() -> (App, Handle<Image>, Handle<Font>) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<Image>();
+        app.init_asset::<Font>();
+
+        let world = app.world_mut();
+        let mut cache = world.resource_scope(|world, mut images: Mut<Assets<Image>>| AssetCache::placeholder(&mut images, &mut world.resource_mut::<Assets<Font>>()));
+        let (image, font) = (cache.button.clone(), cache.font_bold.clone());
+        cache.images.insert("button".to_string(), image.clone());
+        cache.fonts.insert("bold".to_string(), font.clone());
+        app.insert_resource(cache);
+        app.add_systems(Update, AssetCache::hot_reload_system.run_if(on_event::<AssetEvent<Image>>.or(on_event::<AssetEvent<Font>>)));
+        app.update();
+        (app, image, font)
+    }
+
+    /// The tick the component of the entity was last changed on
+    fn last_changed<C: Component>(app: &App, entity: Entity) -> Tick {
+        app.world().entity(entity).get_ref::<C>().expect("The entity lacks the component").last_changed()
+    }
+
+    #[test]
+    fn modified_image_dirties_the_sprites_using_it() {
+        let (mut app, image, _) = hot_reload_app();
+        let uncached = app.world_mut().resource_mut::<Assets<Image>>().add(Image::default());
+        let cached = app.world_mut().spawn(Sprite::from_image(image.clone())).id();
+        let other = app.world_mut().spawn(Sprite::from_image(uncached)).id();
+        app.update();
+        let before = [cached, other].map(|sprite| last_changed::<Sprite>(&app, sprite));
+
+        app.world_mut().send_event(AssetEvent::Modified { id: image.id() });
+        app.update();
+        assert_ne!(last_changed::<Sprite>(&app, cached), before[0], "The sprite of the modified image was not dirtied");
+        assert_eq!(last_changed::<Sprite>(&app, other), before[1], "A sprite of another image was dirtied");
+    }
+
+    #[test]
+    fn modified_image_outside_the_cache_is_ignored() {
+        let (mut app, image, _) = hot_reload_app();
+        let uncached = app.world_mut().resource_mut::<Assets<Image>>().add(Image::default());
+        let sprite = app.world_mut().spawn(Sprite::from_image(image)).id();
+        app.update();
+        let before = last_changed::<Sprite>(&app, sprite);
+
+        app.world_mut().send_event(AssetEvent::Modified { id: uncached.id() });
+        app.update();
+        assert_eq!(last_changed::<Sprite>(&app, sprite), before, "The sprite was dirtied by an image it does not use");
+    }
+
+    #[test]
+    fn modified_font_dirties_the_texts_using_it() {
+        let (mut app, _, font) = hot_reload_app();
+        let text = app.world_mut().spawn((Text2d::new("Play"), TextFont { font: font.clone(), ..default() })).id();
+        app.update();
+        let before = last_changed::<Text2d>(&app, text);
+
+        app.world_mut().send_event(AssetEvent::Modified { id: font.id() });
+        app.update();
+        assert_ne!(last_changed::<Text2d>(&app, text), before, "The text of the modified font was not dirtied");
+    }
+
+    #[test]
+    fn missing_manifest_file_builds_the_placeholder_cache() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin));
+        app.init_asset::<Image>();
+        app.init_asset::<Font>();
+        app.init_asset::<TextureAtlasLayout>();
+        app.init_asset::<bevy_kira_audio::AudioSource>();
+        app.add_plugins(AssetCachePlugin);
+
+        // The test crate has no manifest file, the cache is built once its load failed
+        for _ in 0..200 {
+            if app.world().contains_resource::<AssetCache>() { break; }
+            std::thread::sleep(Duration::from_millis(5));
+            app.update();
+        }
+        app.update();
+        assert!(app.world().contains_resource::<AssetCache>(), "The cache was not built once the manifest failed to load");
+        assert_eq!(*app.world().resource::<State<UiAssetsState>>().get(), UiAssetsState::Failed);
+    }
+}
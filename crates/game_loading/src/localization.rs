@@ -0,0 +1,241 @@
+use std::collections::BTreeSet;
+
+use bevy::{asset::{io::Reader, AssetLoader, LoadContext}, prelude::*, sprite::Anchor, utils::HashMap};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// The translated strings of one language, loaded from `.strings.ron` files mapping keys to text.
+/// Entries can hold `{name}` placeholders filled from the arguments of the [`LocalizedText`].
+#[derive(Asset, TypePath, Clone, PartialEq, Debug, Default)]
+pub struct StringTable(pub HashMap<String, String>);
+
+/// The string tables of all languages in the asset manifest and the keys found missing so far
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct Localization {
+    tables: HashMap<String, Handle<StringTable>>,
+    missing: BTreeSet<(String, String)>,
+}
+impl Localization {
+    /// Returns the entry of the key in the language with the placeholders filled, if it has one
+    pub fn format(&self, tables: &Assets<StringTable>, locale: &str, key: &str, args: &[(String, String)]) -> Option<String> {
+        let entry = tables.get(self.tables.get(locale)?)?.0.get(key)?;
+        Some(args.iter().fold(entry.clone(), |text, (name, value)| text.replace(&format!("{{{name}}}"), value)))
+    }
+    /// The language and key of every entry that was displayed but missing, in order
+    pub fn missing(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.missing.iter().map(|(locale, key)| (locale.as_str(), key.as_str()))
+    }
+    /// Logs all missing entries, like before sending the tables to translators
+    pub fn dump_missing(&self) {
+        for (locale, key) in self.missing() { warn!("Missing string '{key}' for locale '{locale}'"); }
+    }
+    /// This system starts loading the string tables of all languages, again once the manifest file is loaded or edited.
+    fn load_system(mut localization: ResMut<Localization>, manifest: Res<AssetManifest>, asset_server: Res<AssetServer>) {
+        for (locale, definition) in &manifest.locales {
+            let Some(path) = &definition.strings else { continue; };
+            localization.tables.insert(locale.clone(), asset_server.load(path.clone()));
+        }
+    }
+}
+
+/// Send this event to switch the [`ActiveLocale`], every localized text is resolved again in place
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct SetLanguage(pub String);
+impl SetLanguage {
+    /// This system switches to the requested languages that are in the asset manifest.
+    fn system(mut events: EventReader<SetLanguage>, manifest: Res<AssetManifest>, mut locale: ResMut<ActiveLocale>) {
+        for SetLanguage(language) in events.read() {
+            if !manifest.locales.contains_key(language) {
+                warn!("Locale '{language}' is not in the asset manifest");
+                continue;
+            }
+            if locale.0 != *language { locale.0 = language.clone(); }
+        }
+    }
+}
+
+/// Marks a text that follows the [`ActiveLocale`]. With a key its [`Text2d`] shows the entry of the
+/// [`StringTable`], missing entries show as `[key]`. The font switches to the font of the locale once
+/// it is loaded, until then, or if the locale has no font, the font the text was spawned with is used.
+#[derive(Component, Reflect, Clone, PartialEq, Debug, Default)]
+pub struct LocalizedText {
+    /// The key of the entry, texts without one only follow the font
+    pub key: Option<String>,
+    /// Values of the `{name}` placeholders in the entry
+    pub args: Vec<(String, String)>,
+    /// If the text anchor and justification flip for right-to-left languages
+    pub mirrored: bool,
+    default: Option<Handle<Font>>,
+    resolved: Option<String>,
+    flipped: bool,
+}
+impl LocalizedText {
+    /// Creates new instance showing the entry of the key
+    pub fn new(key: impl Into<String>) -> Self {
+        Self { key: Some(key.into()), ..default() }
+    }
+    /// Fill the `{name}` placeholder of the entry with the value.
+    pub fn arg(mut self, name: impl Into<String>, value: impl std::fmt::Display) -> Self {
+        self.args.push((name.into(), value.to_string()));
+        self
+    }
+    /// Flip the text anchor and justification for right-to-left languages.
+    pub fn mirrored(mut self) -> Self {
+        self.mirrored = true;
+        self
+    }
+    /// Replace the font used while the locale has none, like when the widget is restyled.
+    pub fn set_default_font(&mut self, font: Handle<Font>) {
+        self.default = Some(font);
+    }
+    /// The text of the entry in the active locale, once resolved
+    pub fn text(&self) -> Option<&str> {
+        self.resolved.as_deref()
+    }
+    /// This system points the fonts of all localized texts to the font of the active locale.
+    fn font_system(
+        mut commands: Commands,
+        cache: Res<AssetCache>,
+        locale: Res<ActiveLocale>,
+        asset_server: Res<AssetServer>,
+        mut query: Query<(&mut LocalizedText, &mut TextFont)>,
+    ) {
+        let locale_font = cache.locale_font(&locale.0, &asset_server);
+        let mut changed = false;
+        for (mut localized, mut text) in &mut query {
+            // Remembering the font is not a change of what the text shows
+            let default = localized.bypass_change_detection().default.get_or_insert_with(|| text.font.clone()).clone();
+            let font = locale_font.clone().unwrap_or(default);
+            if text.font != font {
+                text.font = font;
+                changed = true;
+            }
+        }
+        if changed { commands.trigger(RecomputeUiLayout); }
+    }
+    /// This system resolves the keys of new texts, and of all texts when the language or a table changed.
+    #[allow(clippy::type_complexity)]
+    fn resolve_system(
+        mut commands: Commands,
+        mut localization: ResMut<Localization>,
+        tables: Res<Assets<StringTable>>,
+        mut events: EventReader<AssetEvent<StringTable>>,
+        locale: Res<ActiveLocale>,
+        manifest: Res<AssetManifest>,
+        mut query: Query<(&mut LocalizedText, Option<&mut Text2d>, Option<&mut Anchor>, Option<&mut TextLayout>)>,
+    ) {
+        // The manifest file arriving can turn the language right to left
+        let all = locale.is_changed() || manifest.is_changed() || events.read().count() > 0;
+        // Texts keep what they show until the table of the language arrives
+        let loading = localization.tables.get(&locale.0).is_some_and(|handle| !tables.contains(handle));
+        let rtl = manifest.locales.get(&locale.0).is_some_and(|definition| definition.rtl);
+        let mut changed = false;
+        for (mut localized, text, anchor, layout) in &mut query {
+            if !all && !localized.is_changed() { continue; }
+
+            // Only a new result counts as a change, so widgets showing the text can follow it
+            if let Some(key) = localized.key.clone().filter(|_| !loading) {
+                let resolved = match localization.format(&tables, &locale.0, &key, &localized.args) {
+                    Some(resolved) => resolved,
+                    None => {
+                        localization.missing.insert((locale.0.clone(), key.clone()));
+                        format!("[{key}]")
+                    },
+                };
+                if let Some(mut text) = text {
+                    if text.0 != resolved {
+                        text.0 = resolved.clone();
+                        changed = true;
+                    }
+                }
+                if localized.resolved.as_ref() != Some(&resolved) { localized.resolved = Some(resolved); }
+            }
+
+            if localized.mirrored && localized.flipped != rtl {
+                localized.flipped = rtl;
+                if let Some(mut anchor) = anchor { *anchor = mirror_anchor(*anchor); }
+                if let Some(mut layout) = layout {
+                    layout.justify = match layout.justify {
+                        JustifyText::Left => JustifyText::Right,
+                        JustifyText::Right => JustifyText::Left,
+                        justify => justify,
+                    };
+                }
+                changed = true;
+            }
+        }
+        if changed { commands.trigger(RecomputeUiLayout); }
+    }
+}
+
+/// Returns the anchor on the other horizontal side
+fn mirror_anchor(anchor: Anchor) -> Anchor {
+    match anchor {
+        Anchor::TopLeft => Anchor::TopRight,
+        Anchor::TopRight => Anchor::TopLeft,
+        Anchor::CenterLeft => Anchor::CenterRight,
+        Anchor::CenterRight => Anchor::CenterLeft,
+        Anchor::BottomLeft => Anchor::BottomRight,
+        Anchor::BottomRight => Anchor::BottomLeft,
+        Anchor::Custom(point) => Anchor::Custom(Vec2::new(-point.x, point.y)),
+        anchor => anchor,
+    }
+}
+
+/// Systems resolving the [`LocalizedText`], run systems reading the resolved texts after them
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LocalizationSystems;
+
+/// Errors produced when a string table file is invalid
+#[derive(Clone, PartialEq, Debug)]
+pub enum StringTableError {
+    /// The file could not be read
+    Io(String),
+    /// The file is not a RON map of strings
+    Parse(String),
+}
+impl std::fmt::Display for StringTableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringTableError::Io(error) => write!(f, "String table could not be read: {error}"),
+            StringTableError::Parse(error) => write!(f, "String table could not be parsed: {error}"),
+        }
+    }
+}
+impl std::error::Error for StringTableError {}
+
+/// Loads [`StringTable`] from `.strings.ron` files
+#[derive(Default)]
+struct StringTableLoader;
+impl AssetLoader for StringTableLoader {
+    type Asset = StringTable;
+    type Settings = ();
+    type Error = StringTableError;
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), _context: &mut LoadContext<'_>) -> Result<StringTable, StringTableError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(|error| StringTableError::Io(error.to_string()))?;
+        ron::from_str(&String::from_utf8_lossy(&bytes)).map(StringTable).map_err(|error| StringTableError::Parse(error.to_string()))
+    }
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+
+/// Plugin loading the [`StringTable`] of each language and keeping the [`LocalizedText`] up to date
+pub(crate) struct LocalizationPlugin;
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<StringTable>();
+        app.register_asset_loader(StringTableLoader);
+        app.init_resource::<Localization>();
+        app.add_event::<SetLanguage>();
+        app.add_systems(Update, (Localization::load_system.run_if(resource_exists_and_changed::<AssetManifest>), SetLanguage::system));
+        app.add_systems(PostUpdate, (
+            LocalizedText::resolve_system,
+            LocalizedText::font_system.run_if(resource_exists::<AssetCache>),
+        ).chain().in_set(LocalizationSystems).after(AssetCache::locale_system));
+    }
+}
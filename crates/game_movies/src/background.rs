@@ -0,0 +1,174 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_lunex::*;
+use vleue_kinetoscope::*;
+
+
+/// What a [`BackgroundPlayer`] shows
+#[derive(Clone, Debug)]
+pub enum BackgroundSource {
+    /// Separate images shown one after another at the frame rate
+    Sequence { frames: Vec<Handle<Image>>, fps: f32 },
+    /// An animated image, like the intro movie, played at its own frame rate
+    Animated(Handle<AnimatedImage>),
+}
+
+/// Plays a looping image sequence or animated image on the sprite of the entity, beneath the UI.
+/// The layout is replaced with a [`Scaling::Fill`] one sized to the frames, so the background keeps
+/// its aspect ratio and is cropped to cover the window like the still backgrounds. The player pauses
+/// itself while the window is unfocused.
+#[derive(Component, Clone, Debug)]
+#[require(Sprite)]
+pub struct BackgroundPlayer {
+    pub source: BackgroundSource,
+    /// If the background starts over once finished
+    pub looping: bool,
+    /// If the player pauses while the window is unfocused, to save battery
+    pub pause_unfocused: bool,
+    paused: bool,
+    /// Seconds played, animated images count from the start or the last seek
+    time: f32,
+    /// The frame size the layout was last fitted to
+    size: Option<UVec2>,
+    finished: bool,
+    /// A seek the animated image has not restarted for yet
+    sought: bool,
+}
+impl BackgroundPlayer {
+    /// Creates new instance playing the frames at the frame rate
+    pub fn sequence(frames: Vec<Handle<Image>>, fps: f32) -> Self {
+        Self::new(BackgroundSource::Sequence { frames, fps })
+    }
+    /// Creates new instance loading numbered frames. The run of `#` in the path is replaced with the
+    /// zero padded frame number, so `"movies/menu/####.png"` loads `0001.png` up to the count.
+    pub fn numbered(asset_server: &AssetServer, path: &str, count: usize, fps: f32) -> Self {
+        let digits = path.chars().filter(|c| *c == '#').count();
+        let pattern = "#".repeat(digits);
+        let frames = (1..=count).map(|index| asset_server.load(path.replacen(&pattern, &format!("{index:0digits$}"), 1))).collect();
+        Self::sequence(frames, fps)
+    }
+    /// Creates new instance playing the animated image
+    pub fn animated(image: Handle<AnimatedImage>) -> Self {
+        Self::new(BackgroundSource::Animated(image))
+    }
+    fn new(source: BackgroundSource) -> Self {
+        Self {
+            source,
+            looping: true,
+            pause_unfocused: true,
+            paused: false,
+            time: 0.0,
+            size: None,
+            finished: false,
+            sought: false,
+        }
+    }
+    /// Play it only once and stop on the last frame.
+    pub fn once(mut self) -> Self {
+        self.looping = false;
+        self
+    }
+    /// Keep playing while the window is unfocused.
+    pub fn play_unfocused(mut self) -> Self {
+        self.pause_unfocused = false;
+        self
+    }
+    /// Resumes the playback
+    pub fn play(&mut self) {
+        self.paused = false;
+    }
+    /// Pauses the playback, it is not resumed on its own
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+    /// If the playback was paused, this ignores the unfocused window
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+    /// Jumps to the seconds. Animated images can only be sought back to the start.
+    pub fn seek(&mut self, seconds: f32) {
+        self.time = match self.source {
+            BackgroundSource::Sequence { .. } => seconds.clamp(0.0, self.duration().unwrap_or_default()),
+            BackgroundSource::Animated(_) => 0.0,
+        };
+        self.finished = false;
+        self.sought = true;
+    }
+    /// Seconds played since the start or the last loop, for syncing transitions to the background
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+    /// Seconds the sequence takes to play once, `None` for animated images
+    pub fn duration(&self) -> Option<f32> {
+        match &self.source {
+            BackgroundSource::Sequence { frames, fps } => Some(frames.len() as f32 / fps.max(f32::EPSILON)),
+            BackgroundSource::Animated(_) => None,
+        }
+    }
+    /// This system advances the players, shows their current frame and fits their layout to the frames.
+    fn system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut BackgroundPlayer, &mut Sprite, Option<&mut AnimatedImageController>)>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        images: Res<Assets<Image>>,
+        time: Res<Time>,
+    ) {
+        let focused = windows.get_single().map_or(true, |window| window.focused);
+        for (entity, mut player, mut sprite, controller) in &mut query {
+            let running = !player.paused && (focused || !player.pause_unfocused);
+            match player.source.clone() {
+                BackgroundSource::Sequence { frames, fps } => {
+                    if frames.is_empty() { continue; }
+                    let duration = player.duration().unwrap_or_default();
+                    if running && !player.finished { player.time += time.delta_secs(); }
+                    if player.time >= duration {
+                        if player.looping {
+                            player.time %= duration;
+                        } else if !player.finished {
+                            player.time = duration;
+                            player.finished = true;
+                            commands.trigger_targets(BackgroundFinished, entity);
+                        }
+                    }
+                    let index = ((player.time * fps) as usize).min(frames.len() - 1);
+                    if sprite.image != frames[index] { sprite.image = frames[index].clone(); }
+                },
+                BackgroundSource::Animated(image) => {
+                    let Some(mut controller) = controller else {
+                        commands.entity(entity).insert(AnimatedImageController::play(image));
+                        continue;
+                    };
+                    if std::mem::take(&mut player.sought) { controller.reset(); }
+                    if running && controller.paused() && !player.finished { controller.resume(); }
+                    if !running && !controller.paused() { controller.pause(); }
+                    if !player.looping && !player.finished && controller.play_count() >= 1 {
+                        controller.pause();
+                        player.finished = true;
+                        commands.trigger_targets(BackgroundFinished, entity);
+                    }
+                    if running && !player.finished { player.time += time.delta_secs(); }
+                },
+            }
+            player.sought = false;
+
+            // Fit the layout once the frame size is known, it changes only if the frames differ in size
+            let Some(size) = images.get(&sprite.image).map(|image| image.size()) else { continue; };
+            if player.size != Some(size) {
+                player.size = Some(size);
+                commands.entity(entity).insert(UiLayout::solid().size((size.x as f32, size.y as f32)).scaling(Scaling::Fill).pack());
+            }
+        }
+    }
+}
+
+/// This event is triggered on a [`BackgroundPlayer`] that plays once when it reaches the end
+#[derive(Event, Clone, Copy, Debug)]
+pub struct BackgroundFinished;
+
+
+/// Plugin playing the [`BackgroundPlayer`] backgrounds
+pub(crate) struct BackgroundPlayerPlugin;
+impl Plugin for BackgroundPlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, BackgroundPlayer::system);
+    }
+}
@@ -2,6 +2,9 @@ use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
 use vleue_kinetoscope::*;
 
+mod background;
+pub use background::*;
+
 #[derive(Component)]
 pub struct Movie {
     pub playback: MoviePlayback,
@@ -92,5 +95,6 @@ impl Plugin for MoviePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<MovieEnded>();
         app.add_systems(Update, Movie::system);
+        app.add_plugins(BackgroundPlayerPlugin);
     }
 }
\ No newline at end of file
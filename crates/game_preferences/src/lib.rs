@@ -4,6 +4,16 @@ use bevy_kira_audio::AudioPlugin;
 use bevy_lunex::UiLunexPlugin;
 use vleue_kinetoscope::AnimatedImagePlugin;
 use clap::Parser;
+use game_loading::ActiveLocale;
+use game_vfx::{motion_scale, BlinkingCaret, CursorTrail, PalettePreset, ReducedMotion, TextEffect, ThemeCrossfade, ThemeId, UiTheme};
+use serde::{Deserialize, Serialize};
+
+mod persistence;
+pub use persistence::*;
+
+/// Everything that differs between the desktop and the browser builds, the rest of the game calls
+/// these instead of checking the target itself
+pub mod platform;
 
 
 /// Launch arguments for the Bevypunk game
@@ -20,6 +30,129 @@ pub struct Args {
     /// Choose to run with weaker GPU
     #[arg(short, long)]
     pub powersaver: bool,
+
+    /// If to record the UI input with F11
+    #[arg(short, long)]
+    pub record: bool,
+}
+
+
+/// Volume settings of the game, all values are in range 0.0 to 1.0
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Volume multiplier applied to everything
+    pub master: f32,
+    /// Volume of the music
+    pub music: f32,
+    /// Volume of the user interface sounds
+    pub ui: f32,
+    /// Silences the user interface sounds without losing their volume
+    pub ui_muted: bool,
+}
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+            ui: 0.6,
+            ui_muted: false,
+        }
+    }
+}
+impl AudioSettings {
+    /// The final volume of the user interface sounds
+    pub fn ui_volume(&self) -> f32 {
+        if self.ui_muted { 0.0 } else { self.master * self.ui }
+    }
+}
+
+
+/// User settings stored in the [`SettingsFile`]
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct GameSettings {
+    /// The built-in palette
+    pub theme: ThemeId,
+    /// The color vision preset
+    pub palette: PalettePreset,
+    /// If the cursor leaves a trail behind
+    pub cursor_trail: bool,
+    /// Turns off the purely decorative motion, defaults to the `BEVYPUNK_REDUCED_MOTION` environment hint
+    pub reduced_motion: bool,
+    /// If the gamepad rumbles on menu feedback
+    pub haptics: bool,
+    /// Strength of the rumble in range 0.0 to 1.0
+    pub haptics_intensity: f32,
+    /// The key of the language in the asset manifest locales
+    pub language: String,
+}
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            theme: ThemeId::default(),
+            palette: PalettePreset::default(),
+            cursor_trail: true,
+            reduced_motion: std::env::var("BEVYPUNK_REDUCED_MOTION").is_ok_and(|hint| !matches!(hint.as_str(), "" | "0" | "false")),
+            haptics: true,
+            haptics_intensity: 1.0,
+            language: ActiveLocale::default().0,
+        }
+    }
+}
+impl GameSettings {
+    /// This system copies the theme choices made in game into the settings.
+    fn sync_system(crossfade: Res<ThemeCrossfade>, mut settings: ResMut<GameSettings>) {
+        if settings.theme != crossfade.current || settings.palette != crossfade.palette {
+            settings.theme = crossfade.current;
+            settings.palette = crossfade.palette;
+        }
+    }
+    /// This system copies the language chosen in game into the settings.
+    fn language_system(locale: Res<ActiveLocale>, mut settings: ResMut<GameSettings>) {
+        if settings.language != locale.0 { settings.language = locale.0.clone(); }
+    }
+    /// This system mirrors the setting into the [`ReducedMotion`] every animated system reads.
+    fn motion_system(settings: Res<GameSettings>, mut reduced: ResMut<ReducedMotion>) {
+        if reduced.0 != settings.reduced_motion { reduced.0 = settings.reduced_motion; }
+    }
+    /// This system turns the cursor trail on or off, it counts as decorative motion.
+    fn trail_system(settings: Res<GameSettings>, reduced: Res<ReducedMotion>, mut trails: Query<&mut CursorTrail>) {
+        let enabled = settings.cursor_trail && motion_scale(&reduced) > 0.0;
+        for mut trail in &mut trails {
+            if trail.enabled != enabled { trail.enabled = enabled; }
+        }
+    }
+    /// This system holds the carets solid under reduced motion.
+    fn caret_system(reduced: Res<ReducedMotion>, mut carets: Query<&mut BlinkingCaret>) {
+        let solid = motion_scale(&reduced) == 0.0;
+        for mut caret in &mut carets {
+            if caret.solid != solid { caret.solid = solid; }
+        }
+    }
+    /// This system holds the text effects still under reduced motion.
+    fn text_effect_system(reduced: Res<ReducedMotion>, mut effects: Query<&mut TextEffect>) {
+        let still = motion_scale(&reduced) == 0.0;
+        for mut effect in &mut effects {
+            if effect.still != still { effect.still = still; }
+        }
+    }
+}
+
+
+/// Plugin registering all the game settings
+pub struct PreferencesPlugin;
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(SettingsPersistencePlugin);
+        app.add_systems(Update, GameSettings::sync_system.run_if(resource_changed::<ThemeCrossfade>));
+        app.add_systems(Update, GameSettings::language_system.run_if(resource_changed::<ActiveLocale>));
+        app.init_resource::<ReducedMotion>();
+        app.add_systems(PreUpdate, GameSettings::motion_system.run_if(resource_changed::<GameSettings>));
+        app.add_systems(Update, GameSettings::trail_system.run_if(resource_changed::<GameSettings>.or(any_match_filter::<Added<CursorTrail>>)));
+        app.add_systems(Update, GameSettings::caret_system.run_if(resource_changed::<ReducedMotion>.or(any_match_filter::<Added<BlinkingCaret>>)));
+        app.add_systems(Update, GameSettings::text_effect_system.run_if(resource_changed::<ReducedMotion>.or(any_match_filter::<Added<TextEffect>>)));
+    }
 }
 
 
@@ -43,6 +176,8 @@ impl PluginGroup for BevyPlugins {
                 mode: WindowMode::Windowed,
                 present_mode: PresentMode::AutoVsync,
                 resolution: WindowResolution::new(1280.0, 720.0),
+                // Only used in the browser
+                fit_canvas_to_parent: true,
                 ..default()
             }),
             ..default()
@@ -59,15 +194,18 @@ impl PluginGroup for BevyPlugins {
             ..default()
         });
 
-        // Disable the buildin pointers
+        // Disable the buildin pointers, except touch where it does not move the cursor
         builder = builder.set(PointerInputPlugin { 
             is_mouse_enabled: false,
-            is_touch_enabled: false,
+            is_touch_enabled: platform::TOUCH_POINTERS,
         });
 
         // Add 3rd-party Bevy plugins
         builder = builder.add(AnimatedImagePlugin).add(AudioPlugin).add(UiLunexPlugin);
 
+        // Add the game settings
+        builder = builder.add(PreferencesPlugin);
+
         // Return the plugin group
         builder
     }
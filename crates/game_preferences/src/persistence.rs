@@ -0,0 +1,125 @@
+use bevy::{app::AppExit, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+
+/// Everything stored in the settings file. Fields missing in the file keep their defaults and
+/// unknown ones are ignored, so files of other versions still load.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct SettingsFile {
+    /// The layout version the file was written with, older ones go through [`SettingsFile::migrate`]
+    pub version: u32,
+    pub game: GameSettings,
+    pub audio: AudioSettings,
+}
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self { version: SettingsFile::VERSION, game: GameSettings::default(), audio: AudioSettings::default() }
+    }
+}
+impl SettingsFile {
+    /// The current layout version
+    pub const VERSION: u32 = 1;
+    /// Parses the file of any version into the current layout
+    pub fn parse(source: &str) -> Result<Self, String> {
+        /// Only the version, read first to pick the layout
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(default)]
+            version: u32,
+        }
+        let header: Header = ron::from_str(source).map_err(|error| error.to_string())?;
+        if header.version > SettingsFile::VERSION { warn!("Settings file is from a newer version {}, unknown settings are dropped", header.version); }
+        SettingsFile::migrate(header.version, source)
+    }
+    /// Reads a file written with an older layout version. Add a branch here when the layout changes.
+    pub fn migrate(version: u32, source: &str) -> Result<Self, String> {
+        match version {
+            // The first files held the game settings only, without a version
+            0 => {
+                let game: GameSettings = ron::from_str(source).map_err(|error| error.to_string())?;
+                Ok(SettingsFile { game, ..default() })
+            },
+            _ => {
+                let file: SettingsFile = ron::from_str(source).map_err(|error| error.to_string())?;
+                Ok(SettingsFile { version: SettingsFile::VERSION, ..file })
+            },
+        }
+    }
+    /// Reads the settings, a missing file gives the defaults and a corrupt one is set aside first
+    pub fn load() -> Self {
+        let Some(source) = platform::read_settings() else { return SettingsFile::default(); };
+        SettingsFile::parse(&source).unwrap_or_else(|error| {
+            warn!("Settings file is invalid, using defaults: {error}");
+            platform::set_aside_settings();
+            SettingsFile::default()
+        })
+    }
+    /// Writes the settings
+    pub fn save(&self) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|error| error.to_string())
+            .and_then(|source| platform::write_settings(&source));
+        if let Err(error) = result { warn!("Failed to save the settings: {error}"); }
+    }
+}
+
+/// Saves the settings a moment after they stop changing, so dragging a volume does not write on every frame
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct SettingsPersistence {
+    /// Seconds without changes before the settings are saved
+    pub delay: f32,
+    /// The time of the last unsaved change
+    dirty_since: Option<f32>,
+}
+impl Default for SettingsPersistence {
+    fn default() -> Self {
+        Self { delay: 0.5, dirty_since: None }
+    }
+}
+impl SettingsPersistence {
+    /// This system notes the time of each change of the settings.
+    fn change_system(mut persistence: ResMut<SettingsPersistence>, time: Res<Time<Real>>) {
+        persistence.dirty_since = Some(time.elapsed_secs());
+    }
+    /// This system saves the settings once they rested for the delay.
+    fn save_system(mut persistence: ResMut<SettingsPersistence>, time: Res<Time<Real>>, game: Res<GameSettings>, audio: Res<AudioSettings>) {
+        let Some(since) = persistence.dirty_since else { return; };
+        if time.elapsed_secs() - since < persistence.delay { return; }
+        persistence.dirty_since = None;
+        SettingsFile { game: game.clone(), audio: *audio, ..default() }.save();
+    }
+    /// This system saves the unsaved changes when the app exits.
+    fn exit_system(mut events: EventReader<AppExit>, mut persistence: ResMut<SettingsPersistence>, game: Res<GameSettings>, audio: Res<AudioSettings>) {
+        if events.read().count() == 0 || persistence.dirty_since.take().is_none() { return; }
+        SettingsFile { game: game.clone(), audio: *audio, ..default() }.save();
+    }
+}
+
+
+/// Plugin loading the [`SettingsFile`] at build time and saving it when the settings change or the app exits
+pub struct SettingsPersistencePlugin;
+impl Plugin for SettingsPersistencePlugin {
+    fn build(&self, app: &mut App) {
+        // Load the settings now, so the palette is correct before the first menu renders
+        let file = SettingsFile::load();
+        let crossfade = ThemeCrossfade::new(file.game.theme, file.game.palette);
+        app.insert_resource::<UiTheme>(crossfade.target());
+        app.insert_resource(crossfade);
+        // The texts start in the saved language
+        app.insert_resource(ActiveLocale(file.game.language.clone()));
+        app.insert_resource(file.game);
+        app.insert_resource(file.audio);
+        app.init_resource::<SettingsPersistence>();
+        app.add_systems(Update, (
+            // Skip the first run, the settings were just loaded
+            SettingsPersistence::change_system.run_if(
+                (resource_changed::<GameSettings>.and(not(resource_added::<GameSettings>)))
+                .or(resource_changed::<AudioSettings>.and(not(resource_added::<AudioSettings>)))
+            ),
+            SettingsPersistence::save_system,
+        ).chain().after(GameSettings::sync_system));
+        app.add_systems(Last, SettingsPersistence::exit_system);
+    }
+}
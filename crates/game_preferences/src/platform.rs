@@ -0,0 +1,143 @@
+use std::{path::{Path, PathBuf}, time::SystemTime};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+
+/// If the game runs in a browser
+pub const IS_WEB: bool = cfg!(target_arch = "wasm32");
+
+/// If the window can move the cursor, browsers do not allow it
+pub const CAN_MOVE_CURSOR: bool = !IS_WEB;
+
+/// If the cursor grab has to wait for a click, browsers lock the pointer only on a user gesture
+pub const GRAB_NEEDS_CLICK: bool = IS_WEB;
+
+/// If touches come from the built-in touch pointers, browsers send them instead of moving the cursor
+pub const TOUCH_POINTERS: bool = IS_WEB;
+
+/// Returns the save files with the extension in the directory and when they were last written
+pub fn files(directory: &Path, extension: &str) -> Vec<(PathBuf, SystemTime)> {
+    list(directory).into_iter().filter(|(path, _)| path.extension().and_then(|e| e.to_str()) == Some(extension)).collect()
+}
+
+
+/// Files and the settings on the disk
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::{fs, path::{Path, PathBuf}, time::SystemTime};
+
+    use bevy::prelude::*;
+
+    /// The settings file in the config directory of the user, or next to the game if there is none
+    pub fn settings_path() -> PathBuf {
+        #[cfg(target_os = "windows")]
+        let config = std::env::var_os("APPDATA").map(PathBuf::from);
+        #[cfg(target_os = "macos")]
+        let config = std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"));
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let config = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+        config.map(|config| config.join("bevypunk").join("settings.ron")).unwrap_or_else(|| PathBuf::from("settings.ron"))
+    }
+    /// Reads the settings, falling back to the file older versions wrote next to the game
+    pub fn read_settings() -> Option<String> {
+        fs::read_to_string(settings_path()).or_else(|_| fs::read_to_string("settings.ron")).ok()
+    }
+    /// Writes the settings
+    pub fn write_settings(source: &str) -> Result<(), String> {
+        let path = settings_path();
+        if let Some(parent) = path.parent() { fs::create_dir_all(parent).map_err(|error| error.to_string())?; }
+        fs::write(path, source).map_err(|error| error.to_string())
+    }
+    /// Renames the corrupt settings file, so it can be recovered by hand and is not overwritten
+    pub fn set_aside_settings() {
+        let path = Some(settings_path()).filter(|path| path.exists()).unwrap_or_else(|| PathBuf::from("settings.ron"));
+        let aside = path.with_extension("ron.corrupt");
+        match fs::rename(&path, &aside) {
+            Ok(()) => warn!("Moved the invalid settings file to '{}'", aside.display()),
+            Err(error) => warn!("Failed to move the invalid settings file aside: {error}"),
+        }
+    }
+    /// Reads the file
+    pub fn read_file(path: &Path) -> Option<String> {
+        fs::read_to_string(path).ok()
+    }
+    /// Writes the developer output, like a dumped layout, to the file
+    pub fn export(path: &str, contents: &str) -> Result<(), String> {
+        fs::write(path, contents).map_err(|error| error.to_string())
+    }
+    /// The seconds since the Unix epoch
+    pub fn unix_seconds() -> u64 {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).map_or(0, |time| time.as_secs())
+    }
+    /// All files in the directory, a missing directory has none
+    pub(super) fn list(directory: &Path) -> Vec<(PathBuf, SystemTime)> {
+        let Ok(entries) = fs::read_dir(directory) else { return Vec::new(); };
+        entries.flatten().filter_map(|entry| Some((entry.path(), entry.metadata().and_then(|m| m.modified()).ok()?))).collect()
+    }
+}
+
+/// Files and the settings in the local storage of the browser, stored under their path
+#[cfg(target_arch = "wasm32")]
+mod web {
+    use std::{path::{Path, PathBuf}, time::{Duration, SystemTime}};
+
+    use bevy::prelude::*;
+
+    /// The key of the settings in the local storage
+    const SETTINGS: &str = "bevypunk.settings";
+    const SETTINGS_ASIDE: &str = "bevypunk.settings.corrupt";
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+    /// Reads the settings
+    pub fn read_settings() -> Option<String> {
+        local_storage()?.get_item(SETTINGS).ok()?
+    }
+    /// Writes the settings
+    pub fn write_settings(source: &str) -> Result<(), String> {
+        let storage = local_storage().ok_or("local storage is not available")?;
+        storage.set_item(SETTINGS, source).map_err(|error| format!("{error:?}"))
+    }
+    /// Moves the corrupt settings to another key, so they are not overwritten
+    pub fn set_aside_settings() {
+        let Some(storage) = local_storage() else { return; };
+        if let Ok(Some(source)) = storage.get_item(SETTINGS) {
+            let _ = storage.set_item(SETTINGS_ASIDE, &source);
+            let _ = storage.remove_item(SETTINGS);
+            warn!("Moved the invalid settings to '{SETTINGS_ASIDE}'");
+        }
+    }
+    /// Reads the file
+    pub fn read_file(path: &Path) -> Option<String> {
+        local_storage()?.get_item(&key(path)).ok()?
+    }
+    /// Logs the developer output, like a dumped layout, there is no file to write it to
+    pub fn export(path: &str, contents: &str) -> Result<(), String> {
+        info!("Contents of '{path}':\n{contents}");
+        Ok(())
+    }
+    /// The seconds since the Unix epoch
+    pub fn unix_seconds() -> u64 {
+        (web_sys::js_sys::Date::now() / 1000.0) as u64
+    }
+    /// All files in the directory. The browser keeps no write times, they are read from the
+    /// `<path>.modified` key in milliseconds since the Unix epoch if the file has one.
+    pub(super) fn list(directory: &Path) -> Vec<(PathBuf, SystemTime)> {
+        let Some(storage) = local_storage() else { return Vec::new(); };
+        let prefix = format!("{}/", key(directory));
+        let length = storage.length().unwrap_or(0);
+        (0..length).filter_map(|index| storage.key(index).ok()?).filter(|key| key.starts_with(&prefix)).filter_map(|key| {
+            let millis = storage.get_item(&format!("{key}.modified")).ok()?.and_then(|millis| millis.parse().ok()).unwrap_or(0);
+            Some((PathBuf::from(key), SystemTime::UNIX_EPOCH + Duration::from_millis(millis)))
+        }).collect()
+    }
+    /// The local storage key of the path
+    fn key(path: &Path) -> String {
+        path.to_string_lossy().replace('\\', "/").trim_end_matches('/').to_string()
+    }
+}
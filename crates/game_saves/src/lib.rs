@@ -0,0 +1,112 @@
+use std::{path::{Path, PathBuf}, time::SystemTime};
+
+use bevy::{prelude::*, tasks::{block_on, futures_lite::future, IoTaskPool, Task}};
+use game_preferences::platform;
+
+
+/// Configures where the game looks for save files. Changing this resource will trigger a rescan.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct SaveDetection {
+    /// The directory containing the save slots
+    pub directory: PathBuf,
+    /// The file extension of a save slot
+    pub extension: String,
+}
+impl Default for SaveDetection {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("saves"),
+            extension: "sav".to_string(),
+        }
+    }
+}
+impl SaveDetection {
+    /// Creates new instance scanning the provided directory
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            ..Default::default()
+        }
+    }
+    /// Replace the default save file extension with a new one.
+    pub fn extension(mut self, extension: impl std::fmt::Display) -> Self {
+        self.extension = extension.to_string();
+        self
+    }
+    /// Walks the directory and returns the most recent save slot. This is blocking, so never call it on the main thread.
+    fn scan(directory: &Path, extension: &str) -> SaveInfo {
+        let mut info = SaveInfo::default();
+
+        // Missing directory simply means no saves
+        for (path, timestamp) in platform::files(directory, extension) {
+            let slot = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+
+            // The first non-empty line of the save holds the chapter name
+            let chapter = platform::read_file(&path)
+                .and_then(|content| content.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string))
+                .unwrap_or_else(|| slot.clone());
+
+            info.slots += 1;
+            if info.latest.as_ref().is_none_or(|latest| latest.timestamp < timestamp) {
+                info.latest = Some(SaveSlot { slot, timestamp, chapter });
+            }
+        }
+
+        info
+    }
+    /// This system starts a new scan on the IO task pool each time the resource changes.
+    fn start_system(mut commands: Commands, detection: Res<SaveDetection>) {
+        let directory = detection.directory.clone();
+        let extension = detection.extension.clone();
+        let task = IoTaskPool::get().spawn(async move { SaveDetection::scan(&directory, &extension) });
+        commands.insert_resource(SaveScanTask(task));
+    }
+    /// This system polls the running scan and inserts the [`SaveInfo`] once it is done.
+    fn poll_system(mut commands: Commands, mut task: ResMut<SaveScanTask>) {
+        if let Some(info) = block_on(future::poll_once(&mut task.0)) {
+            match &info.latest {
+                Some(latest) => info!("Found {} save slot(s), latest is '{}'", info.slots, latest.slot),
+                None => info!("No save slots found"),
+            }
+            commands.insert_resource(info);
+            commands.remove_resource::<SaveScanTask>();
+        }
+    }
+}
+
+/// Information about a single save slot
+#[derive(Clone, PartialEq, Debug)]
+pub struct SaveSlot {
+    /// The name of the slot, derived from the file name
+    pub slot: String,
+    /// When was the slot last written to
+    pub timestamp: SystemTime,
+    /// The chapter the player was in
+    pub chapter: String,
+}
+
+/// The result of the save scan. This resource does not exist until the scan completes.
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct SaveInfo {
+    /// The most recently written save slot
+    pub latest: Option<SaveSlot>,
+    /// The number of slots found
+    pub slots: usize,
+}
+
+/// The scan currently running in the background
+#[derive(Resource)]
+struct SaveScanTask(Task<SaveInfo>);
+
+
+/// Plugin scanning for save files in the background
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveDetection>();
+        app.add_systems(Update, (
+            SaveDetection::start_system.run_if(resource_changed::<SaveDetection>),
+            SaveDetection::poll_system.run_if(resource_exists::<SaveScanTask>),
+        ).chain());
+    }
+}
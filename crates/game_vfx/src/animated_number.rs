@@ -0,0 +1,213 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// How an [`AnimatedNumber`] is written, like `"$1,250.00"`
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct NumberFormat {
+    /// Text before the number
+    pub prefix: String,
+    /// Text after the number
+    pub suffix: String,
+    /// Digits after the decimal point
+    pub decimals: usize,
+    /// The separator between the thousands, if any
+    pub separator: Option<char>,
+}
+impl NumberFormat {
+    /// Creates new instance writing whole numbers
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Replace the default empty prefix with a new one.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+    /// Replace the default empty suffix with a new one.
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+    /// Replace the default no decimals with new ones.
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+    /// Replace the default no separator with a new one.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+    /// Writes the value rounded to the decimals
+    pub fn format(&self, value: f64) -> String {
+        let digits = format!("{:.*}", self.decimals, value.abs());
+        let (whole, fraction) = digits.split_once('.').map_or((digits.as_str(), None), |(whole, fraction)| (whole, Some(fraction)));
+
+        let mut text = self.prefix.clone();
+        // No minus for a value that rounds to zero
+        if value < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0') { text.push('-'); }
+        for (index, c) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index) % 3 == 0 { if let Some(separator) = self.separator { text.push(separator); } }
+            text.push(c);
+        }
+        if let Some(fraction) = fraction {
+            text.push('.');
+            text.push_str(fraction);
+        }
+        text.push_str(&self.suffix);
+        text
+    }
+}
+
+/// Insert this on a text node, like a score or a currency counter, to roll the [`Text2d`] to the value instead of snapping.
+/// Setting a new value rolls there over a duration growing with the change, up to the cap. Setting one
+/// mid roll continues from the number shown. In the odometer mode every digit scrolls in its own column,
+/// the columns are laid out right to left from the node origin, so anchor the node on its right.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Text2d)]
+pub struct AnimatedNumber {
+    /// The value the number rolls to
+    pub value: f64,
+    pub format: NumberFormat,
+    /// Seconds the roll takes for every unit of change
+    pub seconds_per_unit: f32,
+    /// The longest a roll takes in seconds, however large the change
+    pub max_duration: f32,
+    /// If every digit scrolls vertically in its own column
+    pub odometer: bool,
+    /// The roll from the shown number to the value
+    tween: Tween<f64>,
+    /// The spawned odometer columns from the right, each with the shown and the incoming glyph
+    columns: Vec<(Entity, Entity)>,
+}
+impl AnimatedNumber {
+    /// The shortest roll in seconds, so even small changes read as a roll
+    const MIN_DURATION: f32 = 0.15;
+    /// Width of an odometer column as a fraction of the font size
+    const ADVANCE: f32 = 0.62;
+    /// Creates new instance showing the value without a roll
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            format: NumberFormat::new(),
+            seconds_per_unit: 0.01,
+            max_duration: 1.2,
+            odometer: false,
+            tween: Tween::rest(value),
+            columns: Vec::new(),
+        }
+    }
+    /// Replace the default whole number format with a new one.
+    pub fn format(mut self, format: NumberFormat) -> Self {
+        self.format = format;
+        self
+    }
+    /// Replace the default cap of the roll duration with a new one.
+    pub fn max_duration(mut self, max_duration: f32) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+    /// Scroll every digit in its own column like an odometer.
+    pub fn odometer(mut self) -> Self {
+        self.odometer = true;
+        self
+    }
+    /// The number shown right now
+    pub fn displayed(&self) -> f64 {
+        self.tween.value()
+    }
+    /// This system rolls the numbers and writes them into their text or odometer columns.
+    fn system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut AnimatedNumber, &mut Text2d, &TextFont, &TextColor)>,
+        mut columns: Query<(&mut Text2d, &mut TextFont, &mut TextColor, &mut Transform, &mut Visibility), (With<OdometerColumn>, Without<AnimatedNumber>)>,
+        time: Res<Time>,
+    ) {
+        let mut recompute = false;
+        for (entity, mut number, mut text, font, color) in &mut query {
+            let number = number.bypass_change_detection();
+            if number.value != number.tween.to {
+                let change = (number.value - number.tween.value()).abs() as f32;
+                number.tween.retarget(number.value);
+                number.tween.duration = (change * number.seconds_per_unit).clamp(AnimatedNumber::MIN_DURATION, number.max_duration.max(AnimatedNumber::MIN_DURATION));
+                number.tween.easing = Easing::CubicOut;
+            }
+            let displayed = number.tween.tick(time.delta_secs());
+
+            if !number.odometer {
+                for (shown, incoming) in number.columns.drain(..) {
+                    commands.entity(shown).despawn_recursive();
+                    commands.entity(incoming).despawn_recursive();
+                }
+                let written = number.format.format(displayed);
+                if text.0 != written {
+                    text.0 = written;
+                    recompute = true;
+                }
+                continue;
+            }
+
+            // The digits are cut down instead of rounded, so each column rolls over at its own digit
+            let scale = 10f64.powi(number.format.decimals as i32);
+            let scaled = (displayed.abs() * scale).max(0.0);
+            let glyphs: Vec<char> = number.format.format((scaled.floor() / scale).copysign(displayed)).chars().collect();
+            if !text.0.is_empty() {
+                text.0.clear();
+                recompute = true;
+            }
+            while number.columns.len() < glyphs.len() {
+                let mut column = || commands.spawn((Name::new("Odometer Column"), OdometerColumn, Text2d::default(), Anchor::CenterRight, Visibility::Hidden)).set_parent(entity).id();
+                let pair = (column(), column());
+                number.columns.push(pair);
+            }
+
+            let advance = font.font_size * AnimatedNumber::ADVANCE;
+            let mut place = 0;
+            for (index, (shown, incoming)) in number.columns.iter().enumerate() {
+                let glyph = glyphs.len().checked_sub(index + 1).map(|at| glyphs[at]);
+                let (next, roll) = match glyph.and_then(|glyph| glyph.to_digit(10)) {
+                    Some(digit) => {
+                        // The lowest digit rolls all the time, the others while the column below passes from 9 to 0
+                        let fraction = (scaled / 10f64.powi(place)).fract() as f32;
+                        let roll = if place == 0 { fraction } else { (fraction * 10.0 - 9.0).clamp(0.0, 1.0) };
+                        place += 1;
+                        (char::from_digit((digit + 1) % 10, 10), roll)
+                    },
+                    None => (None, 0.0),
+                };
+                let x = -(index as f32) * advance;
+                let mut write = |column: Entity, glyph: Option<char>, y: f32, alpha: f32| {
+                    let Ok((mut text, mut column_font, mut column_color, mut transform, mut visibility)) = columns.get_mut(column) else { return; };
+                    let Some(glyph) = glyph.filter(|_| alpha > 0.0) else {
+                        if *visibility != Visibility::Hidden { *visibility = Visibility::Hidden; }
+                        return;
+                    };
+                    if text.0.chars().ne(std::iter::once(glyph)) { text.0 = glyph.to_string(); }
+                    if column_font.font != font.font || column_font.font_size != font.font_size { *column_font = font.clone(); }
+                    column_color.0 = color.0.with_alpha(color.0.alpha() * alpha);
+                    transform.translation = Vec3::new(x, y, 0.0);
+                    if *visibility != Visibility::Inherited { *visibility = Visibility::Inherited; }
+                };
+                write(*shown, glyph, roll * font.font_size, 1.0 - roll);
+                write(*incoming, next, (roll - 1.0) * font.font_size, roll);
+            }
+        }
+        if recompute { commands.trigger(RecomputeUiLayout); }
+    }
+}
+
+/// One glyph of an [`AnimatedNumber`] in the odometer mode
+#[derive(Component)]
+struct OdometerColumn;
+
+
+/// Plugin rolling the [`AnimatedNumber`] texts
+pub(crate) struct AnimatedNumberPlugin;
+impl Plugin for AnimatedNumberPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, AnimatedNumber::system.run_if(any_with_component::<AnimatedNumber>));
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+
+/// Insert this on a thin sprite node to make it blink like a text caret. Its owner positions it, every
+/// move holds it solid for a moment so it does not blink while the user is typing.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Sprite)]
+pub struct BlinkingCaret {
+    /// Seconds of one blink, shown for the first half and hidden for the second
+    pub period: f32,
+    /// If the alpha fades between shown and hidden instead of toggling
+    pub fade: bool,
+    /// Seconds the caret is held solid after it moves
+    pub hold: f32,
+    /// Keeps the caret solid, set from the reduced motion setting
+    pub solid: bool,
+    /// Seconds into the current blink
+    clock: f32,
+    /// Seconds left of the hold after the last move
+    held: f32,
+    last: Option<Vec3>,
+    /// The alpha of the sprite when shown
+    alpha: Option<f32>,
+}
+impl BlinkingCaret {
+    /// Creates new instance
+    pub fn new(period: f32) -> Self {
+        Self {
+            period,
+            fade: false,
+            hold: 0.5,
+            solid: false,
+            clock: 0.0,
+            held: 0.0,
+            last: None,
+            alpha: None,
+        }
+    }
+    /// Fade the alpha instead of toggling it.
+    pub fn fade(mut self) -> Self {
+        self.fade = true;
+        self
+    }
+    /// Replace the default hold after a move with a new one.
+    pub fn hold(mut self, hold: f32) -> Self {
+        self.hold = hold;
+        self
+    }
+    /// Holds the caret solid as if it moved, for owners that change the text without moving it
+    pub fn wake(&mut self) {
+        self.held = self.hold;
+        self.clock = 0.0;
+    }
+    /// Returns the visibility of the caret in range 0.0 to 1.0
+    fn opacity(&self) -> f32 {
+        if self.solid || self.held > 0.0 || self.period <= 0.0 { return 1.0; }
+        let t = self.clock / self.period;
+        if self.fade {
+            (std::f32::consts::TAU * t).cos() * 0.5 + 0.5
+        } else if t < 0.5 { 1.0 } else { 0.0 }
+    }
+    /// This system blinks the carets and holds them solid after they move.
+    fn system(mut query: Query<(&mut BlinkingCaret, &mut Sprite, &GlobalTransform)>, time: Res<Time>) {
+        for (mut caret, mut sprite, transform) in &mut query {
+            let alpha = *caret.alpha.get_or_insert(sprite.color.alpha());
+
+            let position = transform.translation();
+            if caret.last.is_some_and(|last| last != position) { caret.wake(); }
+            caret.last = Some(position);
+
+            if caret.held > 0.0 {
+                caret.held = (caret.held - time.delta_secs()).max(0.0);
+            } else if caret.period > 0.0 {
+                caret.clock = (caret.clock + time.delta_secs()) % caret.period;
+            }
+
+            let target = alpha * caret.opacity();
+            if sprite.color.alpha() != target { sprite.color.set_alpha(target); }
+        }
+    }
+}
+
+
+/// Plugin blinking the [`BlinkingCaret`] nodes
+pub(crate) struct CaretPlugin;
+impl Plugin for CaretPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, BlinkingCaret::system);
+    }
+}
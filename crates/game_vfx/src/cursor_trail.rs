@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use crate::*;
+
+
+/// Insert this on the cursor entity to leave a trail of fading sprites behind it while it moves
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct CursorTrail {
+    /// Turn the trail off without removing the component
+    pub enabled: bool,
+    /// Distance in pixels the cursor has to travel to drop the next particle
+    pub spacing: f32,
+    /// How long a particle takes to fade out in seconds
+    pub lifetime: f32,
+    /// Size of a fresh particle in pixels
+    pub size: f32,
+    /// Particles are reused once this many exist
+    pub capacity: usize,
+    pool: Vec<Entity>,
+    next: usize,
+    last: Option<Vec2>,
+}
+impl Default for CursorTrail {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            spacing: 6.0,
+            lifetime: 0.35,
+            size: 10.0,
+            capacity: 48,
+            pool: Vec::new(),
+            next: 0,
+            last: None,
+        }
+    }
+}
+impl CursorTrail {
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Replace the default lifetime with a new one.
+    pub fn lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+    /// Replace the default capacity with a new one.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+    /// This system drops particles along the path of the cursor, nothing is spawned while it stands still.
+    fn system(
+        mut commands: Commands,
+        theme: Res<UiTheme>,
+        mut trails: Query<(&mut CursorTrail, &GlobalTransform)>,
+        mut particles: Query<(&mut TrailParticle, &mut Transform, &mut Visibility)>,
+    ) {
+        for (mut trail, transform) in &mut trails {
+            let position = transform.translation();
+            if !trail.enabled {
+                trail.last = None;
+                continue;
+            }
+            let Some(last) = trail.last else {
+                trail.last = Some(position.truncate());
+                continue;
+            };
+
+            // Fill the gap with evenly spaced particles, so fast moves do not leave holes
+            let distance = last.distance(position.truncate());
+            if distance < trail.spacing { continue; }
+            let count = ((distance / trail.spacing) as usize).min(trail.capacity);
+            for i in 1..=count {
+                let point = last.lerp(position.truncate(), i as f32 / count as f32).extend(position.z - 1.0);
+                let particle = TrailParticle { age: 0.0, lifetime: trail.lifetime, size: trail.size };
+
+                // Reuse the oldest particle once the pool is full
+                if trail.pool.len() < trail.capacity {
+                    let entity = commands.spawn((
+                        particle,
+                        Sprite { color: theme.primary, custom_size: Some(Vec2::splat(trail.size)), ..default() },
+                        Transform::from_translation(point),
+                        Visibility::Visible,
+                    )).id();
+                    trail.pool.push(entity);
+                } else {
+                    let entity = trail.pool[trail.next];
+                    trail.next = (trail.next + 1) % trail.capacity;
+                    if let Ok((mut old, mut transform, mut visibility)) = particles.get_mut(entity) {
+                        *old = particle;
+                        transform.translation = point;
+                        *visibility = Visibility::Visible;
+                    }
+                }
+            }
+            trail.last = Some(position.truncate());
+        }
+    }
+}
+
+/// A particle of the [`CursorTrail`], hidden once faded and kept for reuse
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct TrailParticle {
+    age: f32,
+    lifetime: f32,
+    size: f32,
+}
+impl TrailParticle {
+    /// This system fades and shrinks the particles.
+    fn system(time: Res<Time>, theme: Res<UiTheme>, mut query: Query<(&mut TrailParticle, &mut Sprite, &mut Visibility)>) {
+        for (mut particle, mut sprite, mut visibility) in &mut query {
+            if *visibility == Visibility::Hidden { continue; }
+            particle.age += time.delta_secs();
+            let left = 1.0 - (particle.age / particle.lifetime.max(f32::EPSILON)).min(1.0);
+            if left <= 0.0 {
+                *visibility = Visibility::Hidden;
+                continue;
+            }
+            sprite.color = theme.primary.with_alpha(theme.primary.alpha() * left * 0.6);
+            sprite.custom_size = Some(Vec2::splat(particle.size * (0.4 + 0.6 * left)));
+        }
+    }
+}
+
+
+/// Plugin with the [`CursorTrail`] effect
+pub(crate) struct CursorTrailPlugin;
+impl Plugin for CursorTrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (CursorTrail::system, TrailParticle::system).chain());
+    }
+}
@@ -1,8 +1,28 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+use std::{any::TypeId, borrow::Cow, hash::{DefaultHasher, Hash, Hasher}};
 
-use bevy::{core_pipeline::bloom::Bloom, prelude::*};
+use bevy::{core_pipeline::bloom::Bloom, ecs::system::SystemParam, prelude::*};
 use bevy_lunex::*;
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
+
+mod animated_number;
+pub use animated_number::*;
+mod caret;
+pub use caret::*;
+mod cursor_trail;
+pub use cursor_trail::*;
+mod palette_swap;
+pub use palette_swap::*;
+mod shader_background;
+pub use shader_background::*;
+mod text_effect;
+pub use text_effect::*;
+mod text_shadow;
+pub use text_shadow::*;
+mod typewriter;
+pub use typewriter::*;
+mod ui_anim;
+pub use ui_anim::*;
 
 
 
@@ -20,6 +40,522 @@ impl BevypunkColorPalette for Color {
     const BEVYPUNK_BLUE: Color = Color::srgba(8./255., 226./255., 252./255., 1.0);
 }
 
+/// The color space used for interpolation
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ColorSpace {
+    /// Component-wise in sRGB, cheap but passes through muddy midpoints
+    Srgb,
+    /// Along the shortest way around the hue wheel
+    Hsl,
+    /// Perceptually uniform, keeps the brightness of the midpoints even
+    #[default]
+    Oklab,
+}
+
+/// Selects the [`ColorSpace`] used by animated widgets. Set it to [`ColorSpace::Srgb`] to keep the original look.
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ColorInterpolation(pub ColorSpace);
+
+/// Interpolation between two colors
+pub trait LerpColor {
+    /// Interpolates towards the other color in sRGB component space
+    fn lerp(&self, other: Self, t: f32) -> Self;
+    /// Interpolates towards the other color in HSL, taking the shorter way around the hue wheel
+    fn lerp_hsl(&self, other: Self, t: f32) -> Self;
+    /// Interpolates towards the other color in OKLab
+    fn lerp_oklab(&self, other: Self, t: f32) -> Self;
+    /// Interpolates towards the other color in the provided space
+    fn lerp_in(&self, other: Self, t: f32, space: ColorSpace) -> Self;
+}
+impl LerpColor for Color {
+    fn lerp(&self, other: Color, t: f32) -> Color {
+        Color::Srgba(self.to_srgba().mix(&other.to_srgba(), t))
+    }
+    fn lerp_hsl(&self, other: Color, t: f32) -> Color {
+        let (a, b) = (Hsla::from(*self), Hsla::from(other));
+
+        // Grays have no meaningful hue, so borrow the hue of the other color instead of sweeping through the wheel
+        let (hue_a, hue_b) = match (a.saturation == 0.0, b.saturation == 0.0) {
+            (true, false) => (b.hue, b.hue),
+            (false, true) => (a.hue, a.hue),
+            _ => (a.hue, b.hue),
+        };
+
+        // Wrap the difference into -180..180 degrees to always go the shorter way
+        let delta = (hue_b - hue_a + 540.0).rem_euclid(360.0) - 180.0;
+        Color::Hsla(Hsla::new(
+            (hue_a + delta * t).rem_euclid(360.0),
+            a.saturation + (b.saturation - a.saturation) * t,
+            a.lightness + (b.lightness - a.lightness) * t,
+            a.alpha + (b.alpha - a.alpha) * t,
+        ))
+    }
+    fn lerp_oklab(&self, other: Color, t: f32) -> Color {
+        Color::Oklaba(Oklaba::from(*self).mix(&Oklaba::from(other), t))
+    }
+    fn lerp_in(&self, other: Color, t: f32, space: ColorSpace) -> Color {
+        match space {
+            ColorSpace::Srgb => self.lerp(other, t),
+            ColorSpace::Hsl => self.lerp_hsl(other, t),
+            ColorSpace::Oklab => self.lerp_oklab(other, t),
+        }
+    }
+}
+
+/// Semantic colors of the user interface, defaults to the Bevypunk palette.
+/// Mutating this resource restyles every entity with [`ThemedColor`].
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct UiTheme {
+    /// Images and outlines of interactive widgets
+    pub primary: Color,
+    /// Widgets under the pointer
+    pub primary_hover: Color,
+    /// Regular text
+    pub text: Color,
+    /// Text of disabled widgets
+    pub text_dim: Color,
+    /// Hints and secondary highlights
+    pub accent: Color,
+    /// Backdrops behind the widgets
+    pub background: Color,
+    /// Destructive actions and errors
+    pub danger: Color,
+    /// Confirmations and warnings
+    pub warning: Color,
+}
+impl Default for UiTheme {
+    fn default() -> Self {
+        Self {
+            primary: Color::BEVYPUNK_RED,
+            primary_hover: Color::BEVYPUNK_YELLOW,
+            text: Color::BEVYPUNK_RED,
+            text_dim: Color::BEVYPUNK_RED_DIM,
+            accent: Color::BEVYPUNK_BLUE,
+            background: Color::BLACK,
+            danger: Color::BEVYPUNK_RED,
+            warning: Color::BEVYPUNK_YELLOW,
+        }
+    }
+}
+impl UiTheme {
+    /// The light built-in palette
+    pub fn light() -> Self {
+        Self {
+            primary: Color::srgb(0.78, 0.22, 0.18),
+            primary_hover: Color::srgb(0.95, 0.55, 0.0),
+            text: Color::srgb(0.20, 0.08, 0.08),
+            text_dim: Color::srgb(0.45, 0.35, 0.35),
+            accent: Color::srgb(0.0, 0.45, 0.60),
+            background: Color::srgb(0.93, 0.91, 0.88),
+            danger: Color::srgb(0.80, 0.10, 0.10),
+            warning: Color::srgb(0.85, 0.60, 0.0),
+        }
+    }
+    /// Interpolates all colors towards the other theme
+    pub fn lerp(&self, other: &UiTheme, t: f32, space: ColorSpace) -> Self {
+        Self {
+            primary: self.primary.lerp_in(other.primary, t, space),
+            primary_hover: self.primary_hover.lerp_in(other.primary_hover, t, space),
+            text: self.text.lerp_in(other.text, t, space),
+            text_dim: self.text_dim.lerp_in(other.text_dim, t, space),
+            accent: self.accent.lerp_in(other.accent, t, space),
+            background: self.background.lerp_in(other.background, t, space),
+            danger: self.danger.lerp_in(other.danger, t, space),
+            warning: self.warning.lerp_in(other.warning, t, space),
+        }
+    }
+    /// Replaces the color of this role
+    pub fn set(&mut self, role: ThemeRole, color: Color) {
+        match role {
+            ThemeRole::Primary => self.primary = color,
+            ThemeRole::PrimaryHover => self.primary_hover = color,
+            ThemeRole::Text => self.text = color,
+            ThemeRole::TextDim => self.text_dim = color,
+            ThemeRole::Accent => self.accent = color,
+            ThemeRole::Background => self.background = color,
+            ThemeRole::Danger => self.danger = color,
+            ThemeRole::Warning => self.warning = color,
+        }
+    }
+    /// Returns the color of this role
+    pub fn get(&self, role: ThemeRole) -> Color {
+        match role {
+            ThemeRole::Primary => self.primary,
+            ThemeRole::PrimaryHover => self.primary_hover,
+            ThemeRole::Text => self.text,
+            ThemeRole::TextDim => self.text_dim,
+            ThemeRole::Accent => self.accent,
+            ThemeRole::Background => self.background,
+            ThemeRole::Danger => self.danger,
+            ThemeRole::Warning => self.warning,
+        }
+    }
+}
+
+/// The built-in palettes
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ThemeId {
+    /// The Bevypunk palette
+    #[default]
+    Dark,
+    Light,
+}
+impl ThemeId {
+    /// Returns the palette
+    pub fn theme(&self) -> UiTheme {
+        match self {
+            ThemeId::Dark => UiTheme::default(),
+            ThemeId::Light => UiTheme::light(),
+        }
+    }
+    /// Returns the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeId::Dark => "Dark",
+            ThemeId::Light => "Light",
+        }
+    }
+    /// Returns the other palette
+    pub fn toggled(&self) -> ThemeId {
+        match self {
+            ThemeId::Dark => ThemeId::Light,
+            ThemeId::Light => ThemeId::Dark,
+        }
+    }
+}
+
+/// Color vision presets remapping the status colors of a palette to distinguishable hues.
+/// Widgets signalling a status should also show an icon or text, never rely on the color alone.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum PalettePreset {
+    /// Keep the colors of the palette
+    #[default]
+    Default,
+    /// Red-green, green weak
+    Deuteranopia,
+    /// Red-green, red weak
+    Protanopia,
+    /// Blue-yellow
+    Tritanopia,
+}
+impl PalettePreset {
+    /// All presets in the order they are cycled through
+    pub const ALL: [PalettePreset; 4] = [PalettePreset::Default, PalettePreset::Deuteranopia, PalettePreset::Protanopia, PalettePreset::Tritanopia];
+    /// Returns the palette with remapped status colors
+    pub fn apply(&self, theme: UiTheme) -> UiTheme {
+        // Picked from the Okabe-Ito palette, which stays distinguishable for each deficiency
+        match self {
+            PalettePreset::Default => theme,
+            PalettePreset::Deuteranopia => UiTheme { danger: Color::srgb(0.84, 0.37, 0.0), warning: Color::srgb(0.0, 0.45, 0.70), ..theme },
+            PalettePreset::Protanopia => UiTheme { danger: Color::srgb(0.90, 0.60, 0.0), warning: Color::srgb(0.34, 0.71, 0.91), ..theme },
+            PalettePreset::Tritanopia => UiTheme { danger: Color::srgb(0.80, 0.47, 0.65), warning: Color::srgb(0.0, 0.62, 0.45), accent: Color::srgb(0.0, 0.62, 0.45), ..theme },
+        }
+    }
+    /// Returns the display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            PalettePreset::Default => "Default",
+            PalettePreset::Deuteranopia => "Deuteranopia",
+            PalettePreset::Protanopia => "Protanopia",
+            PalettePreset::Tritanopia => "Tritanopia",
+        }
+    }
+    /// Returns the next preset
+    pub fn next(&self) -> PalettePreset {
+        let index = PalettePreset::ALL.iter().position(|preset| preset == self).unwrap_or(0);
+        PalettePreset::ALL[(index + 1) % PalettePreset::ALL.len()]
+    }
+}
+
+/// Send this event to crossfade the [`UiTheme`] to one of the built-in palettes
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SetTheme(pub ThemeId);
+
+/// Send this event to crossfade the [`UiTheme`] to a color vision preset
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SetPalette(pub PalettePreset);
+
+/// Tracks the crossfade between palettes started by [`SetTheme`]
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct ThemeCrossfade {
+    /// The palette being faded to, or already shown
+    pub current: ThemeId,
+    /// The color vision preset applied on top of the palette
+    pub palette: PalettePreset,
+    /// Duration of the crossfade in seconds
+    pub duration: f32,
+    dark: UiTheme,
+    from: UiTheme,
+    progress: f32,
+}
+impl Default for ThemeCrossfade {
+    fn default() -> Self {
+        ThemeCrossfade::new(ThemeId::default(), PalettePreset::default())
+    }
+}
+impl ThemeCrossfade {
+    /// Creates new instance already showing this palette
+    pub fn new(current: ThemeId, palette: PalettePreset) -> Self {
+        Self {
+            current,
+            palette,
+            duration: 0.4,
+            dark: ThemeId::Dark.theme(),
+            from: palette.apply(current.theme()),
+            progress: 1.0,
+        }
+    }
+    /// Returns the theme being faded to
+    pub fn target(&self) -> UiTheme {
+        let theme = match self.current {
+            ThemeId::Dark => self.dark.clone(),
+            ThemeId::Light => ThemeId::Light.theme(),
+        };
+        self.palette.apply(theme)
+    }
+    /// Replaces the colors of the dark palette, fading to them from the shown theme if it is active
+    pub fn set_dark(&mut self, dark: UiTheme, shown: &UiTheme) {
+        if self.dark == dark { return; }
+        self.dark = dark;
+        if self.current == ThemeId::Dark {
+            self.from = shown.clone();
+            self.progress = 0.0;
+        }
+    }
+    /// This system fades the theme towards the requested palette. Only the endpoint colors of the
+    /// states change, so the hover transitions of the widgets keep running on top of the fade.
+    fn system(
+        mut themes: EventReader<SetTheme>,
+        mut palettes: EventReader<SetPalette>,
+        mut crossfade: ResMut<ThemeCrossfade>,
+        mut theme: ResMut<UiTheme>,
+        interpolation: Res<ColorInterpolation>,
+        time: Res<Time>,
+    ) {
+        let (mut current, mut palette) = (crossfade.current, crossfade.palette);
+        for SetTheme(id) in themes.read() { current = *id; }
+        for SetPalette(preset) in palettes.read() { palette = *preset; }
+        if current != crossfade.current || palette != crossfade.palette {
+            // Start from the current colors, so switching back mid-fade does not jump
+            crossfade.from = theme.clone();
+            crossfade.current = current;
+            crossfade.palette = palette;
+            crossfade.progress = 0.0;
+        }
+        if crossfade.progress >= 1.0 { return; }
+        crossfade.progress = (crossfade.progress + time.delta_secs() / crossfade.duration.max(f32::EPSILON)).min(1.0);
+        *theme = crossfade.from.lerp(&crossfade.target(), crossfade.progress, interpolation.0);
+    }
+}
+
+/// A semantic color of the [`UiTheme`]
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ThemeRole {
+    Primary,
+    PrimaryHover,
+    Text,
+    TextDim,
+    Accent,
+    Background,
+    Danger,
+    Warning,
+}
+impl ThemeRole {
+    /// All roles in the order of the [`UiTheme`] fields
+    pub const ALL: [ThemeRole; 8] = [
+        ThemeRole::Primary, ThemeRole::PrimaryHover, ThemeRole::Text, ThemeRole::TextDim,
+        ThemeRole::Accent, ThemeRole::Background, ThemeRole::Danger, ThemeRole::Warning,
+    ];
+    /// Returns the field name used in the style file
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeRole::Primary => "primary",
+            ThemeRole::PrimaryHover => "primary_hover",
+            ThemeRole::Text => "text",
+            ThemeRole::TextDim => "text_dim",
+            ThemeRole::Accent => "accent",
+            ThemeRole::Background => "background",
+            ThemeRole::Danger => "danger",
+            ThemeRole::Warning => "warning",
+        }
+    }
+    /// Returns the role with the field name used in the style file
+    pub fn from_name(name: &str) -> Option<Self> {
+        ThemeRole::ALL.into_iter().find(|role| role.name() == name)
+    }
+}
+
+/// Use this instead of [`UiColor`] to take the colors from the [`UiTheme`].
+/// Each state has a role and an alpha multiplier, the resolved [`UiColor`] is kept up to date for you.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ThemedColor(pub Vec<(TypeId, ThemeRole, f32)>);
+impl ThemedColor {
+    /// Creates new instance from roles for multiple states
+    pub fn new(colors: Vec<(TypeId, ThemeRole, f32)>) -> Self {
+        Self(colors)
+    }
+    /// Creates new instance with a single role for the base state with modified alpha
+    pub fn alpha(role: ThemeRole, alpha: f32) -> Self {
+        Self(vec![(UiBase::id(), role, alpha)])
+    }
+    /// Returns the colors of the current theme
+    pub fn resolve(&self, theme: &UiTheme) -> UiColor {
+        UiColor::new(self.0.iter().map(|(state, role, alpha)| (*state, theme.get(*role).with_alpha(*alpha))).collect())
+    }
+    /// Returns the color of this state in the current theme
+    pub fn state(&self, theme: &UiTheme, state: TypeId) -> Option<Color> {
+        self.0.iter().find(|(id, ..)| *id == state).map(|(_, role, alpha)| theme.get(*role).with_alpha(*alpha))
+    }
+    /// Returns the color between the base and the hover state, for widgets animating the transition themselves
+    pub fn sample(&self, theme: &UiTheme, hover: f32, space: ColorSpace) -> Color {
+        let base = self.state(theme, UiBase::id()).unwrap_or(Color::WHITE);
+        match self.state(theme, UiHover::id()) {
+            Some(hovered) => base.lerp_in(hovered, hover, space),
+            None => base,
+        }
+    }
+    /// This system resolves the themed colors when they or the theme change. Hover transitions
+    /// in progress keep running, they only get new endpoint colors.
+    fn system(
+        mut commands: Commands,
+        resolver: ThemeResolver,
+        query: Query<(Entity, Ref<ThemedColor>)>,
+        changed: Query<(), Or<(Changed<ThemeOverride>, Changed<ThemeRules>)>>,
+        mut removed: RemovedComponents<ThemeOverride>,
+        mut removed_rules: RemovedComponents<ThemeRules>,
+    ) {
+        // Overrides apply to descendants too, so any change re-resolves everything
+        let overrides_changed = !changed.is_empty() || removed.read().count() > 0 || removed_rules.read().count() > 0;
+        for (entity, themed) in &query {
+            if resolver.is_changed() || overrides_changed || themed.is_changed() {
+                commands.entity(entity).insert(themed.resolve(&resolver.theme_for(entity)));
+            }
+        }
+    }
+}
+/// Replaces some of the [`UiTheme`] colors for this entity and its descendants, whatever the active theme is.
+/// Remove the component to go back to the theme.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ThemeOverride {
+    pub primary: Option<Color>,
+    pub primary_hover: Option<Color>,
+    pub text: Option<Color>,
+    pub text_dim: Option<Color>,
+    pub accent: Option<Color>,
+    pub background: Option<Color>,
+    pub danger: Option<Color>,
+    pub warning: Option<Color>,
+    /// If the override also applies to the descendants
+    pub descendants: bool,
+}
+impl Default for ThemeOverride {
+    fn default() -> Self {
+        Self {
+            primary: None,
+            primary_hover: None,
+            text: None,
+            text_dim: None,
+            accent: None,
+            background: None,
+            danger: None,
+            warning: None,
+            descendants: true,
+        }
+    }
+}
+impl ThemeOverride {
+    /// Creates new instance overriding nothing
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Override the color of this role with a new one.
+    pub fn set(mut self, role: ThemeRole, color: Color) -> Self {
+        match role {
+            ThemeRole::Primary => self.primary = Some(color),
+            ThemeRole::PrimaryHover => self.primary_hover = Some(color),
+            ThemeRole::Text => self.text = Some(color),
+            ThemeRole::TextDim => self.text_dim = Some(color),
+            ThemeRole::Accent => self.accent = Some(color),
+            ThemeRole::Background => self.background = Some(color),
+            ThemeRole::Danger => self.danger = Some(color),
+            ThemeRole::Warning => self.warning = Some(color),
+        }
+        self
+    }
+    /// Replace the default inheritance by descendants with a new one.
+    pub fn descendants(mut self, descendants: bool) -> Self {
+        self.descendants = descendants;
+        self
+    }
+    /// Returns the theme with the overridden colors replaced
+    pub fn apply(&self, theme: &UiTheme) -> UiTheme {
+        UiTheme {
+            primary: self.primary.unwrap_or(theme.primary),
+            primary_hover: self.primary_hover.unwrap_or(theme.primary_hover),
+            text: self.text.unwrap_or(theme.text),
+            text_dim: self.text_dim.unwrap_or(theme.text_dim),
+            accent: self.accent.unwrap_or(theme.accent),
+            background: self.background.unwrap_or(theme.background),
+            danger: self.danger.unwrap_or(theme.danger),
+            warning: self.warning.unwrap_or(theme.warning),
+        }
+    }
+}
+
+/// Colors assigned to this entity and its descendants by rules, like the stylesheet of the widgets.
+/// The [`ThemeOverride`] of the same entity is applied over them.
+#[derive(Component, Clone, PartialEq, Debug, Default)]
+pub struct ThemeRules(pub ThemeOverride);
+
+/// Resolves the [`UiTheme`] of an entity, taking the [`ThemeRules`] and [`ThemeOverride`] of it and its ancestors into account
+#[derive(SystemParam)]
+pub struct ThemeResolver<'w, 's> {
+    theme: Res<'w, UiTheme>,
+    swap: Option<Res<'w, PaletteSwapState>>,
+    overrides: Query<'w, 's, &'static ThemeOverride>,
+    rules: Query<'w, 's, &'static ThemeRules>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+impl ThemeResolver<'_, '_> {
+    /// Returns the theme for this entity, the nearest override wins
+    pub fn theme_for(&self, entity: Entity) -> Cow<'_, UiTheme> {
+        // Nodes not yet reached by a palette swap keep the old colors
+        let base = self.swap.as_ref().and_then(|swap| swap.pending(entity)).unwrap_or(&*self.theme);
+        if self.overrides.is_empty() && self.rules.is_empty() { return Cow::Borrowed(base); }
+
+        // The override of each entity comes before its rules, so it is applied after them
+        let mut chain = Vec::new();
+        if let Ok(own) = self.overrides.get(entity) { chain.push(own); }
+        if let Ok(ThemeRules(own)) = self.rules.get(entity) { chain.push(own); }
+        let mut current = entity;
+        while let Ok(parent) = self.parents.get(current) {
+            current = parent.get();
+            if let Ok(inherited) = self.overrides.get(current) {
+                if inherited.descendants { chain.push(inherited); }
+            }
+            if let Ok(ThemeRules(inherited)) = self.rules.get(current) {
+                if inherited.descendants { chain.push(inherited); }
+            }
+        }
+        if chain.is_empty() { return Cow::Borrowed(base); }
+
+        // Apply from the farthest ancestor, so the nearer overrides are applied last
+        let mut theme = base.clone();
+        for over in chain.into_iter().rev() { theme = over.apply(&theme); }
+        Cow::Owned(theme)
+    }
+    /// If the global theme changed since the last run or a palette swap is in progress
+    pub fn is_changed(&self) -> bool {
+        self.theme.is_changed() || self.swap.as_ref().is_some_and(|swap| swap.is_changed())
+    }
+}
+
+impl From<ThemeRole> for ThemedColor {
+    fn from(role: ThemeRole) -> Self {
+        ThemedColor::alpha(role, 1.0)
+    }
+}
+
 
 
 #[derive(Component)]
@@ -138,6 +674,10 @@ impl TextAnimator {
         self.duration = duration;
         self
     }
+    /// Returns the original string that is being animated.
+    pub fn text(&self) -> &str {
+        &self.string
+    }
     /// This system takes care of updating the TextAnimator in time.
     fn system(mut query: Query<(&mut Text2d, &mut TextAnimator)>, time: Res<Time>, mut commads: Commands) {
         for (mut text, mut animator) in &mut query {
@@ -160,12 +700,14 @@ impl TextAnimator {
 
 /// Simulates typing animation with an underscore cursor
 pub fn typing_animation(t: f32, text: &str) -> String {
-    let visible_chars = (t * text.len() as f32).floor() as usize;
-    let visible_chars = visible_chars.min(text.len());
+    // Count characters, not bytes, so non-Latin text does not get sliced mid-character
+    let len = text.chars().count();
+    let visible_chars = (t * len as f32).floor() as usize;
+    let visible_chars = visible_chars.min(len);
 
-    if visible_chars < text.len() {
+    if visible_chars < len {
         // Show typed characters plus cursor
-        format!("{}{}", &text[..visible_chars], "_")
+        format!("{}{}", text.chars().take(visible_chars).collect::<String>(), "_")
     } else {
         // All characters visible, show cursor at end
         text.to_string()
@@ -186,9 +728,10 @@ pub fn decryption_animation(t: f32, text: &str) -> String {
     // Define symbols used
     let symbols = "!@#$%^&*()_+-=[]{}|;:'\",.<>/?`~";
     let mut result = String::with_capacity(text.len());
+    let len = text.chars().count();
 
     for (i, c) in text.chars().enumerate() {
-        let char_progress = (t * text.len() as f32) - i as f32;
+        let char_progress = (t * len as f32) - i as f32;
 
         if char_progress < 0.0 {
             // Not yet started decrypting this character
@@ -213,7 +756,7 @@ pub fn decryption_animation(t: f32, text: &str) -> String {
 /// Creates a slide-in effect where characters come in from the sides
 pub fn slide_in_animation(t: f32, text: &str) -> String {
     let mut result = String::with_capacity(text.len());
-    let center = text.len() / 2;
+    let center = text.chars().count() / 2;
 
     for (i, c) in text.chars().enumerate() {
         let distance_from_center = if i < center {
@@ -242,7 +785,8 @@ pub fn slide_in_animation(t: f32, text: &str) -> String {
 /// Reveals characters in a scrambled order
 pub fn scrambled_reveal_animation(t: f32, text: &str) -> String {
     // Create a seeded RNG for consistent scrambling
-    let mut indices: Vec<usize> = (0..text.len()).collect();
+    let chars: Vec<char> = text.chars().collect();
+    let mut indices: Vec<usize> = (0..chars.len()).collect();
     let seed = 42; // Fixed seed for consistent scrambling
     let mut rng = StdRng::seed_from_u64(seed);
 
@@ -250,12 +794,12 @@ pub fn scrambled_reveal_animation(t: f32, text: &str) -> String {
     use rand::seq::SliceRandom;
     indices.shuffle(&mut rng);
 
-    let chars_to_reveal = (t * text.len() as f32).floor() as usize;
-    let mut result = vec![' '; text.len()];
+    let chars_to_reveal = (t * chars.len() as f32).floor() as usize;
+    let mut result = vec![' '; chars.len()];
 
     // Reveal characters in scrambled order
-    for i in indices.iter().take(chars_to_reveal.min(text.len())) {
-        result[*i] = text.chars().nth(*i).unwrap();
+    for i in indices.iter().take(chars_to_reveal.min(chars.len())) {
+        result[*i] = chars[*i];
     }
 
     result.into_iter().collect()
@@ -268,8 +812,99 @@ pub struct VFXPlugin;
 impl Plugin for VFXPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<UiTheme>()
+            .init_resource::<ThemeCrossfade>()
+            .init_resource::<ColorInterpolation>()
+            .init_resource::<ReducedMotion>()
+            .add_event::<SetTheme>()
+            .add_event::<SetPalette>()
+            .add_systems(Update, ThemeCrossfade::system)
+            .add_plugins((PaletteSwapPlugin, CursorTrailPlugin, TypewriterPlugin, CaretPlugin, ShaderBackgroundPlugin, AnimatedNumberPlugin, TextEffectPlugin, TextShadowPlugin))
+            .add_systems(PostUpdate, ThemedColor::system)
             .add_systems(Update, VFXBloomFlicker::system)
             .add_systems(Update, TextAnimator::system)
-            .add_systems(Update, AnimatedTextSlider::system);
+            .add_systems(Update, AnimatedTextSlider::system)
+            .add_event::<ColorAnimationFinished>()
+            .register_type::<AnimateColor>()
+            .add_systems(Update, (
+                AnimateColor::system::<Sprite>,
+                AnimateColor::system::<TextColor>,
+                Animate::<Color>::system::<Sprite>,
+                Animate::<Color>::system::<TextColor>,
+                Animate::<f32>::system::<Sprite>,
+                Animate::<f32>::system::<TextColor>,
+                Animate::<Vec2>::system::<Transform>,
+            ));
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::*;
+
+    const SPACES: [ColorSpace; 3] = [ColorSpace::Srgb, ColorSpace::Hsl, ColorSpace::Oklab];
+
+    #[track_caller]
+    fn assert_color_approx(actual: Color, expected: Color) {
+        let (actual, expected) = (actual.to_srgba().to_f32_array(), expected.to_srgba().to_f32_array());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-4, "Expected {expected:?}, got {actual:?}");
+        }
+    }
+
+    #[test]
+    fn lerp_starts_at_the_first_color() {
+        let (from, to) = (Color::BEVYPUNK_RED, Color::BEVYPUNK_BLUE.with_alpha(0.5));
+        for space in SPACES {
+            assert_color_approx(from.lerp_in(to, 0.0, space), from);
+        }
+    }
+
+    #[test]
+    fn lerp_ends_at_the_second_color() {
+        let (from, to) = (Color::BEVYPUNK_RED, Color::BEVYPUNK_BLUE.with_alpha(0.5));
+        for space in SPACES {
+            assert_color_approx(from.lerp_in(to, 1.0, space), to);
+        }
+    }
+
+    #[test]
+    fn srgb_midpoint_is_component_wise() {
+        let mid = Color::srgba(1.0, 0.0, 0.0, 1.0).lerp_in(Color::srgba(0.0, 0.0, 1.0, 0.0), 0.5, ColorSpace::Srgb);
+        assert_color_approx(mid, Color::srgba(0.5, 0.0, 0.5, 0.5));
+    }
+
+    #[test]
+    fn hsl_midpoint_takes_the_shorter_way_around_the_wheel() {
+        // Red is at 0 and blue at 240 degrees, so the shorter way passes magenta and not green
+        let mid = Color::srgb(1.0, 0.0, 0.0).lerp_in(Color::srgb(0.0, 0.0, 1.0), 0.5, ColorSpace::Hsl);
+        assert_color_approx(mid, Color::srgb(1.0, 0.0, 1.0));
+
+        // Crossing zero wraps the hue instead of sweeping back through it
+        let mid = Color::hsl(350.0, 1.0, 0.5).lerp_in(Color::hsl(10.0, 1.0, 0.5), 0.5, ColorSpace::Hsl);
+        assert_color_approx(mid, Color::hsl(0.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn hsl_midpoint_with_gray_keeps_the_hue() {
+        let mid = Color::hsl(120.0, 1.0, 0.5).lerp_in(Color::hsl(0.0, 0.0, 0.5), 0.5, ColorSpace::Hsl);
+        assert_color_approx(mid, Color::hsl(120.0, 0.5, 0.5));
+    }
+
+    #[test]
+    fn oklab_midpoint_is_perceptually_halfway() {
+        let mid = Oklaba::from(Color::BLACK.lerp_in(Color::WHITE, 0.5, ColorSpace::Oklab));
+        assert!((mid.lightness - 0.5).abs() < 1e-3, "Expected half the lightness, got {}", mid.lightness);
+        assert!(mid.a.abs() < 1e-3 && mid.b.abs() < 1e-3, "Expected a gray, got {mid:?}");
+    }
+
+    #[test]
+    fn lerp_in_matches_the_space_methods() {
+        let (from, to) = (Color::BEVYPUNK_RED, Color::BEVYPUNK_YELLOW);
+        assert_color_approx(from.lerp_in(to, 0.5, ColorSpace::Srgb), from.lerp(to, 0.5));
+        assert_color_approx(from.lerp_in(to, 0.5, ColorSpace::Hsl), from.lerp_hsl(to, 0.5));
+        assert_color_approx(from.lerp_in(to, 0.5, ColorSpace::Oklab), from.lerp_oklab(to, 0.5));
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_lunex::*;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::*;
+
+
+/// How the colors of a [`PaletteSwap`] travel across the nodes
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SwapStyle {
+    /// The new colors spread in a circle expanding from the screen center
+    #[default]
+    RadialWipe,
+    /// Every node flickers between the palettes at its own random moment
+    StaggeredFlicker,
+}
+
+/// Send this event to swap the whole [`UiTheme`] with a dramatic effect, for example at the start of a live event
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct PaletteSwap {
+    pub to: UiTheme,
+    pub style: SwapStyle,
+}
+impl PaletteSwap {
+    /// Creates new instance
+    pub fn new(to: UiTheme, style: SwapStyle) -> Self {
+        Self { to, style }
+    }
+}
+
+/// The [`PaletteSwap`] in progress. The [`UiTheme`] already holds the new colors,
+/// nodes that existed before the swap keep the old ones until their moment comes.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct PaletteSwapState {
+    pub style: SwapStyle,
+    /// Duration of the whole swap in seconds
+    pub duration: f32,
+    /// Time since the start in seconds
+    pub elapsed: f32,
+    from: UiTheme,
+    offsets: HashMap<Entity, f32>,
+}
+impl PaletteSwapState {
+    /// Duration of the swap in seconds
+    const DURATION: f32 = 1.2;
+    /// How long a node flickers before settling on the new colors
+    const FLICKER: f32 = 0.3;
+    /// How long each flicker shows one of the palettes
+    const FLICKER_STEP: f32 = 0.05;
+    /// Returns the old theme if this entity was not reached by the swap yet
+    pub fn pending(&self, entity: Entity) -> Option<&UiTheme> {
+        // Nodes spawned after the start are not tracked and get the new colors right away
+        let offset = *self.offsets.get(&entity)?;
+        let since = self.elapsed - offset;
+        let old = match self.style {
+            SwapStyle::RadialWipe => since < 0.0,
+            SwapStyle::StaggeredFlicker => since < 0.0 || (since < Self::FLICKER && (since / Self::FLICKER_STEP) as u32 % 2 == 1),
+        };
+        old.then_some(&self.from)
+    }
+    /// If every node shows the new colors
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+    /// This system starts the swaps and computes the moment each node changes its colors.
+    fn start_system(
+        mut commands: Commands,
+        mut events: EventReader<PaletteSwap>,
+        mut theme: ResMut<UiTheme>,
+        swap: Option<Res<PaletteSwapState>>,
+        nodes: Query<(Entity, &GlobalTransform), With<UiLayout>>,
+    ) {
+        let Some(event) = events.read().last() else { return; };
+
+        // Nodes not reached by an interrupted swap start from the colors they show now
+        let from = match &swap {
+            Some(swap) if !swap.is_done() => swap.from.clone(),
+            _ => theme.clone(),
+        };
+
+        let positions: Vec<(Entity, Vec2)> = nodes.iter().map(|(entity, transform)| (entity, transform.translation().truncate())).collect();
+        let spread = Self::DURATION - Self::FLICKER;
+        let offsets = match event.style {
+            SwapStyle::RadialWipe => {
+                // The center of the laid out nodes is the center of the screen
+                let (min, max) = positions.iter().fold((Vec2::MAX, Vec2::MIN), |(min, max), (_, position)| (min.min(*position), max.max(*position)));
+                let center = (min + max) / 2.0;
+                let radius = positions.iter().map(|(_, position)| position.distance(center)).fold(f32::EPSILON, f32::max);
+                positions.into_iter().map(|(entity, position)| (entity, spread * position.distance(center) / radius)).collect()
+            },
+            SwapStyle::StaggeredFlicker => {
+                positions.into_iter().map(|(entity, _)| (entity, StdRng::seed_from_u64(entity.to_bits()).random_range(0.0..spread))).collect()
+            },
+        };
+
+        *theme = event.to.clone();
+        commands.insert_resource(PaletteSwapState {
+            style: event.style,
+            duration: Self::DURATION,
+            elapsed: 0.0,
+            from,
+            offsets,
+        });
+    }
+    /// This system advances the swap and removes it once every node has the new colors.
+    fn tick_system(mut commands: Commands, mut swap: ResMut<PaletteSwapState>, time: Res<Time>) {
+        // The last frame is still applied, the resolver re-resolves only while the state changes
+        if swap.is_done() {
+            commands.remove_resource::<PaletteSwapState>();
+            return;
+        }
+        swap.elapsed = (swap.elapsed + time.delta_secs()).min(swap.duration);
+    }
+}
+
+
+/// Plugin with the [`PaletteSwap`] effect
+pub(crate) struct PaletteSwapPlugin;
+impl Plugin for PaletteSwapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PaletteSwap>();
+        app.add_systems(Update, (
+            PaletteSwapState::start_system.run_if(on_event::<PaletteSwap>),
+            PaletteSwapState::tick_system.run_if(resource_exists::<PaletteSwapState>),
+        ).chain().after(ThemeCrossfade::system));
+    }
+}
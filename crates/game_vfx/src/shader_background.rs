@@ -0,0 +1,168 @@
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use bevy::{
+    asset::LoadState,
+    prelude::*,
+    render::{
+        render_resource::{AsBindGroup, CachedPipelineState, PipelineCache, PipelineDescriptor, ShaderRef},
+        Render, RenderApp,
+    },
+    sprite::{Material2d, Material2dPlugin},
+};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// Insert this on a full-window node, like the menu background, to fill it with flowing neon lines drawn by a shader.
+/// The colors come from the roles of the [`UiTheme`], so switching the theme recolors it live. If the
+/// shader fails to load or compile, like on older GL for the web, the fallback image is shown instead.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ShaderBackground {
+    /// How fast the lines flow, 1.0 is the default pace
+    pub speed: f32,
+    /// The role of the lines
+    pub color_a: ThemeRole,
+    /// The role of the haze behind the lines
+    pub color_b: ThemeRole,
+    /// The static image shown if the shader can not run
+    pub fallback: Option<Handle<Image>>,
+    /// The animation time, kept here so changing the speed does not jump
+    time: f32,
+}
+impl ShaderBackground {
+    /// Creates new instance
+    pub fn new(color_a: ThemeRole, color_b: ThemeRole) -> Self {
+        Self { speed: 1.0, color_a, color_b, fallback: None, time: 0.0 }
+    }
+    /// Replace the default speed with a new one.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+    /// Show the image if the shader can not run.
+    pub fn fallback(mut self, image: Handle<Image>) -> Self {
+        self.fallback = Some(image);
+        self
+    }
+    /// This system gives new backgrounds their quad and material.
+    fn build_system(
+        mut commands: Commands,
+        query: Query<Entity, (Added<ShaderBackground>, Without<ShaderBackgroundFallback>)>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<ShaderBackgroundMaterial>>,
+    ) {
+        for entity in &query {
+            commands.entity(entity).insert((
+                Mesh2d(meshes.add(Rectangle::default())),
+                MeshMaterial2d(materials.add(ShaderBackgroundMaterial::default())),
+            ));
+        }
+    }
+    /// This system advances the time, resolves the theme colors and sizes the quad to the node.
+    fn system(
+        mut query: Query<(Entity, &mut ShaderBackground, &MeshMaterial2d<ShaderBackgroundMaterial>, &Mesh2d, Ref<Dimension>)>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<ShaderBackgroundMaterial>>,
+        resolver: ThemeResolver,
+        time: Res<Time>,
+    ) {
+        for (entity, mut background, material, mesh, dimension) in &mut query {
+            background.time += time.delta_secs() * background.speed;
+            if dimension.is_changed() {
+                if let Some(mesh) = meshes.get_mut(&mesh.0) { *mesh = Rectangle::from_size(dimension.0).into(); }
+            }
+
+            let Some(material) = materials.get_mut(&material.0) else { continue; };
+            let theme = resolver.theme_for(entity);
+            material.color_a = theme.get(background.color_a).into();
+            material.color_b = theme.get(background.color_b).into();
+            material.params = Vec4::new(background.time, dimension.0.x / dimension.0.y.max(1.0), 0.0, 0.0);
+        }
+    }
+    /// This system swaps the quads for the fallback image once the shader failed.
+    fn fallback_system(
+        mut commands: Commands,
+        query: Query<(Entity, &ShaderBackground), Without<ShaderBackgroundFallback>>,
+        status: Res<ShaderBackgroundStatus>,
+        asset_server: Res<AssetServer>,
+        mut warned: Local<bool>,
+    ) {
+        let failed = status.failed.load(Ordering::Relaxed) || matches!(asset_server.load_state(&status.shader), LoadState::Failed(_));
+        if !failed { return; }
+        if !*warned && !query.is_empty() {
+            warn!("ShaderBackground could not run its shader, the fallback image is shown instead");
+            *warned = true;
+        }
+        for (entity, background) in &query {
+            let mut entity = commands.entity(entity);
+            entity.remove::<(Mesh2d, MeshMaterial2d<ShaderBackgroundMaterial>)>().insert(ShaderBackgroundFallback);
+            if let Some(image) = &background.fallback { entity.insert(Sprite::from_image(image.clone())); }
+        }
+    }
+}
+
+/// Marks a [`ShaderBackground`] showing its fallback image
+#[derive(Component)]
+struct ShaderBackgroundFallback;
+
+/// The material of the [`ShaderBackground`] quads
+#[derive(Asset, TypePath, AsBindGroup, Clone, Debug, Default)]
+pub struct ShaderBackgroundMaterial {
+    #[uniform(0)]
+    pub color_a: LinearRgba,
+    #[uniform(1)]
+    pub color_b: LinearRgba,
+    /// The time and the aspect ratio
+    #[uniform(2)]
+    pub params: Vec4,
+}
+impl ShaderBackgroundMaterial {
+    const SHADER: &'static str = "shaders/shader_background.wgsl";
+}
+impl Material2d for ShaderBackgroundMaterial {
+    fn fragment_shader() -> ShaderRef {
+        ShaderBackgroundMaterial::SHADER.into()
+    }
+}
+
+/// If the shader of the [`ShaderBackground`] failed, shared with the render world that compiles it
+#[derive(Resource, Clone)]
+struct ShaderBackgroundStatus {
+    shader: Handle<Shader>,
+    failed: Arc<AtomicBool>,
+}
+impl ShaderBackgroundStatus {
+    /// This system looks for the pipelines of the shader that failed to compile.
+    fn pipeline_system(cache: Res<PipelineCache>, status: Res<ShaderBackgroundStatus>) {
+        if status.failed.load(Ordering::Relaxed) { return; }
+        let failed = cache.pipelines().any(|pipeline| {
+            let PipelineDescriptor::RenderPipelineDescriptor(descriptor) = &pipeline.descriptor else { return false; };
+            matches!(pipeline.state, CachedPipelineState::Err(_)) && descriptor.fragment.as_ref().is_some_and(|fragment| fragment.shader.id() == status.shader.id())
+        });
+        if failed { status.failed.store(true, Ordering::Relaxed); }
+    }
+}
+
+
+/// Plugin with the [`ShaderBackground`] material
+pub(crate) struct ShaderBackgroundPlugin;
+impl Plugin for ShaderBackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<ShaderBackgroundMaterial>::default());
+        let status = ShaderBackgroundStatus {
+            shader: app.world().resource::<AssetServer>().load(ShaderBackgroundMaterial::SHADER),
+            failed: Arc::new(AtomicBool::new(false)),
+        };
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(status.clone());
+            render_app.add_systems(Render, ShaderBackgroundStatus::pipeline_system);
+        }
+        app.insert_resource(status);
+        app.add_systems(Update, (
+            ShaderBackground::fallback_system,
+            ShaderBackground::build_system,
+            ShaderBackground::system,
+        ).chain().run_if(any_with_component::<ShaderBackground>));
+    }
+}
@@ -0,0 +1,174 @@
+use bevy::{prelude::*, sprite::Anchor, text::TextBounds};
+
+use crate::*;
+
+
+/// How the glyphs of a [`TextEffect`] move
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TextEffectKind {
+    /// Up and down in a wave running along the text
+    #[default]
+    Wave,
+    /// Hopping up from the baseline one after another
+    Bounce,
+    /// Cycling through the hues instead of moving
+    Rainbow,
+}
+
+/// Insert this on a text node, like a splash heading, to animate each glyph of its [`Text2d`] with a phase by its index.
+/// Every glyph is drawn by its own overlay child laying out the whole text, so each one sits exactly where
+/// the plain text puts it and the width never changes. The overlays are rebuilt when the text changes,
+/// like on a language switch. Under reduced motion the glyphs rest in place.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Text2d)]
+pub struct TextEffect {
+    pub kind: TextEffectKind,
+    /// How far the glyphs move, as a fraction of the font size
+    pub amplitude: f32,
+    /// Cycles per second
+    pub speed: f32,
+    /// If the glyphs rest, like under reduced motion
+    pub still: bool,
+    /// Seconds the effect has been running
+    clock: f32,
+}
+impl TextEffect {
+    /// The phase between two glyphs in cycles
+    const PHASE: f32 = 0.08;
+    /// Creates new instance
+    pub fn new(kind: TextEffectKind) -> Self {
+        Self { kind, amplitude: 0.15, speed: 1.0, still: false, clock: 0.0 }
+    }
+    /// Replace the default amplitude with a new one.
+    pub fn amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+    /// Replace the default speed with a new one.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+    /// This system hides the plain text and spawns a glyph overlay for every visible character, again when the text changed.
+    #[allow(clippy::type_complexity)]
+    fn build_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &Text2d, &TextFont, &TextLayout, &Anchor, Option<&TextBounds>, &mut TextColor, Option<&TextEffectGlyphs>), With<TextEffect>>,
+    ) {
+        for (entity, text, font, layout, anchor, bounds, mut color, glyphs) in &mut query {
+            if glyphs.is_some_and(|glyphs| glyphs.text == text.0) { continue; }
+
+            // Keep the plain text for the layout, only transparent
+            let base = glyphs.map_or(color.0, |glyphs| glyphs.color);
+            if color.0.alpha() != 0.0 { color.0.set_alpha(0.0); }
+            if let Some(glyphs) = glyphs {
+                for (overlay, _) in &glyphs.overlays { commands.entity(*overlay).despawn_recursive(); }
+            }
+
+            let mut overlays = Vec::new();
+            commands.entity(entity).with_children(|ui| {
+                for (at, glyph) in text.0.char_indices().filter(|(_, glyph)| !glyph.is_whitespace()) {
+                    let end = at + glyph.len_utf8();
+                    let mut span = Entity::PLACEHOLDER;
+                    let mut overlay = ui.spawn((
+                        Name::new("Text Effect Glyph"),
+                        Text2d::new(&text.0[..at]),
+                        font.clone(),
+                        *layout,
+                        *anchor,
+                        TextColor(Color::NONE),
+                        PickingBehavior::IGNORE,
+                    ));
+                    if let Some(bounds) = bounds { overlay.insert(*bounds); }
+                    overlay.with_children(|ui| {
+                        span = ui.spawn((TextSpan::new(&text.0[at..end]), font.clone(), TextColor(base))).id();
+                        ui.spawn((TextSpan::new(&text.0[end..]), font.clone(), TextColor(Color::NONE)));
+                    });
+                    overlays.push((overlay.id(), span));
+                }
+            });
+            commands.entity(entity).insert(TextEffectGlyphs { text: text.0.clone(), color: base, overlays });
+        }
+    }
+    /// This system moves or recolors the glyphs by their phase and follows the color and the font of the plain text.
+    #[allow(clippy::type_complexity)]
+    fn system(
+        mut query: Query<(&mut TextEffect, &mut TextEffectGlyphs, &mut TextColor, Ref<TextFont>)>,
+        mut overlays: Query<(&mut Transform, &mut TextFont), (With<Text2d>, Without<TextEffectGlyphs>)>,
+        mut spans: Query<(&mut TextColor, &mut TextFont), (With<TextSpan>, Without<Text2d>, Without<TextEffectGlyphs>)>,
+        time: Res<Time>,
+    ) {
+        for (mut effect, mut glyphs, mut color, font) in &mut query {
+            // A new color set on the plain text, like by a hover animation, is taken over by the glyphs
+            if color.0.alpha() != 0.0 {
+                glyphs.color = color.0;
+                color.0.set_alpha(0.0);
+            }
+            if !effect.still { effect.clock += time.delta_secs() * effect.speed; }
+            let amplitude = effect.amplitude * font.font_size;
+
+            for (index, (overlay, span)) in glyphs.overlays.iter().enumerate() {
+                let phase = (effect.clock - index as f32 * TextEffect::PHASE) * std::f32::consts::TAU;
+                let offset = match effect.kind {
+                    _ if effect.still => 0.0,
+                    TextEffectKind::Wave => phase.sin() * amplitude,
+                    TextEffectKind::Bounce => phase.sin().abs() * amplitude,
+                    TextEffectKind::Rainbow => 0.0,
+                };
+                if let Ok((mut transform, mut overlay_font)) = overlays.get_mut(*overlay) {
+                    if transform.translation.y != offset { transform.translation.y = offset; }
+                    if font.is_changed() { *overlay_font = font.clone(); }
+                }
+
+                let Ok((mut span_color, mut span_font)) = spans.get_mut(*span) else { continue; };
+                if font.is_changed() { *span_font = font.clone(); }
+                let glyph_color = match effect.kind {
+                    TextEffectKind::Rainbow => {
+                        let base = Hsla::from(glyphs.color);
+                        Color::Hsla(Hsla::new(
+                            (base.hue + effect.clock.fract() * 360.0 + index as f32 * TextEffect::PHASE * 360.0).rem_euclid(360.0),
+                            base.saturation.max(0.8),
+                            base.lightness.clamp(0.4, 0.7),
+                            base.alpha,
+                        ))
+                    },
+                    _ => glyphs.color,
+                };
+                if span_color.0 != glyph_color { span_color.0 = glyph_color; }
+            }
+        }
+    }
+    /// This system shows the plain text again and removes the glyphs once the effect was removed.
+    fn cleanup_system(mut commands: Commands, mut query: Query<(Entity, &TextEffectGlyphs, &mut TextColor), Without<TextEffect>>) {
+        for (entity, glyphs, mut color) in &mut query {
+            color.0 = glyphs.color;
+            for (overlay, _) in &glyphs.overlays { commands.entity(*overlay).despawn_recursive(); }
+            commands.entity(entity).remove::<TextEffectGlyphs>();
+        }
+    }
+}
+
+/// The glyph overlays of a [`TextEffect`]
+#[derive(Component)]
+struct TextEffectGlyphs {
+    /// The text the overlays were built for
+    text: String,
+    /// The color of the plain text, drawn by the glyphs while it is transparent
+    color: Color,
+    /// The overlay of every glyph with the span drawing the glyph
+    overlays: Vec<(Entity, Entity)>,
+}
+
+
+/// Plugin animating the [`TextEffect`] glyphs
+pub(crate) struct TextEffectPlugin;
+impl Plugin for TextEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<TextEffectKind>();
+        app.add_systems(Update, (
+            TextEffect::cleanup_system,
+            TextEffect::build_system,
+            TextEffect::system,
+        ).chain());
+    }
+}
@@ -0,0 +1,205 @@
+use bevy::{prelude::*, sprite::Anchor, text::TextBounds};
+
+use crate::*;
+
+
+/// Insert this on a text node, like a button label, to draw a copy of its [`Text2d`] offset behind it.
+/// The copy mirrors the text, the font and the size of the node and is updated when they change.
+/// Only the text of the node itself is copied, its spans are not.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Text2d)]
+pub struct TextShadow {
+    /// How far the shadow is moved, as a fraction of the font size
+    pub offset: Vec2,
+    pub color: Color,
+    /// If the shadow takes the color of the text, like during its hover animation, only keeping its own alpha
+    pub follow_color: bool,
+}
+impl Default for TextShadow {
+    fn default() -> Self {
+        Self { offset: Vec2::new(0.05, -0.05), color: Color::srgba(0.0, 0.0, 0.0, 0.6), follow_color: false }
+    }
+}
+impl TextShadow {
+    /// Creates new instance
+    pub fn new(offset: Vec2, color: impl Into<Color>) -> Self {
+        Self { offset, color: color.into(), follow_color: false }
+    }
+    /// Replace the default static color with the color of the text.
+    pub fn follow_color(mut self, follow_color: bool) -> Self {
+        self.follow_color = follow_color;
+        self
+    }
+    /// The offsets of the copies as a fraction of the font size
+    fn offsets(&self) -> Vec<Vec2> {
+        vec![self.offset]
+    }
+}
+
+/// Insert this on a text node to outline its [`Text2d`] with copies moved around it in 4 directions, or 8 when smooth.
+/// The copies mirror the text, the font and the size of the node and are updated when they change.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Text2d)]
+pub struct TextOutline {
+    /// How thick the outline is, as a fraction of the font size
+    pub width: f32,
+    pub color: Color,
+    /// If the diagonals are drawn too, for rounder corners
+    pub smooth: bool,
+    /// If the outline takes the color of the text, only keeping its own alpha
+    pub follow_color: bool,
+}
+impl Default for TextOutline {
+    fn default() -> Self {
+        Self { width: 0.04, color: Color::srgba(0.0, 0.0, 0.0, 0.8), smooth: false, follow_color: false }
+    }
+}
+impl TextOutline {
+    /// Creates new instance
+    pub fn new(width: f32, color: impl Into<Color>) -> Self {
+        Self { width, color: color.into(), ..default() }
+    }
+    /// Replace the default 4 copies with 8.
+    pub fn smooth(mut self, smooth: bool) -> Self {
+        self.smooth = smooth;
+        self
+    }
+    /// Replace the default static color with the color of the text.
+    pub fn follow_color(mut self, follow_color: bool) -> Self {
+        self.follow_color = follow_color;
+        self
+    }
+    /// The offsets of the copies as a fraction of the font size
+    fn offsets(&self) -> Vec<Vec2> {
+        let directions: &[Vec2] = if self.smooth {
+            &[Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y, Vec2::ONE, Vec2::NEG_ONE, Vec2::new(1.0, -1.0), Vec2::new(-1.0, 1.0)]
+        } else {
+            &[Vec2::X, Vec2::NEG_X, Vec2::Y, Vec2::NEG_Y]
+        };
+        directions.iter().map(|direction| direction.normalize() * self.width).collect()
+    }
+}
+
+/// The copies behind a text with a [`TextShadow`] or a [`TextOutline`]
+#[derive(Component, Default)]
+struct TextCopies {
+    shadow: Vec<(Entity, Vec2)>,
+    outline: Vec<(Entity, Vec2)>,
+}
+impl TextCopies {
+    /// How far behind the text the copies are, the shadow is behind the outline
+    const DEPTH: f32 = 0.001;
+    /// The copy color for the text color
+    fn color(color: Color, text: Color, follow: bool) -> Color {
+        if follow { text.with_alpha(text.alpha() * color.alpha()) } else { color }
+    }
+    /// This system spawns the copies of new shadows and outlines, again when their settings changed.
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    fn build_system(
+        mut commands: Commands,
+        query: Query<(Entity, &Text2d, &TextFont, &TextLayout, &Anchor, Option<&TextBounds>, &TextColor, Option<Ref<TextShadow>>, Option<Ref<TextOutline>>, Option<&TextCopies>), Or<(Changed<TextShadow>, Changed<TextOutline>)>>,
+    ) {
+        for (entity, text, font, layout, anchor, bounds, color, shadow, outline, copies) in &query {
+            let mut spawn = |offsets: Vec<Vec2>, copy_color: Color, depth: f32, old: Option<&Vec<(Entity, Vec2)>>| {
+                for (copy, _) in old.into_iter().flatten() { commands.entity(*copy).despawn_recursive(); }
+                let mut spawned = Vec::with_capacity(offsets.len());
+                commands.entity(entity).with_children(|ui| {
+                    for offset in offsets {
+                        let mut copy = ui.spawn((
+                            Name::new("Text Copy"),
+                            Text2d::new(&text.0),
+                            font.clone(),
+                            *layout,
+                            *anchor,
+                            TextColor(copy_color),
+                            Transform::from_translation((offset * font.font_size).extend(-depth)),
+                            PickingBehavior::IGNORE,
+                            TextCopy,
+                        ));
+                        if let Some(bounds) = bounds { copy.insert(*bounds); }
+                        spawned.push((copy.id(), offset));
+                    }
+                });
+                spawned
+            };
+
+            let mut rebuilt = TextCopies::default();
+            match &shadow {
+                Some(shadow) if shadow.is_changed() => {
+                    let copy_color = TextCopies::color(shadow.color, color.0, shadow.follow_color);
+                    rebuilt.shadow = spawn(shadow.offsets(), copy_color, 2.0 * TextCopies::DEPTH, copies.map(|copies| &copies.shadow));
+                },
+                _ => rebuilt.shadow = copies.map_or(Vec::new(), |copies| copies.shadow.clone()),
+            }
+            match &outline {
+                Some(outline) if outline.is_changed() => {
+                    let copy_color = TextCopies::color(outline.color, color.0, outline.follow_color);
+                    rebuilt.outline = spawn(outline.offsets(), copy_color, TextCopies::DEPTH, copies.map(|copies| &copies.outline));
+                },
+                _ => rebuilt.outline = copies.map_or(Vec::new(), |copies| copies.outline.clone()),
+            }
+            commands.entity(entity).insert(rebuilt);
+        }
+    }
+    /// This system mirrors the text, the font and the following colors of the main text on its copies.
+    #[allow(clippy::type_complexity)]
+    fn system(
+        query: Query<(Ref<Text2d>, Ref<TextFont>, Ref<TextLayout>, Ref<TextColor>, &TextCopies, Option<&TextShadow>, Option<&TextOutline>), Without<TextCopy>>,
+        mut copies: Query<(&mut Text2d, &mut TextFont, &mut TextLayout, &mut TextColor, &mut Transform), (With<TextCopy>, Without<TextCopies>)>,
+    ) {
+        for (text, font, layout, color, rig, shadow, outline) in &query {
+            if !text.is_changed() && !font.is_changed() && !layout.is_changed() && !color.is_changed() { continue; }
+            let shadow = rig.shadow.iter().map(|copy| (copy, shadow.map(|shadow| (shadow.color, shadow.follow_color))));
+            let outline = rig.outline.iter().map(|copy| (copy, outline.map(|outline| (outline.color, outline.follow_color))));
+            for ((copy, offset), settings) in shadow.chain(outline) {
+                let Ok((mut copy_text, mut copy_font, mut copy_layout, mut copy_color, mut transform)) = copies.get_mut(*copy) else { continue; };
+                if text.is_changed() && copy_text.0 != text.0 { copy_text.0.clone_from(&text.0); }
+                if font.is_changed() {
+                    *copy_font = font.clone();
+                    let translation = (*offset * font.font_size).extend(transform.translation.z);
+                    if transform.translation != translation { transform.translation = translation; }
+                }
+                if layout.is_changed() { *copy_layout = *layout; }
+                if let Some((base, true)) = settings {
+                    let target = TextCopies::color(base, color.0, true);
+                    if copy_color.0 != target { copy_color.0 = target; }
+                }
+            }
+        }
+    }
+    /// This system despawns the copies of removed shadows and outlines.
+    fn cleanup_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut TextCopies, Has<TextShadow>, Has<TextOutline>)>,
+        mut removed_shadows: RemovedComponents<TextShadow>,
+        mut removed_outlines: RemovedComponents<TextOutline>,
+    ) {
+        for entity in removed_shadows.read().chain(removed_outlines.read()) {
+            let Ok((entity, mut copies, has_shadow, has_outline)) = query.get_mut(entity) else { continue; };
+            if !has_shadow {
+                for (copy, _) in copies.shadow.drain(..) { commands.entity(copy).despawn_recursive(); }
+            }
+            if !has_outline {
+                for (copy, _) in copies.outline.drain(..) { commands.entity(copy).despawn_recursive(); }
+            }
+            if copies.shadow.is_empty() && copies.outline.is_empty() { commands.entity(entity).remove::<TextCopies>(); }
+        }
+    }
+}
+
+/// Marks a copy drawn behind a text by its [`TextCopies`]
+#[derive(Component)]
+struct TextCopy;
+
+
+/// Plugin drawing the [`TextShadow`] and [`TextOutline`] copies
+pub(crate) struct TextShadowPlugin;
+impl Plugin for TextShadowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            TextCopies::cleanup_system,
+            TextCopies::build_system,
+            TextCopies::system,
+        ).chain().after(TextAnimator::system));
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use bevy_lunex::*;
+
+
+/// One piece of the parsed [`TypewriterText`] string
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TypewriterToken {
+    Char(char),
+    /// Waits the seconds before the next character
+    Pause(f32),
+    /// Changes the characters per second from here on
+    Cps(f32),
+}
+
+/// Insert this on any text node to reveal the [`Text2d`] character by character, like for menu subtitles or tooltips.
+/// The string can contain `{pause:0.5}` to wait the seconds and `{cps:40}` to change the speed, these are
+/// applied and never shown. By default the text grows by whole words, so a word wrapping to the next line
+/// does not jump there halfway typed. [`TypewriterFinished`] is triggered on the entity once all is shown.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct TypewriterText {
+    /// The string to reveal, changing it starts the reveal over
+    pub full: String,
+    /// Characters revealed per second, until a `{cps:x}` tag changes it
+    pub cps: f32,
+    /// If the text grows by whole words instead of characters
+    pub whole_words: bool,
+    /// The string the tokens were parsed from
+    parsed: Option<String>,
+    tokens: Vec<TypewriterToken>,
+    /// Index of the next token to reveal
+    index: usize,
+    /// Characters per second at the index
+    speed: f32,
+    /// Seconds not spent on the tokens yet
+    budget: f32,
+    revealed: String,
+    finished: bool,
+}
+impl TypewriterText {
+    /// Creates new instance
+    pub fn new(full: impl std::fmt::Display, cps: f32) -> Self {
+        Self {
+            full: full.to_string(),
+            cps,
+            whole_words: true,
+            parsed: None,
+            tokens: Vec::new(),
+            index: 0,
+            speed: cps,
+            budget: 0.0,
+            revealed: String::new(),
+            finished: false,
+        }
+    }
+    /// Grow the text by characters instead of whole words.
+    pub fn by_character(mut self) -> Self {
+        self.whole_words = false;
+        self
+    }
+    /// Reveals the rest of the text at once, skipping the pauses
+    pub fn skip(&mut self) {
+        self.parse();
+        for token in &self.tokens[self.index..] {
+            if let TypewriterToken::Char(c) = token { self.revealed.push(*c); }
+        }
+        self.index = self.tokens.len();
+    }
+    /// If the whole text is shown
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// Returns the text without the tags, as it reads once finished
+    pub fn plain(&self) -> String {
+        TypewriterText::tokenize(&self.full).into_iter().filter_map(|token| match token {
+            TypewriterToken::Char(c) => Some(c),
+            _ => None,
+        }).collect()
+    }
+    /// Splits the string into characters and tags, unknown or malformed tags are kept as text
+    fn tokenize(string: &str) -> Vec<TypewriterToken> {
+        let mut tokens = Vec::new();
+        let mut rest = string;
+        while let Some(c) = rest.chars().next() {
+            let tag = rest.strip_prefix('{').and_then(|tag| tag.split_once('}')).and_then(|(tag, after)| {
+                let (name, value) = tag.split_once(':')?;
+                let value: f32 = value.trim().parse().ok().filter(|value: &f32| value.is_finite() && *value >= 0.0)?;
+                match name.trim() {
+                    "pause" => Some((TypewriterToken::Pause(value), after)),
+                    "cps" => Some((TypewriterToken::Cps(value), after)),
+                    _ => None,
+                }
+            });
+            match tag {
+                Some((token, after)) => { tokens.push(token); rest = after; },
+                None => { tokens.push(TypewriterToken::Char(c)); rest = &rest[c.len_utf8()..]; },
+            }
+        }
+        tokens
+    }
+    /// Parses the string again if it changed, restarting the reveal
+    fn parse(&mut self) {
+        if self.parsed.as_ref() == Some(&self.full) { return; }
+        self.tokens = TypewriterText::tokenize(&self.full);
+        self.parsed = Some(self.full.clone());
+        self.index = 0;
+        self.speed = self.cps;
+        self.budget = 0.0;
+        self.revealed.clear();
+        self.finished = false;
+    }
+    /// Spends the seconds on the next tokens
+    fn advance(&mut self, seconds: f32) {
+        self.budget += seconds;
+        while let Some(token) = self.tokens.get(self.index) {
+            match *token {
+                TypewriterToken::Pause(pause) => {
+                    if self.budget < pause { break; }
+                    self.budget -= pause;
+                },
+                TypewriterToken::Cps(cps) => self.speed = cps,
+                TypewriterToken::Char(c) => {
+                    // Zero speed holds the text until skipped
+                    if self.speed <= 0.0 { break; }
+                    let cost = if c.is_whitespace() { 0.0 } else { 1.0 / self.speed };
+                    if self.budget < cost { break; }
+                    self.budget -= cost;
+                    self.revealed.push(c);
+                },
+            }
+            self.index += 1;
+        }
+        // Nothing is waiting, do not save up time for a later change of the text
+        if self.index == self.tokens.len() { self.budget = 0.0; }
+    }
+    /// Returns the part of the revealed text that should be shown
+    fn visible(&self) -> &str {
+        if !self.whole_words || self.index == self.tokens.len() { return &self.revealed; }
+
+        // The word is shown only once the next character ends it, a pause shows it right away
+        let next_ends_word = match self.tokens[self.index..].iter().find(|token| !matches!(token, TypewriterToken::Cps(_))) {
+            Some(TypewriterToken::Char(c)) => c.is_whitespace(),
+            _ => true,
+        };
+        if next_ends_word { return &self.revealed; }
+        match self.revealed.rfind(char::is_whitespace) {
+            Some(index) => &self.revealed[..index],
+            None => "",
+        }
+    }
+    /// This system reveals the text of the typewriters in time.
+    fn system(mut query: Query<(Entity, &mut Text2d, &mut TypewriterText)>, time: Res<Time>, mut commands: Commands) {
+        for (entity, mut text, mut typewriter) in &mut query {
+            typewriter.parse();
+            if typewriter.finished { continue; }
+            typewriter.advance(time.delta_secs());
+
+            let visible = typewriter.visible();
+            if text.0 != visible {
+                text.0 = visible.to_string();
+                commands.trigger(RecomputeUiLayout);
+            }
+            if typewriter.index == typewriter.tokens.len() {
+                typewriter.finished = true;
+                commands.trigger_targets(TypewriterFinished, entity);
+            }
+        }
+    }
+}
+
+/// This event is triggered on a [`TypewriterText`] once its whole text is shown
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TypewriterFinished;
+
+
+/// Plugin revealing the [`TypewriterText`] texts
+pub(crate) struct TypewriterPlugin;
+impl Plugin for TypewriterPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, TypewriterText::system);
+    }
+}
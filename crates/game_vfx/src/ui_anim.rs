@@ -0,0 +1,357 @@
+use bevy::{color::EuclideanDistance, prelude::*};
+
+use crate::*;
+
+
+/// Easing curves remapping the linear progress of a [`Tween`]
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    SineInOut,
+    CubicOut,
+    /// Overshoots the target a little before settling
+    BackOut,
+    /// Springs around the target before settling
+    Elastic,
+}
+impl Easing {
+    /// Remaps the progress in range 0.0 to 1.0
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::SineInOut => -((std::f32::consts::PI * t).cos() - 1.0) / 2.0,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            },
+            Easing::Elastic => {
+                if t == 0.0 || t == 1.0 { return t; }
+                let c4 = std::f32::consts::TAU / 3.0;
+                2.0_f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * c4).sin() + 1.0
+            },
+        }
+    }
+}
+
+/// Values that can be driven by a [`Tween`]
+pub trait Tweenable: Clone + PartialEq + Send + Sync + 'static {
+    /// Interpolates towards the other value, `t` may leave the 0.0 to 1.0 range for overshooting easings
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+    /// Distance to the other value, used to keep a constant speed when retargeting
+    fn distance(&self, other: &Self) -> f32;
+}
+impl Tweenable for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+    fn distance(&self, other: &Self) -> f32 {
+        (other - self).abs()
+    }
+}
+impl Tweenable for f64 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t as f64
+    }
+    fn distance(&self, other: &Self) -> f32 {
+        (other - self).abs() as f32
+    }
+}
+impl Tweenable for Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        *self + (*other - *self) * t
+    }
+    fn distance(&self, other: &Self) -> f32 {
+        Vec2::distance(*self, *other)
+    }
+}
+impl Tweenable for Color {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self.lerp_oklab(*other, t)
+    }
+    fn distance(&self, other: &Self) -> f32 {
+        Oklaba::from(*self).distance(&Oklaba::from(*other))
+    }
+}
+
+/// Animates a value from one point to another over time
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub struct Tween<T: Tweenable> {
+    pub from: T,
+    pub to: T,
+    /// Duration in seconds
+    pub duration: f32,
+    pub easing: Easing,
+    /// Time since the start in seconds
+    pub elapsed: f32,
+}
+impl<T: Tweenable> Tween<T> {
+    /// Creates new instance with linear easing
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self { from, to, duration, easing: Easing::Linear, elapsed: 0.0 }
+    }
+    /// Creates new finished instance resting at the value
+    pub fn rest(value: T) -> Self {
+        Self { from: value.clone(), to: value, duration: 0.0, easing: Easing::Linear, elapsed: 0.0 }
+    }
+    /// Replace the default easing with a new one.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// The linear progress in range 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 { 1.0 } else { (self.elapsed / self.duration).min(1.0) }
+    }
+    /// If the tween reached its target
+    pub fn is_done(&self) -> bool {
+        self.progress() >= 1.0
+    }
+    /// The current value, exactly the target once done so no float error is left at the end
+    pub fn value(&self) -> T {
+        if self.is_done() { return self.to.clone(); }
+        self.from.interpolate(&self.to, self.easing.apply(self.progress()))
+    }
+    /// Advances the time and returns the new value
+    pub fn tick(&mut self, delta: f32) -> T {
+        self.elapsed = (self.elapsed + delta).min(self.duration.max(0.0));
+        self.value()
+    }
+    /// Restarts from the current value towards a new target, keeping the duration
+    pub fn retarget(&mut self, to: T) {
+        self.from = self.value();
+        self.to = to;
+        self.elapsed = 0.0;
+    }
+    /// Restarts from the current value towards a new target at a constant speed in units per second
+    pub fn towards(&mut self, to: T, speed: f32) {
+        self.retarget(to);
+        self.duration = self.from.distance(&self.to) / speed.max(f32::EPSILON);
+    }
+}
+
+/// If the decorative motion is turned off, the one switch every animated system follows.
+/// It mirrors the reduced motion setting, read it through [`motion_scale`] so the policy stays in one place.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ReducedMotion(pub bool);
+impl ReducedMotion {
+    /// Seconds an entrance or an exit takes at most under reduced motion, where it collapses to a fade
+    pub const FADE: f32 = 0.1;
+    /// Returns the kept duration, the [`ReducedMotion::FADE`] at most under reduced motion
+    pub fn duration(&self, duration: f32) -> f32 {
+        if self.0 { duration.min(ReducedMotion::FADE) } else { duration }
+    }
+}
+
+/// Returns how much of the motion plays, 1.0 normally and 0.0 under reduced motion.
+/// Scale durations and amplitudes by it, a zero duration is an instant state change.
+pub fn motion_scale(reduced: &ReducedMotion) -> f32 {
+    if reduced.0 { 0.0 } else { 1.0 }
+}
+
+/// Components that an [`Animate`] can write its value into
+pub trait AnimationTarget<T>: Component {
+    /// Returns the current value
+    fn animated(&self) -> T;
+    /// Applies the animated value
+    fn set_animated(&mut self, value: T);
+}
+impl AnimationTarget<Color> for Sprite {
+    fn animated(&self) -> Color {
+        self.color
+    }
+    fn set_animated(&mut self, value: Color) {
+        self.color = value;
+    }
+}
+impl AnimationTarget<f32> for Sprite {
+    fn animated(&self) -> f32 {
+        self.color.alpha()
+    }
+    fn set_animated(&mut self, value: f32) {
+        self.color.set_alpha(value);
+    }
+}
+impl AnimationTarget<Color> for TextColor {
+    fn animated(&self) -> Color {
+        self.0
+    }
+    fn set_animated(&mut self, value: Color) {
+        self.0 = value;
+    }
+}
+impl AnimationTarget<f32> for TextColor {
+    fn animated(&self) -> f32 {
+        self.0.alpha()
+    }
+    fn set_animated(&mut self, value: f32) {
+        self.0.set_alpha(value);
+    }
+}
+impl AnimationTarget<Vec2> for Transform {
+    fn animated(&self) -> Vec2 {
+        self.scale.truncate()
+    }
+    fn set_animated(&mut self, value: Vec2) {
+        self.scale = value.extend(self.scale.z);
+    }
+}
+
+/// Plays a [`Tween`] on the entity and writes the value into every [`AnimationTarget`] component it has
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct Animate<T: Tweenable> {
+    pub tween: Tween<T>,
+}
+impl<T: Tweenable> Animate<T> {
+    /// Creates new instance
+    pub fn new(tween: Tween<T>) -> Self {
+        Self { tween }
+    }
+    /// This system advances the tweens and writes the values, finished ones are written only once.
+    pub fn system<C: AnimationTarget<T>>(time: Res<Time>, mut query: Query<(&mut Animate<T>, &mut C)>) {
+        for (mut animate, mut target) in &mut query {
+            // Ticking bypasses change detection, so only new or replaced tweens count as changed
+            if animate.tween.is_done() && !animate.is_changed() { continue; }
+            let value = animate.bypass_change_detection().tween.tick(time.delta_secs());
+            target.set_animated(value);
+        }
+    }
+}
+
+/// Insert this on a node with [`Sprite`] or [`TextColor`] to animate it to a new color. The component
+/// removes itself once done. Inserting a new one while running continues from the current color.
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct AnimateColor {
+    /// The final color
+    pub to: Color,
+    /// Duration in seconds
+    pub duration: f32,
+    pub easing: Easing,
+    /// The space the colors are interpolated in
+    pub space: ColorSpace,
+    /// If to send [`ColorAnimationFinished`] when done
+    pub notify: bool,
+    from: Option<Color>,
+    elapsed: f32,
+}
+impl AnimateColor {
+    /// Creates new instance with linear easing
+    pub fn new(to: Color, duration: f32) -> Self {
+        Self {
+            to,
+            duration,
+            easing: Easing::Linear,
+            space: ColorSpace::default(),
+            notify: false,
+            from: None,
+            elapsed: 0.0,
+        }
+    }
+    /// Replace the default easing with a new one.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// Replace the default color space with a new one.
+    pub fn space(mut self, space: ColorSpace) -> Self {
+        self.space = space;
+        self
+    }
+    /// Send [`ColorAnimationFinished`] when the animation is done.
+    pub fn notify(mut self) -> Self {
+        self.notify = true;
+        self
+    }
+    /// The linear progress in range 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 { 1.0 } else { (self.elapsed / self.duration).min(1.0) }
+    }
+    /// If the animation started and reached its target
+    pub fn is_done(&self) -> bool {
+        self.from.is_some() && self.progress() >= 1.0
+    }
+    /// This system advances all color animations and removes the finished ones.
+    pub fn system<C: AnimationTarget<Color>>(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut query: Query<(Entity, &mut AnimateColor, &mut C)>,
+        mut finished: EventWriter<ColorAnimationFinished>,
+    ) {
+        for (entity, mut animation, mut target) in &mut query {
+            // A freshly inserted animation starts from whatever color the node has now
+            let current = target.animated();
+            let from = *animation.from.get_or_insert(current);
+            animation.elapsed += time.delta_secs();
+
+            // The last frame lands on the target exactly, however long it was
+            let progress = animation.progress();
+            let color = if progress >= 1.0 { animation.to } else { from.lerp_in(animation.to, animation.easing.apply(progress), animation.space) };
+            if current != color { target.set_animated(color); }
+
+            if animation.is_done() {
+                // Check again when applied, a new animation might have been inserted in the meantime
+                commands.entity(entity).queue(|entity: Entity, world: &mut World| {
+                    if world.get::<AnimateColor>(entity).is_some_and(AnimateColor::is_done) {
+                        world.entity_mut(entity).remove::<AnimateColor>();
+                    }
+                });
+                if animation.notify { finished.send(ColorAnimationFinished { entity }); }
+            }
+        }
+    }
+}
+
+/// Sent when an [`AnimateColor`] with notify enabled finishes
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ColorAnimationFinished {
+    pub entity: Entity,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    /// Ticks the tween for a second in steps of the delta, returns the value after every step
+    fn tick_for_a_second(mut tween: Tween<f32>, delta: f32) -> Vec<f32> {
+        (0..(1.0 / delta).round() as usize).map(|_| tween.tick(delta)).collect()
+    }
+
+    #[test]
+    fn tween_lands_on_the_target_at_5_and_200_ms_steps() {
+        for easing in [Easing::Linear, Easing::SineInOut, Easing::CubicOut] {
+            for delta in [0.005, 0.2] {
+                let values = tick_for_a_second(Tween::new(0.0, 1.0, 0.25).easing(easing), delta);
+                assert!(values.windows(2).all(|pair| pair[0] <= pair[1]), "The {easing:?} tween went back at {delta} s steps: {values:?}");
+                assert_eq!(values.last(), Some(&1.0), "The {easing:?} tween missed the target at {delta} s steps");
+            }
+        }
+    }
+
+    #[test]
+    fn tween_is_done_exactly_once() {
+        for delta in [0.005, 0.2] {
+            let mut tween = Tween::new(0.0, 1.0, 0.25);
+            let mut completions = 0;
+            for _ in 0..(1.0 / delta).round() as usize {
+                let was_done = tween.is_done();
+                tween.tick(delta);
+                if tween.is_done() && !was_done { completions += 1; }
+            }
+            assert_eq!(completions, 1, "The tween completed {completions} times at {delta} s steps");
+        }
+    }
+
+    #[test]
+    fn retargeting_keeps_the_speed() {
+        let mut tween = Tween::new(0.0, 1.0, 1.0);
+        tween.tick(0.5);
+        tween.towards(0.0, 1.0);
+        assert_eq!(tween.from, 0.5);
+        assert!((tween.duration - 0.5).abs() < 1e-6, "Half the way back took {} s", tween.duration);
+    }
+}
@@ -0,0 +1,100 @@
+//! Frame costs of menus full of main buttons, run with `cargo bench -p game_widgets --features test-utils`.
+//! Save a baseline with `-- --save-baseline before` to compare a change against, and set `BENCH_THREADS=1`
+//! to compare the parallel update with a serial one.
+
+use bevy::{prelude::*, sprite::Anchor, tasks::{ComputeTaskPool, TaskPoolBuilder}};
+use criterion::{criterion_group, criterion_main, Criterion};
+use game_widgets::*;
+
+
+/// Buttons in the benchmarked menu
+const BUTTONS: usize = 1000;
+/// Buttons in the stress test of the parallel update
+const STRESS_BUTTONS: usize = 3000;
+/// Rows in the benchmarked menu file
+const MENU_ROWS: usize = 100;
+
+/// The app of a benchmark, the task pool is limited to `BENCH_THREADS` threads if set
+fn test_app() -> UiTestApp {
+    if let Some(threads) = std::env::var("BENCH_THREADS").ok().and_then(|threads| threads.parse().ok()) {
+        ComputeTaskPool::get_or_init(|| TaskPoolBuilder::new().num_threads(threads).build());
+    }
+    UiTestApp::new()
+}
+
+/// A frame of a menu where every button is settled, the control update skips all of them
+fn settled_buttons(c: &mut Criterion) {
+    let mut test = test_app();
+    test.spawn_buttons(BUTTONS);
+    // Let the reveal and the first transitions finish
+    test.advance(1.0);
+    c.bench_function("settled_buttons", |b| b.iter(|| test.app.update()));
+}
+
+/// A frame of the same menu with one button hovered, only that one is updated
+fn one_hovered_button(c: &mut Criterion) {
+    let mut test = test_app();
+    let buttons = test.spawn_buttons(BUTTONS);
+    test.advance(1.0);
+    test.hover(buttons[0]);
+    c.bench_function("one_hovered_button", |b| b.iter(|| test.app.update()));
+}
+
+/// A frame where every button turns around, so all their color animations start again in one batch
+fn animating_buttons(c: &mut Criterion) {
+    bench_turning_buttons(c, "animating_buttons", BUTTONS);
+}
+
+/// The same as [`animating_buttons`] with thousands of buttons, where the parallel update pays off
+fn parallel_update(c: &mut Criterion) {
+    bench_turning_buttons(c, "parallel_update", STRESS_BUTTONS);
+}
+
+/// Benchmarks a frame of the number of buttons all turning around
+fn bench_turning_buttons(c: &mut Criterion, name: &str, count: usize) {
+    let mut test = test_app();
+    let buttons = test.spawn_buttons(count);
+    let mut hovered = false;
+    c.bench_function(name, |b| b.iter(|| {
+        hovered = !hovered;
+        for button in &buttons {
+            let Some(mut control) = test.world_mut().get_mut::<MainButtonControl>(*button) else { continue; };
+            control.animation_direction = if hovered { 1.0 } else { -1.0 };
+        }
+        test.app.update();
+    }));
+}
+
+/// Opening and closing a menu file where every row is spawned anew, like before the [`UiNodePool`]
+fn open_menu_spawned(c: &mut Criterion) {
+    bench_open_menu(c, "open_menu_spawned", false);
+}
+
+/// The same as [`open_menu_spawned`] with the rows of the closed menu reused from the [`UiNodePool`]
+fn open_menu_pooled(c: &mut Criterion) {
+    bench_open_menu(c, "open_menu_pooled", true);
+}
+
+/// Benchmarks opening a menu of button rows until it is built, then closing it again
+fn bench_open_menu(c: &mut Criterion, name: &str, pooled: bool) {
+    let mut test = test_app();
+    let menu = test.world_mut().resource_mut::<Assets<MenuDefinition>>().add(MenuDefinition { nodes: (0..MENU_ROWS).map(|row| MenuNode {
+        id: format!("row{row}"),
+        kind: MenuNodeKind::Button { text: format!("Row {row}"), hint: String::new(), disabled: false, auto_width: false },
+        pos: Vec2::new(0.0, row as f32),
+        size: Vec2::new(100.0, 1.0),
+        anchor: Anchor::TopLeft,
+        children: Vec::new(),
+    }).collect() });
+    c.bench_function(name, |b| b.iter(|| {
+        test.world_mut().send_event(SpawnMenu(menu.clone()));
+        test.advance(3.0 * UiTestApp::FRAME);
+        let mut query = test.world_mut().query_filtered::<Entity, With<MenuInstance>>();
+        let root = query.single(test.world());
+        if pooled { MenuInstance::close(&mut test.world_mut().commands(), root); } else { test.world_mut().entity_mut(root).despawn_recursive(); }
+        test.world_mut().flush();
+    }));
+}
+
+criterion_group!(benches, settled_buttons, one_hovered_button, animating_buttons, parallel_update, open_menu_spawned, open_menu_pooled);
+criterion_main!(benches);
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+
+use crate::*;
+
+
+/// When an [`AnimatedIcon`] plays
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum IconPlayMode {
+    /// All the time, starting over after the last frame
+    #[default]
+    Loop,
+    /// Once, then it stays on the last frame
+    Once,
+    /// Only while the widget it is in is hovered, back to the first frame on leave
+    OnHover,
+}
+
+/// Insert this on an icon node instead of a static image to play a sprite sheet, like a rotating gear.
+/// The frames are advanced by time, so the speed does not depend on the frame rate. For [`IconPlayMode::OnHover`]
+/// the hover of the closest [`MainButton`] it is spawned under is used.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Sprite)]
+pub struct AnimatedIcon {
+    pub atlas: Handle<TextureAtlasLayout>,
+    pub image: Handle<Image>,
+    /// Frames per second
+    pub fps: f32,
+    pub mode: IconPlayMode,
+    /// Seconds the icon has been playing
+    clock: f32,
+}
+impl AnimatedIcon {
+    /// Creates new instance
+    pub fn new(image: Handle<Image>, atlas: Handle<TextureAtlasLayout>, fps: f32) -> Self {
+        Self { atlas, image, fps, mode: IconPlayMode::Loop, clock: 0.0 }
+    }
+    /// Replace the default loop mode with a new one.
+    pub fn mode(mut self, mode: IconPlayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Play the icon again from the first frame.
+    pub fn restart(&mut self) {
+        self.clock = 0.0;
+    }
+    /// This system puts the sheet into the sprite of new icons.
+    fn build_system(mut query: Query<(&AnimatedIcon, &mut Sprite), Changed<AnimatedIcon>>) {
+        for (icon, mut sprite) in &mut query {
+            if sprite.image != icon.image { sprite.image = icon.image.clone(); }
+            if sprite.texture_atlas.as_ref().is_none_or(|atlas| atlas.layout != icon.atlas) {
+                sprite.texture_atlas = Some(TextureAtlas { layout: icon.atlas.clone(), index: 0 });
+            }
+        }
+    }
+    /// This system advances the frames of all icons.
+    fn system(
+        mut query: Query<(Entity, &mut AnimatedIcon, &mut Sprite)>,
+        controls: Query<&MainButtonControl>,
+        parents: Query<&Parent>,
+        layouts: Res<Assets<TextureAtlasLayout>>,
+        time: Res<Time>,
+    ) {
+        for (entity, mut icon, mut sprite) in &mut query {
+            let Some(frames) = layouts.get(&icon.atlas).map(|layout| layout.len()).filter(|frames| *frames > 0) else { continue; };
+
+            let playing = match icon.mode {
+                IconPlayMode::Loop | IconPlayMode::Once => true,
+                IconPlayMode::OnHover => parents.iter_ancestors(entity).find_map(|ancestor| controls.get(ancestor).ok()).is_some_and(|control| control.hovered),
+            };
+            if playing {
+                icon.bypass_change_detection().clock += time.delta_secs();
+            } else if icon.clock != 0.0 {
+                icon.bypass_change_detection().clock = 0.0;
+            }
+
+            let frame = (icon.clock * icon.fps.max(0.0)) as usize;
+            let index = match icon.mode {
+                IconPlayMode::Once => frame.min(frames - 1),
+                IconPlayMode::Loop | IconPlayMode::OnHover => frame % frames,
+            };
+            if sprite.texture_atlas.as_ref().is_some_and(|atlas| atlas.index != index) {
+                if let Some(atlas) = &mut sprite.texture_atlas { atlas.index = index; }
+            }
+        }
+    }
+}
+
+
+/// Plugin with the [`AnimatedIcon`] sprite sheets
+pub(crate) struct AnimatedIconPlugin;
+impl Plugin for AnimatedIconPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<IconPlayMode>();
+        app.add_systems(Update, (
+            AnimatedIcon::build_system,
+            AnimatedIcon::system,
+        ).chain().run_if(any_with_component::<AnimatedIcon>));
+    }
+}
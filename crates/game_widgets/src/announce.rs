@@ -0,0 +1,110 @@
+use bevy::{a11y::{accesskit::{Live, Node, Role}, AccessibilityNode}, prelude::*};
+
+
+/// Send this event to have screen readers speak the text, for feedback that has no node of its own
+/// like a toast, a countdown or a changed value. Announcements are spoken through the [`UiAnnouncer`].
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct Announce {
+    /// The spoken text
+    pub text: String,
+    /// Announcements of the same key replace the waiting one, like the values of a dragged slider
+    pub key: Option<String>,
+    /// If the text interrupts what is being spoken
+    pub assertive: bool,
+}
+impl Announce {
+    /// Creates new instance
+    pub fn new(text: impl std::fmt::Display) -> Self {
+        Self {
+            text: text.to_string(),
+            key: None,
+            assertive: false,
+        }
+    }
+    /// Replace the waiting announcement of the same key instead of queueing after it.
+    pub fn key(mut self, key: impl std::fmt::Display) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+    /// Interrupt what is being spoken.
+    pub fn assertive(mut self) -> Self {
+        self.assertive = true;
+        self
+    }
+}
+
+/// Speaks the [`Announce`] events through a live region. Announcements arriving faster than the
+/// interval wait and are spoken together, and a text is not repeated right after itself.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct UiAnnouncer {
+    /// Seconds between two announcements
+    pub interval: f32,
+    /// Seconds before the same text can be spoken again
+    pub repeat: f32,
+    /// If the announcements are logged too
+    pub log: bool,
+    pending: Vec<Announce>,
+    last: Option<(String, f32)>,
+    next: f32,
+}
+impl Default for UiAnnouncer {
+    fn default() -> Self {
+        Self {
+            interval: 0.4,
+            repeat: 2.0,
+            log: false,
+            pending: Vec::new(),
+            last: None,
+            next: 0.0,
+        }
+    }
+}
+impl UiAnnouncer {
+    /// This system queues the new announcements and speaks the waiting ones once the interval passed.
+    fn system(
+        mut commands: Commands,
+        mut announcer: ResMut<UiAnnouncer>,
+        mut events: EventReader<Announce>,
+        time: Res<Time<Real>>,
+        region: Query<Entity, With<UiLiveRegion>>,
+    ) {
+        for event in events.read() {
+            if let Some(key) = &event.key { announcer.pending.retain(|pending| pending.key.as_ref() != Some(key)); }
+            announcer.pending.retain(|pending| pending.text != event.text);
+            announcer.pending.push(event.clone());
+        }
+
+        let now = time.elapsed_secs();
+        if announcer.pending.is_empty() || now < announcer.next { return; }
+        let assertive = announcer.pending.iter().any(|pending| pending.assertive);
+        let text = announcer.pending.drain(..).map(|pending| pending.text).collect::<Vec<_>>().join(". ");
+        if announcer.last.as_ref().is_some_and(|(last, at)| *last == text && now - at < announcer.repeat) { return; }
+        announcer.next = now + announcer.interval;
+        announcer.last = Some((text.clone(), now));
+        if announcer.log { info!("Announced: {text}"); }
+
+        // Screen readers speak the live region each time its label changes
+        let mut node = Node::new(Role::Status);
+        node.set_label(text);
+        node.set_live(if assertive { Live::Assertive } else { Live::Polite });
+        match region.get_single() {
+            Ok(entity) => { commands.entity(entity).insert(AccessibilityNode(node)); },
+            Err(_) => { commands.spawn((UiLiveRegion, AccessibilityNode(node))); },
+        }
+    }
+}
+
+/// The node without visuals holding the last announcement
+#[derive(Component)]
+struct UiLiveRegion;
+
+
+/// Plugin speaking the [`Announce`] events
+pub(crate) struct UiAnnouncerPlugin;
+impl Plugin for UiAnnouncerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Announce>();
+        app.init_resource::<UiAnnouncer>();
+        app.add_systems(PostUpdate, UiAnnouncer::system);
+    }
+}
@@ -0,0 +1,111 @@
+use bevy::{prelude::*, utils::{HashMap, HashSet}};
+use bevy_kira_audio::prelude::*;
+use game_loading::*;
+use game_preferences::*;
+
+
+/// Maximum number of user interface sounds playing at the same time
+pub const MAX_UI_VOICES: usize = 8;
+
+/// What happened in the user interface that makes a sound, the [`UiSoundMap`] decides which one
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UiSoundKind {
+    /// The pointer rests on a widget
+    Hover,
+    /// The pointer rests on a destructive widget, played lower than the hover
+    DangerHover,
+    /// The gamepad or keyboard moved the focus
+    Focus,
+    /// A widget was activated
+    Click,
+    /// A switch was turned on
+    ToggleOn,
+    /// A switch was turned off
+    ToggleOff,
+    /// Something can not be done
+    Error,
+    /// A popup or screen opened
+    Open,
+    /// A popup or screen closed
+    Close,
+}
+impl UiSoundKind {
+    /// The playback rate the sound of this kind is played at
+    fn playback_rate(&self) -> f64 {
+        if *self == UiSoundKind::DangerHover { 0.8 } else { 1.0 }
+    }
+}
+
+/// The sound played for each [`UiSoundKind`], kinds without one are silent.
+/// Insert another map to swap the whole soundscape, the default one is built from the [`AssetCache`].
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct UiSoundMap(pub HashMap<UiSoundKind, Handle<AudioSource>>);
+impl UiSoundMap {
+    /// Creates the default map from the sounds of the asset cache
+    pub fn from_cache(assets: &AssetCache) -> Self {
+        Self(HashMap::from_iter([
+            (UiSoundKind::Hover, assets.hover_sfx.clone()),
+            (UiSoundKind::DangerHover, assets.hover_sfx.clone()),
+            (UiSoundKind::Focus, assets.hover_sfx.clone()),
+            (UiSoundKind::Click, assets.click_sfx.clone()),
+            (UiSoundKind::ToggleOn, assets.click_sfx.clone()),
+            (UiSoundKind::ToggleOff, assets.click_sfx.clone()),
+            (UiSoundKind::Error, assets.error_sfx.clone()),
+            (UiSoundKind::Open, assets.open_sfx.clone()),
+            (UiSoundKind::Close, assets.close_sfx.clone()),
+        ]))
+    }
+    /// Replace the sound of the kind with a new one.
+    pub fn with(mut self, kind: UiSoundKind, sound: Handle<AudioSource>) -> Self {
+        self.0.insert(kind, sound);
+        self
+    }
+    /// This system builds the default map once the asset cache exists, unless one was inserted already.
+    fn init_system(mut commands: Commands, assets: Res<AssetCache>) {
+        commands.insert_resource(UiSoundMap::from_cache(&assets));
+    }
+}
+
+/// Send this event to play a user interface sound. Widgets should never play audio on their own.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlayUiSound(pub UiSoundKind);
+
+/// The user interface sounds currently playing
+#[derive(Resource, Default)]
+struct UiVoices(Vec<Handle<AudioInstance>>);
+impl UiVoices {
+    /// This system plays all requested sounds, skipping duplicates within the same frame and respecting the voice limit.
+    fn system(
+        mut events: EventReader<PlayUiSound>,
+        mut voices: ResMut<UiVoices>,
+        instances: Res<Assets<AudioInstance>>,
+        sounds: Res<UiSoundMap>,
+        settings: Res<AudioSettings>,
+        audio: Res<Audio>,
+    ) {
+        // Forget the finished voices
+        voices.0.retain(|handle| instances.get(handle).is_some_and(|instance| instance.state() != PlaybackState::Stopped));
+
+        let volume = settings.ui_volume();
+        let mut played = HashSet::new();
+        for PlayUiSound(kind) in events.read() {
+            if volume <= 0.0 || voices.0.len() >= MAX_UI_VOICES { break; }
+            let Some(sound) = sounds.0.get(kind) else { continue; };
+            if !played.insert(*kind) { continue; }
+            voices.0.push(audio.play(sound.clone()).with_volume(volume as f64).with_playback_rate(kind.playback_rate()).handle());
+        }
+        events.clear();
+    }
+}
+
+
+/// Plugin playing the [`PlayUiSound`] requests through the [`UiSoundMap`]
+pub(crate) struct UiAudioPlugin;
+impl Plugin for UiAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlayUiSound>();
+        app.init_resource::<UiVoices>();
+        app.add_systems(Update, UiSoundMap::init_system.run_if(resource_exists::<AssetCache>.and(not(resource_exists::<UiSoundMap>))));
+        app.add_systems(PostUpdate, UiVoices::system.run_if(resource_exists::<UiSoundMap>));
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::{prelude::*, text::TextLayoutInfo};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// The unit the [`AutoWidth`] of a button is measured in
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AutoWidthUnit {
+    /// Pixels of the layout, see [`Ab`]
+    #[default]
+    Absolute,
+    /// Percent of the parent width, see [`Rl`]
+    Relative,
+}
+
+/// Insert this next to a [`MainButton`] to fit the width of its root to the label and the hint, like in a menu
+/// mixing `PLAY` with `ACCESSIBILITY OPTIONS`. The layout is the one of the root, only its width is replaced.
+/// The label is measured once its font is loaded and its text is fully shown, again when the text or the font
+/// changes. Anchor the layout at its top center to keep a column of such buttons centered.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct AutoWidth {
+    /// The layout of the button root, its width is replaced by the measured one
+    pub layout: UiLayoutTypeWindow,
+    /// Space left between the label and the hint, in pixels of the layout
+    pub padding: f32,
+    /// The narrowest width, in the unit
+    pub min: f32,
+    /// The widest width, in the unit
+    pub max: f32,
+    pub unit: AutoWidthUnit,
+}
+impl AutoWidth {
+    /// Where the hint ends, as a fraction of the button width
+    const HINT_END: f32 = 0.82;
+    /// Creates new instance
+    pub fn new(layout: UiLayoutTypeWindow) -> Self {
+        Self { layout, padding: 24.0, min: 0.0, max: f32::INFINITY, unit: AutoWidthUnit::Absolute }
+    }
+    /// Replace the default padding with a new one.
+    pub fn padding(mut self, padding: f32) -> Self {
+        self.padding = padding;
+        self
+    }
+    /// Replace the default min width with a new one.
+    pub fn min(mut self, min: f32) -> Self {
+        self.min = min;
+        self
+    }
+    /// Replace the default max width with a new one.
+    pub fn max(mut self, max: f32) -> Self {
+        self.max = max;
+        self
+    }
+    /// Replace the default unit with a new one.
+    pub fn unit(mut self, unit: AutoWidthUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+    /// The width of a text shown at the height, none while it can not be measured yet
+    fn measure(text: Option<(Ref<Text2d>, Ref<TextFont>, &TextLayoutInfo, Option<&TextAnimator>)>, height: f32, fonts: &Assets<Font>) -> Option<f32> {
+        let (text, font, info, animator) = text?;
+        if text.0.is_empty() && animator.is_none_or(|animator| animator.text().is_empty()) { return Some(0.0); }
+        // The fallback font and the scrambled label would size the button wrong
+        if !fonts.contains(&font.font) { return None; }
+        if animator.is_some_and(|animator| animator.text() != text.0) { return None; }
+        // The layout is from the last frame, so it is stale if the text changed since
+        if text.is_changed() || font.is_changed() || info.size.y <= 0.0 { return None; }
+        Some(info.size.x / info.size.y * height)
+    }
+    /// This system measures the labels and hints and resizes the buttons that no longer fit.
+    #[allow(clippy::type_complexity)]
+    fn system(
+        mut commands: Commands,
+        query: Query<(Entity, Ref<AutoWidth>, &MainButtonControl, &Dimension, Option<&Parent>, Option<&AutoWidthApplied>)>,
+        texts: Query<(Ref<Text2d>, Ref<TextFont>, &TextLayoutInfo, Option<&TextAnimator>)>,
+        dimensions: Query<&Dimension>,
+        fonts: Res<Assets<Font>>,
+    ) {
+        for (entity, auto, control, dimension, parent, applied) in &query {
+            let height = dimension.0.y;
+            if height <= 0.0 { continue; }
+
+            // The label and the arrow are 60% of the height, the hint is 40%
+            let Some(label) = AutoWidth::measure(texts.get(control.text()).ok(), 0.6 * height, &fonts) else { continue; };
+            // Compact buttons have no hint
+            let Some(hint) = control.hint().map_or(Some(0.0), |hint| AutoWidth::measure(texts.get(hint).ok(), 0.4 * height, &fonts)) else { continue; };
+            let gap = if hint > 0.0 { auto.padding } else { 0.0 };
+            // The label starts 40% of the height in, the hint ends at its fraction of the width
+            let pixels = (0.4 * height + label + auto.padding + gap + hint) / AutoWidth::HINT_END;
+
+            let width = match auto.unit {
+                AutoWidthUnit::Absolute => pixels,
+                AutoWidthUnit::Relative => {
+                    let Some(parent) = parent.and_then(|parent| dimensions.get(parent.get()).ok()).filter(|parent| parent.0.x > 0.0) else { continue; };
+                    pixels / parent.0.x * 100.0
+                },
+            }.clamp(auto.min, auto.max.max(auto.min));
+
+            if !auto.is_changed() && applied.is_some_and(|applied| (applied.0 - width).abs() < 0.1) { continue; }
+            let layout = match auto.unit {
+                AutoWidthUnit::Absolute => auto.layout.width(Ab(width)),
+                AutoWidthUnit::Relative => auto.layout.width(Rl(width)),
+            };
+            commands.entity(entity).insert((layout.pack(), AutoWidthApplied(width)));
+        }
+    }
+    /// This system forgets the applied width once the component was removed, the root keeps the last one.
+    fn cleanup_system(mut commands: Commands, mut removed: RemovedComponents<AutoWidth>, query: Query<(), With<AutoWidthApplied>>) {
+        for entity in removed.read() {
+            if query.contains(entity) { commands.entity(entity).remove::<AutoWidthApplied>(); }
+        }
+    }
+}
+
+/// The width the [`AutoWidth`] last gave its button, in its unit
+#[derive(Component)]
+struct AutoWidthApplied(f32);
+
+
+/// Plugin fitting the [`AutoWidth`] buttons to their labels
+pub(crate) struct AutoWidthPlugin;
+impl Plugin for AutoWidthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (AutoWidth::cleanup_system, AutoWidth::system).chain());
+    }
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+use crate::*;
+
+
+/// Insert this on the parent of [`MainButton`] children, like a navigation rail or a difficulty selector, to keep
+/// one of them current. Clicking a button marks it [`ButtonCurrent`] so it stays highlighted, the previous one
+/// plays its usual out animation and [`GroupSelectionChanged`] is sent. The members are the buttons among the
+/// children at the time of the click, so buttons spawned later join on their own.
+#[derive(Component, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ButtonGroup {
+    current: Option<Entity>,
+}
+impl ButtonGroup {
+    /// Creates new instance with no button current
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// The current button, if any
+    pub fn current(&self) -> Option<Entity> {
+        self.current
+    }
+    /// This observer makes the clicked member the current one.
+    fn click_observer(
+        trigger: Trigger<MainButtonClicked>,
+        mut commands: Commands,
+        parents: Query<&Parent>,
+        mut groups: Query<(&mut ButtonGroup, &Children)>,
+        buttons: Query<(), With<MainButton>>,
+        mut changed: EventWriter<GroupSelectionChanged>,
+    ) {
+        let entity = trigger.entity();
+        let Ok(parent) = parents.get(entity) else { return; };
+        let Ok((mut group, children)) = groups.get_mut(parent.get()) else { return; };
+        if group.current == Some(entity) { return; }
+
+        if let Some(mut previous) = group.current.and_then(|previous| commands.get_entity(previous)) { previous.remove::<ButtonCurrent>(); }
+        commands.entity(entity).insert(ButtonCurrent);
+        group.current = Some(entity);
+        let index = children.iter().filter(|child| buttons.contains(**child)).position(|child| *child == entity);
+        changed.send(GroupSelectionChanged { group: parent.get(), index });
+    }
+    /// This system falls back to no current button once the current one was despawned or moved out of the group.
+    fn member_system(mut commands: Commands, mut groups: Query<(Entity, &mut ButtonGroup, Option<&Children>)>, mut changed: EventWriter<GroupSelectionChanged>) {
+        for (entity, mut group, children) in &mut groups {
+            let Some(current) = group.current else { continue; };
+            if children.is_some_and(|children| children.contains(&current)) { continue; }
+            if let Some(mut button) = commands.get_entity(current) { button.remove::<ButtonCurrent>(); }
+            group.current = None;
+            changed.send(GroupSelectionChanged { group: entity, index: None });
+        }
+    }
+}
+
+/// Marks the current button of a [`ButtonGroup`], it looks hovered until another member is clicked
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ButtonCurrent;
+
+/// Sent when the current button of a [`ButtonGroup`] changed, the index counts the buttons among its children
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GroupSelectionChanged {
+    pub group: Entity,
+    /// The index of the new current button, none once it was despawned
+    pub index: Option<usize>,
+}
+
+
+/// Plugin with the [`ButtonGroup`] selection
+pub(crate) struct ButtonGroupPlugin;
+impl Plugin for ButtonGroupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GroupSelectionChanged>();
+        app.add_observer(ButtonGroup::click_observer);
+        app.add_systems(Update, ButtonGroup::member_system.run_if(any_with_component::<ButtonGroup>));
+    }
+}
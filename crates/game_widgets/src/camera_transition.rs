@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Insert this resource to push the UI camera in towards the activated button while the screen it opened
+/// replaces the old one. The camera eases towards the button over the [`DespawnWithAnimation`] of the old
+/// screen and snaps back to rest once it is gone, when the new screen takes over. Nothing happens if the
+/// UI camera, the only [`Camera2d`] with [`UiSourceCamera`], is not found or not alone, and under reduced motion.
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct CameraTransition {
+    /// The camera scale at the end of the push, below 1.0 zooms in
+    pub zoom: f32,
+    /// How far the camera moves towards the button in range 0.0 to 1.0
+    pub pull: f32,
+}
+impl Default for CameraTransition {
+    fn default() -> Self {
+        Self { zoom: 0.94, pull: 0.12 }
+    }
+}
+impl CameraTransition {
+    /// Seconds an activation waits for the screen it opens, so older clicks do not push
+    const WINDOW: f32 = 0.5;
+    /// This observer remembers the center of the activated widget.
+    fn activate_observer(trigger: Trigger<MainButtonClicked>, query: Query<&GlobalTransform>, mut push: ResMut<CameraPush>, time: Res<Time<Real>>) {
+        let Ok(transform) = query.get(trigger.entity()) else { return; };
+        push.activated = Some((transform.translation().truncate(), time.elapsed_secs()));
+    }
+    /// This system starts the push when a screen starts despawning after an activation.
+    #[allow(clippy::type_complexity)]
+    fn start_system(
+        transition: Res<CameraTransition>,
+        mut push: ResMut<CameraPush>,
+        roots: Query<(Entity, &DespawnWithAnimation), (Added<DespawnWithAnimation>, With<UiLayoutRoot>)>,
+        cameras: Query<(Entity, &Transform), (With<Camera2d>, With<UiSourceCamera<0>>)>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time<Real>>,
+    ) {
+        let Some((root, exit)) = roots.iter().next() else { return; };
+        let Some((center, at)) = push.activated.take() else { return; };
+        if motion_scale(&reduced) == 0.0 || push.running.is_some() || time.elapsed_secs() - at > CameraTransition::WINDOW { return; }
+        let Ok((camera, rest)) = cameras.get_single() else { return; };
+
+        push.running = Some(RunningPush {
+            camera, root, rest: *rest,
+            translation: rest.translation.truncate().lerp(center, transition.pull.clamp(0.0, 1.0)),
+            scale: transition.zoom,
+            tween: Tween::new(0.0, 1.0, exit.duration).easing(Easing::SineInOut),
+        });
+    }
+    /// This system eases the camera in and puts it back to rest once the old screen is gone.
+    fn system(
+        mut push: ResMut<CameraPush>,
+        mut cameras: Query<&mut Transform>,
+        roots: Query<(), With<UiLayoutRoot>>,
+        mut finished: EventReader<DespawnAnimationFinished>,
+        time: Res<Time>,
+    ) {
+        let finished: Vec<Entity> = finished.read().map(|event| event.entity).collect();
+        let Some(running) = &mut push.running else { return; };
+        let Ok(mut transform) = cameras.get_mut(running.camera) else {
+            push.running = None;
+            return;
+        };
+
+        if finished.contains(&running.root) || !roots.contains(running.root) {
+            *transform = running.rest;
+            push.running = None;
+            return;
+        }
+        let t = running.tween.tick(time.delta_secs());
+        transform.translation = running.rest.translation.truncate().lerp(running.translation, t).extend(running.rest.translation.z);
+        transform.scale = running.rest.scale * 1.0_f32.interpolate(&running.scale, t);
+    }
+}
+
+/// The last activation and the running push of the [`CameraTransition`]
+#[derive(Resource, Default)]
+struct CameraPush {
+    /// The center of the activated widget and when it was activated
+    activated: Option<(Vec2, f32)>,
+    running: Option<RunningPush>,
+}
+
+/// A [`CameraTransition`] playing on the camera while the root despawns
+struct RunningPush {
+    camera: Entity,
+    root: Entity,
+    /// The transform the camera goes back to
+    rest: Transform,
+    /// The camera translation at the end of the push
+    translation: Vec2,
+    /// The scale factor at the end of the push
+    scale: f32,
+    tween: Tween<f32>,
+}
+
+
+/// Plugin with the optional [`CameraTransition`]
+pub(crate) struct CameraTransitionPlugin;
+impl Plugin for CameraTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraPush>();
+        app.add_observer(CameraTransition::activate_observer);
+        app.add_systems(Update, (
+            CameraTransition::start_system,
+            CameraTransition::system,
+        ).chain().run_if(resource_exists::<CameraTransition>));
+    }
+}
@@ -0,0 +1,195 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{camera::RenderTarget, render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages}, view::RenderLayers},
+};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// The render layer the capture camera sees, the captured tree is moved to it for the capture
+pub const CAPTURE_LAYER: usize = 31;
+
+/// Insert this on a [`UiLayoutRoot`] to render its tree into the image, like for a save-slot thumbnail
+/// or a screen transition. The tree is laid out for the size of the image instead of the window while
+/// the capture runs, and only the capture camera renders it meanwhile, so the screen never shows it at the
+/// wrong size. Then [`CaptureComplete`] is triggered on the root and this component is removed.
+/// Captures run one after another and share one camera.
+#[derive(Component, Clone, Debug)]
+pub struct CaptureToTexture {
+    /// The image rendered into, create it with [`CaptureToTexture::create_target`]
+    pub target: Handle<Image>,
+    /// The size the tree is laid out for, in pixels
+    pub size: UVec2,
+}
+impl CaptureToTexture {
+    /// Frames the capture waits for the layout and the render before it completes
+    const FRAMES: u8 = 2;
+    /// Creates new instance
+    pub fn new(target: Handle<Image>, size: UVec2) -> Self {
+        Self { target, size }
+    }
+    /// Creates a transparent image the capture can render into
+    pub fn create_target(images: &mut Assets<Image>, size: UVec2) -> Handle<Image> {
+        let mut image = Image::new_fill(
+            Extent3d { width: size.x.max(1), height: size.y.max(1), depth_or_array_layers: 1 },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Bgra8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+        images.add(image)
+    }
+    /// This system starts the waiting captures, keeps the captured tree on the capture layer only and tears the capture down once rendered.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        mut rig: ResMut<CaptureRig>,
+        captures: Query<(Entity, &CaptureToTexture, &GlobalTransform, Option<&Dimension>, Has<UiFetchFromCamera<0>>)>,
+        descendants: Query<&Children>,
+        layers: Query<Option<&RenderLayers>, Without<CaptureRestore>>,
+        restores: Query<(Entity, &CaptureRestore)>,
+        mut cameras: Query<(&mut Camera, &mut Transform), With<CaptureCamera>>,
+    ) {
+        // Start the next capture, reusing the camera of the previous one
+        if rig.active.is_none() {
+            let Some((root, capture, transform, dimension, fetch)) = captures.iter().next() else { return; };
+            let translation = transform.translation().truncate().extend(1000.0);
+            match rig.camera.and_then(|camera| cameras.get_mut(camera).ok()) {
+                Some((mut camera, mut camera_transform)) => {
+                    camera.target = RenderTarget::Image(capture.target.clone().into());
+                    camera.is_active = true;
+                    camera_transform.translation = translation;
+                },
+                None => {
+                    rig.camera = Some(commands.spawn((
+                        Name::new("Capture Camera"),
+                        CaptureCamera,
+                        Camera2d,
+                        Camera::clear_render_to(capture.target.clone()).with_order(-1),
+                        RenderLayers::layer(CAPTURE_LAYER),
+                        Transform::from_translation(translation),
+                    )).id());
+                },
+            }
+            commands.entity(root).remove::<UiFetchFromCamera<0>>().insert(Dimension(capture.size.as_vec2()));
+            rig.active = Some(ActiveCapture { root, frames: 0, dimension: dimension.map(|dimension| dimension.0), fetch });
+        }
+        let Some(active) = rig.active.as_mut() else { return; };
+
+        // The root was despawned during the capture
+        let Ok((root, capture, ..)) = captures.get(active.root) else {
+            for (entity, _) in &restores { commands.entity(entity).remove::<CaptureRestore>(); }
+            if let Some((mut camera, _)) = rig.camera.and_then(|camera| cameras.get_mut(camera).ok()) { camera.is_active = false; }
+            rig.active = None;
+            return;
+        };
+
+        // The tree leaves its own layers, nodes spawned during the capture, like sandboxed internals, follow
+        for entity in std::iter::once(root).chain(descendants.iter_descendants(root)) {
+            let Ok(old) = layers.get(entity) else { continue; };
+            commands.entity(entity).insert((CaptureRestore(old.cloned()), RenderLayers::layer(CAPTURE_LAYER)));
+        }
+
+        active.frames += 1;
+        if active.frames <= CaptureToTexture::FRAMES { return; }
+
+        // Everything is rendered, put the tree back the way it was
+        for (entity, restore) in &restores {
+            let mut entity = commands.entity(entity);
+            entity.remove::<CaptureRestore>();
+            match &restore.0 {
+                Some(layers) => { entity.insert(layers.clone()); },
+                None => { entity.remove::<RenderLayers>(); },
+            }
+        }
+        let mut entity = commands.entity(root);
+        entity.remove::<CaptureToTexture>();
+        match active.dimension {
+            Some(dimension) => { entity.insert(Dimension(dimension)); },
+            None => { entity.remove::<Dimension>(); },
+        }
+        if active.fetch { entity.insert(UiFetchFromCamera::<0>); }
+        commands.trigger_targets(CaptureComplete { target: capture.target.clone() }, root);
+
+        if let Some((mut camera, _)) = rig.camera.and_then(|camera| cameras.get_mut(camera).ok()) { camera.is_active = false; }
+        rig.active = None;
+    }
+}
+
+/// This event is triggered on the root of a [`CaptureToTexture`] once its tree was rendered into the image
+#[derive(Event, Clone, Debug)]
+pub struct CaptureComplete {
+    pub target: Handle<Image>,
+}
+
+/// The camera shared by all captures
+#[derive(Resource, Default)]
+struct CaptureRig {
+    camera: Option<Entity>,
+    active: Option<ActiveCapture>,
+}
+
+/// The running capture and what the root had before it
+struct ActiveCapture {
+    root: Entity,
+    frames: u8,
+    dimension: Option<Vec2>,
+    fetch: bool,
+}
+
+/// Marks the camera of the [`CaptureRig`]
+#[derive(Component)]
+struct CaptureCamera;
+
+/// The render layers a node had before it joined the capture
+#[derive(Component)]
+struct CaptureRestore(Option<RenderLayers>);
+
+
+/// Plugin rendering the [`CaptureToTexture`] trees
+pub(crate) struct CapturePlugin;
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CaptureRig>();
+        app.add_systems(Update, CaptureToTexture::system.after(SandboxedUiSystems));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, render::view::RenderLayers};
+    use bevy_lunex::Dimension;
+
+    use crate::*;
+
+    #[test]
+    fn capture_leaves_the_on_screen_tree_as_it_was() {
+        let mut test = UiTestApp::new();
+        test.watch::<CaptureComplete>();
+        let button = test.spawn_button("Play");
+        let (image, _) = test.button_parts(button);
+        let root = test.world().get::<Parent>(button).unwrap().get();
+        let before = (test.world().get::<Dimension>(button).unwrap().0, *test.world().get::<Transform>(button).unwrap());
+
+        let size = UVec2::new(256, 144);
+        let target = CaptureToTexture::create_target(&mut test.world_mut().resource_mut::<Assets<Image>>(), size);
+        test.world_mut().entity_mut(root).insert(CaptureToTexture::new(target, size));
+        test.advance(UiTestApp::FRAME);
+        for entity in [root, button, image] {
+            assert_eq!(test.world().get::<RenderLayers>(entity), Some(&RenderLayers::layer(CAPTURE_LAYER)), "Entity {entity} is still on its own layers while captured");
+        }
+
+        test.advance(4.0 * UiTestApp::FRAME);
+        assert_eq!(test.assert_event::<CaptureComplete>(), root);
+        test.advance(UiTestApp::FRAME);
+        let after = (test.world().get::<Dimension>(button).unwrap().0, *test.world().get::<Transform>(button).unwrap());
+        assert_eq!(after, before, "The on screen layout changed after the capture");
+        for entity in [root, button, image] {
+            assert_eq!(test.world().get::<RenderLayers>(entity), None, "Entity {entity} did not get its layers back");
+        }
+    }
+}
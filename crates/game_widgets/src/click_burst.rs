@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use game_vfx::*;
+use rand::Rng;
+
+use crate::*;
+
+
+/// Insert this on a widget to throw a burst of sparks from the click when it is activated, in its hover color.
+/// [`ClickBurstSettings`] turns it on for every [`MainButton`] at once. The sparks are children of the
+/// widget, so they are drawn in its tree above the image and below the backdrop of any open modal.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct ClickBurst {
+    /// The fewest and the most sparks of one burst
+    pub count: (usize, usize),
+    /// The fastest spark in pixels per second
+    pub speed: f32,
+    /// Seconds a spark lives
+    pub lifetime: f32,
+}
+impl Default for ClickBurst {
+    fn default() -> Self {
+        Self { count: (8, 16), speed: 260.0, lifetime: 0.4 }
+    }
+}
+impl ClickBurst {
+    /// Size of a spark in pixels
+    const SIZE: f32 = 5.0;
+    /// Sparks are drawn this far above the widget, over its image and internals
+    const DEPTH: f32 = 0.3;
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Replace the default lifetime with a new one.
+    pub fn lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+    /// This observer remembers where the pointer clicked, the activation event does not carry it.
+    /// The click bubbles, so only the widgets are recorded and not their ancestors.
+    fn click_observer(trigger: Trigger<Pointer<Click>>, widgets: Query<(), Or<(With<ClickBurst>, With<MainButton>)>>, mut last: ResMut<LastClick>) {
+        if !widgets.contains(trigger.entity()) { return; }
+        if let Some(position) = trigger.hit.position { last.0 = Some((trigger.entity(), position)); }
+    }
+    /// This observer throws the sparks of an activated widget, from the click or the center for a gamepad.
+    #[allow(clippy::too_many_arguments)]
+    fn activate_observer(
+        trigger: Trigger<MainButtonClicked>,
+        mut commands: Commands,
+        query: Query<(Option<&ClickBurst>, &GlobalTransform, Has<MainButton>)>,
+        mut last: ResMut<LastClick>,
+        mut pool: ResMut<UiNodePool>,
+        settings: Res<ClickBurstSettings>,
+        reduced: Res<ReducedMotion>,
+        resolver: ThemeResolver,
+    ) {
+        let entity = trigger.entity();
+        let last_click = last.0.take();
+        if motion_scale(&reduced) == 0.0 { return; }
+        let Ok((burst, transform, is_button)) = query.get(entity) else { return; };
+        let Some(burst) = burst.copied().or((settings.everywhere && is_button).then(ClickBurst::default)) else { return; };
+
+        let origin = last_click
+            .filter(|(clicked, _)| *clicked == entity)
+            .map_or(Vec2::ZERO, |(_, position)| transform.affine().inverse().transform_point3(position).truncate());
+        let color = resolver.theme_for(entity).get(ThemeRole::PrimaryHover);
+
+        let mut rng = rand::rng();
+        let count = rng.random_range(burst.count.0.min(burst.count.1)..=burst.count.1.max(burst.count.0));
+        for _ in 0..count {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let spark = Spark {
+                velocity: Vec2::from_angle(angle) * rng.random_range(0.35..1.0) * burst.speed,
+                age: 0.0,
+                lifetime: burst.lifetime * rng.random_range(0.6..1.0),
+                color,
+            };
+            let translation = origin.extend(ClickBurst::DEPTH);
+
+            // Reuse a finished spark if any, so rapid clicking does not spawn more
+            let spark = pool.checkout(&mut commands, (
+                Name::new("Click Spark"),
+                spark,
+                Sprite { color, custom_size: Some(Vec2::splat(ClickBurst::SIZE)), ..default() },
+                Transform::from_translation(translation),
+                PickingBehavior::IGNORE,
+            ));
+            commands.entity(entity).add_child(spark);
+        }
+    }
+}
+
+/// Turns on the [`ClickBurst`] of every [`MainButton`], ones with their own component keep its settings
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ClickBurstSettings {
+    pub everywhere: bool,
+}
+
+/// The widget and the world position of the last pointer click
+#[derive(Resource, Default)]
+struct LastClick(Option<(Entity, Vec3)>);
+
+/// One flying spark of a [`ClickBurst`]
+#[derive(Component)]
+struct Spark {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    color: Color,
+}
+impl Spark {
+    /// This system moves and fades the sparks, finished ones are returned to the [`UiNodePool`].
+    fn system(mut commands: Commands, mut query: Query<(Entity, &mut Spark, &mut Sprite, &mut Transform)>, time: Res<Time>) {
+        for (entity, mut spark, mut sprite, mut transform) in &mut query {
+            spark.age += time.delta_secs();
+            let t = (spark.age / spark.lifetime.max(f32::EPSILON)).min(1.0);
+            if t >= 1.0 {
+                UiNodePool::release(&mut commands, entity);
+                continue;
+            }
+            // Sparks slow down as they fly out
+            spark.velocity *= 1.0 - (6.0 * time.delta_secs()).min(1.0);
+            transform.translation += (spark.velocity * time.delta_secs()).extend(0.0);
+            sprite.color = spark.color.with_alpha(spark.color.alpha() * (1.0 - t));
+        }
+    }
+}
+
+
+/// Plugin with the [`ClickBurst`] sparks
+pub(crate) struct ClickBurstPlugin;
+impl Plugin for ClickBurstPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClickBurstSettings>();
+        app.init_resource::<LastClick>();
+        app.add_observer(ClickBurst::click_observer);
+        app.add_observer(ClickBurst::activate_observer);
+        app.add_systems(Update, Spark::system.run_if(any_with_component::<Spark>));
+    }
+}
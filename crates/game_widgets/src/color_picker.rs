@@ -0,0 +1,104 @@
+use bevy::{a11y::{accesskit::{Action, Node, Role}, AccessibilityNode}, ecs::system::EntityCommands, prelude::*, window::SystemCursorIcon};
+use bevy_lunex::*;
+use game_loading::*;
+
+use crate::*;
+
+
+/// Grid of color swatches. Spawn it on an entity with [`UiLayout`] and the swatches will be built for you,
+/// clicking one triggers [`ColorPicked`] on this entity.
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct ColorPicker {
+    /// Number of hues around the color wheel
+    pub hues: usize,
+    /// Lightness of each row of hues, a grayscale row is added below
+    pub lightness: Vec<f32>,
+}
+impl Default for ColorPicker {
+    fn default() -> Self {
+        Self {
+            hues: 12,
+            lightness: vec![0.3, 0.5, 0.7],
+        }
+    }
+}
+impl ColorPicker {
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Replace the default number of hues with a new one.
+    pub fn hues(mut self, hues: usize) -> Self {
+        self.hues = hues.max(1);
+        self
+    }
+    /// Returns the colors of the swatches row by row
+    fn swatches(&self) -> Vec<Vec<Color>> {
+        let mut rows: Vec<Vec<Color>> = self.lightness.iter().map(|lightness| {
+            (0..self.hues).map(|i| Color::hsl(360.0 * i as f32 / self.hues as f32, 0.9, *lightness)).collect()
+        }).collect();
+        rows.push((0..self.hues).map(|i| Color::hsl(0.0, 0.0, i as f32 / (self.hues.max(2) - 1) as f32)).collect());
+        rows
+    }
+    /// What screen readers are told about a swatch, invoking it picks the color like a click
+    fn swatch_node(color: Color) -> AccessibilityNode {
+        let mut node = Node::new(Role::Button);
+        node.set_label(color.to_srgba().to_hex());
+        node.add_action(Action::Click);
+        AccessibilityNode(node)
+    }
+}
+
+impl SandboxedUi for ColorPicker {
+    type Control = ColorPickerControl;
+    fn internals(control: &ColorPickerControl) -> Vec<Entity> {
+        control.swatches.clone()
+    }
+    fn teardown(entity: &mut EntityCommands) {
+        entity.remove::<AccessibilityNode>();
+    }
+}
+
+impl SandboxWidget for ColorPicker {
+    type BuildParam = ();
+    type UpdateParam = ();
+    /// Spawns the swatches, picked by a click or by the [`FocusActivated`] of assistive technology
+    fn build(&self, ui: &mut WidgetBuilder, _param: &mut (), _assets: &AssetCache) -> ColorPickerControl {
+        let entity = ui.entity();
+        let rows = self.swatches();
+        let (width, height) = (100.0 / self.hues as f32, 100.0 / rows.len() as f32);
+
+        let mut node = Node::new(Role::Group);
+        node.set_label("Color picker");
+        ui.insert(AccessibilityNode(node));
+
+        let mut swatches = Vec::new();
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, color) in row.into_iter().enumerate() {
+                swatches.push(ui.spawn(None, (
+                    UiLayout::window().pos(Rl((x as f32 * width, y as f32 * height))).size(Rl((width, height))).pack(),
+                    UiColor::from(color),
+                    Sprite::default(),
+                    OnHoverSetCursor::new(SystemCursorIcon::Pointer),
+                    ColorPicker::swatch_node(color),
+                )).observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                    commands.trigger_targets(ColorPicked(color), entity);
+                }).observe(move |_: Trigger<FocusActivated>, mut commands: Commands| {
+                    commands.trigger_targets(ColorPicked(color), entity);
+                }).id());
+            }
+        }
+        ColorPickerControl { swatches }
+    }
+}
+
+/// Holds the swatches of a built [`ColorPicker`]
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ColorPickerControl {
+    swatches: Vec<Entity>,
+}
+
+/// This event is triggered on a [`ColorPicker`] entity when a swatch is clicked
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ColorPicked(pub Color);
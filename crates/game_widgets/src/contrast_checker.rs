@@ -0,0 +1,183 @@
+use bevy::{prelude::*, sprite::Anchor, utils::HashMap};
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// The minimal contrast ratio of normal text required by WCAG level AA
+pub const CONTRAST_AA: f32 = 4.5;
+
+/// Returns the WCAG contrast ratio of the two colors, from 1.0 to 21.0
+pub fn contrast_ratio(a: Color, b: Color) -> f32 {
+    let luminance = |color: Color| {
+        let color = color.to_linear();
+        0.2126 * color.red + 0.7152 * color.green + 0.0722 * color.blue
+    };
+    let (a, b) = (luminance(a), luminance(b));
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Returns the color seen when the top color is drawn over the opaque bottom one
+fn composite(top: Color, bottom: Color) -> Color {
+    let (top, bottom) = (top.to_linear(), bottom.to_linear());
+    let alpha = top.alpha;
+    Color::linear_rgb(
+        bottom.red + (top.red - bottom.red) * alpha,
+        bottom.green + (top.green - bottom.green) * alpha,
+        bottom.blue + (top.blue - bottom.blue) * alpha,
+    )
+}
+
+/// Developer panel listing the text nodes failing [`CONTRAST_AA`], toggled with F9
+#[derive(Component)]
+pub struct ContrastCheckerPanel;
+impl ContrastCheckerPanel {
+    /// This system opens and closes the panel.
+    fn toggle_system(
+        mut commands: Commands,
+        keys: Res<ButtonInput<KeyCode>>,
+        panels: Query<Entity, With<ContrastCheckerPanel>>,
+        badges: Query<Entity, With<ContrastBadge>>,
+        assets: Res<AssetCache>,
+        style: Res<UiStyle>,
+    ) {
+        if !keys.just_pressed(KeyCode::F9) { return; }
+        if !panels.is_empty() {
+            for entity in panels.iter().chain(&badges) { commands.entity(entity).despawn_recursive(); }
+            return;
+        }
+
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            UiFetchFromCamera::<0>,
+            ContrastCheckerPanel,
+        )).with_children(|ui| {
+
+            // Spawn the panel on the left side
+            ui.spawn((
+                UiLayout::window().pos(Rl((2.0, 5.0))).size(Rl((30.0, 90.0))).pack(),
+                UiDepth::Add(50.0),
+                ThemedColor::alpha(ThemeRole::Background, 0.9),
+                Sprite::default(),
+                ContrastIgnored,
+            )).with_children(|ui| {
+
+                ui.spawn((
+                    UiLayout::window().pos(Rl((5.0, 4.0))).anchor(Anchor::CenterLeft).pack(),
+                    UiTextSize::from(Rh(3.0)),
+                    Text2d::new("CONTRAST CHECKER"),
+                    Typography::heading(&assets, &style).bundle(),
+                    ContrastIgnored,
+                ));
+
+                // The audit fills in the summary
+                ui.spawn((
+                    UiLayout::window().pos(Rl((5.0, 9.0))).anchor(Anchor::TopLeft).pack(),
+                    UiTextSize::from(Rh(2.0)),
+                    Text2d::default(),
+                    Typography::mono(&assets, &style).bundle(),
+                    ContrastSummary,
+                    ContrastIgnored,
+                ));
+            });
+        });
+    }
+    /// This system evaluates the contrast of every text node against the sprite behind it, for both the idle
+    /// and the hover colors, whenever the panel opens or the theme changes.
+    #[allow(clippy::too_many_arguments)]
+    fn audit_system(
+        mut commands: Commands,
+        resolver: ThemeResolver,
+        opened: Query<(), Added<ContrastCheckerPanel>>,
+        texts: Query<(Entity, &Text2d, &TextColor, Option<&ThemedColor>), (Without<ContrastIgnored>, Without<ContrastBadge>)>,
+        sprites: Query<(&Sprite, Option<&ThemedColor>)>,
+        parents: Query<&Parent>,
+        buttons: Query<(Entity, &MainButton, &MainButtonControl)>,
+        badges: Query<Entity, With<ContrastBadge>>,
+        mut summary: Query<&mut Text2d, (With<ContrastSummary>, With<ContrastIgnored>)>,
+        clear: Res<ClearColor>,
+        assets: Res<AssetCache>,
+        style: Res<UiStyle>,
+    ) {
+        if opened.is_empty() && !resolver.is_changed() { return; }
+        let Ok(mut summary) = summary.get_single_mut() else { return; };
+        for badge in &badges { commands.entity(badge).despawn_recursive(); }
+
+        // Button internals are colored by their control, not by a themed color
+        let mut animated = HashMap::new();
+        for (entity, button, control) in &buttons {
+            animated.extend(control.endpoints(button, &resolver.theme_for(entity)));
+        }
+        let endpoints = |entity: Entity, color: Color, themed: Option<&ThemedColor>| -> [Color; 2] {
+            if let Some(colors) = animated.get(&entity) { return *colors; }
+            match themed {
+                Some(themed) => {
+                    let theme = resolver.theme_for(entity);
+                    [themed.sample(&theme, 0.0, ColorSpace::Srgb), themed.sample(&theme, 1.0, ColorSpace::Srgb)]
+                },
+                None => [color; 2],
+            }
+        };
+
+        let (mut checked, mut lines) = (0, Vec::new());
+        for (entity, text, color, themed) in &texts {
+            if text.0.trim().is_empty() { continue; }
+            let foreground = endpoints(entity, color.0, themed);
+
+            // The background is the nearest sprite above the text, drawn over the clear color
+            let mut background = [clear.0; 2];
+            let mut current = entity;
+            while let Ok(parent) = parents.get(current) {
+                current = parent.get();
+                if let Ok((sprite, themed)) = sprites.get(current) {
+                    let colors = endpoints(current, sprite.color, themed);
+                    background = [composite(colors[0], clear.0), composite(colors[1], clear.0)];
+                    break;
+                }
+            }
+
+            checked += 1;
+            let ratios = [0, 1].map(|i| contrast_ratio(composite(foreground[i], background[i]), background[i]));
+            if ratios[0] >= CONTRAST_AA && ratios[1] >= CONTRAST_AA { continue; }
+            lines.push(format!("{:<16} {:>5.2} {:>5.2}", text.0.chars().take(16).collect::<String>(), ratios[0], ratios[1]));
+
+            // Put the badge just above the failing text
+            commands.entity(entity).with_children(|ui| {
+                ui.spawn((
+                    Text2d::new(format!("{:.1}:1", ratios[0].min(ratios[1]))),
+                    Typography::mono(&assets, &style).color(ThemeRole::Danger).bundle(),
+                    Anchor::BottomLeft,
+                    Transform::from_xyz(0.0, 20.0, 10.0),
+                    ContrastBadge,
+                ));
+            });
+        }
+
+        let mut report = format!("{} OF {checked} TEXTS FAIL {CONTRAST_AA}:1\n\n{:<16} {:>5} {:>5}\n", lines.len(), "TEXT", "IDLE", "HOVER");
+        for line in lines { report += &line; report += "\n"; }
+        summary.0 = report;
+    }
+}
+
+/// Marks the nodes of the panel, so they are left out of the audit
+#[derive(Component)]
+struct ContrastIgnored;
+
+/// Marks the text holding the audit results
+#[derive(Component)]
+struct ContrastSummary;
+
+/// Marks the ratio shown next to a failing text
+#[derive(Component)]
+struct ContrastBadge;
+
+
+/// Plugin with the developer contrast checker
+pub struct ContrastCheckerPlugin;
+impl Plugin for ContrastCheckerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (ContrastCheckerPanel::toggle_system, ContrastCheckerPanel::audit_system).chain());
+    }
+}
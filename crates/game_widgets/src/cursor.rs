@@ -0,0 +1,395 @@
+use bevy::{ecs::system::SystemParam, picking::{focus::HoverMap, pointer::{PointerId, PointerLocation}}, prelude::*, render::camera::NormalizedRenderTarget, sprite::Anchor, transform::TransformSystem, utils::HashMap, window::{PrimaryWindow, SystemCursorIcon}, winit::cursor::CursorIcon};
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Returns the name of the cursor icon used in the `cursors` section of the asset manifest
+pub fn cursor_icon_name(icon: SystemCursorIcon) -> &'static str {
+    match icon {
+        SystemCursorIcon::Default => "default",
+        SystemCursorIcon::Pointer => "pointer",
+        SystemCursorIcon::Grab => "grab",
+        SystemCursorIcon::Grabbing => "grabbing",
+        SystemCursorIcon::Copy => "copy",
+        SystemCursorIcon::Move => "move",
+        SystemCursorIcon::Text => "text",
+        SystemCursorIcon::Crosshair => "crosshair",
+        SystemCursorIcon::Help => "help",
+        SystemCursorIcon::Wait => "wait",
+        SystemCursorIcon::Progress => "progress",
+        SystemCursorIcon::NotAllowed => "not_allowed",
+        SystemCursorIcon::ZoomIn => "zoom_in",
+        SystemCursorIcon::ZoomOut => "zoom_out",
+        _ => "other",
+    }
+}
+
+/// Finds the UI camera of a window, so the cursor systems work with any number of windows and cameras.
+/// A [`SoftwareCursor`] and a UI tree belong to the camera they are spawned under.
+#[derive(SystemParam)]
+pub struct UiCameras<'w, 's> {
+    cameras: Query<'w, 's, (Entity, &'static Camera, &'static GlobalTransform), With<UiSourceCamera<0>>>,
+    pointers: Query<'w, 's, (&'static PointerId, &'static PointerLocation)>,
+    primary: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+impl UiCameras<'_, '_> {
+    /// Returns the active camera rendering into the target, like the window a pointer event came from
+    pub fn for_target(&self, target: &NormalizedRenderTarget) -> Option<(&Camera, &GlobalTransform)> {
+        let primary = self.primary.get_single().ok();
+        self.cameras.iter()
+            .find(|(_, camera, _)| camera.is_active && camera.target.normalize(primary).as_ref() == Some(target))
+            .map(|(_, camera, transform)| (camera, transform))
+    }
+    /// Returns the camera the entity is spawned under
+    pub fn camera_of(&self, entity: Entity) -> Option<Entity> {
+        std::iter::once(entity).chain(self.parents.iter_ancestors(entity)).find(|entity| self.cameras.contains(*entity))
+    }
+    /// Returns where the mouse is now, if it is over any window
+    pub fn mouse_target(&self) -> Option<&NormalizedRenderTarget> {
+        self.pointers.iter().find(|(id, _)| **id == PointerId::Mouse).and_then(|(_, location)| location.location()).map(|location| &location.target)
+    }
+    /// If the mouse is over the window the camera of the entity renders into
+    pub fn has_mouse(&self, entity: Entity) -> bool {
+        let primary = self.primary.get_single().ok();
+        let Some(target) = self.mouse_target() else { return false; };
+        self.camera_of(entity)
+            .and_then(|camera| self.cameras.get(camera).ok())
+            .is_some_and(|(_, camera, _)| camera.target.normalize(primary).as_ref() == Some(target))
+    }
+}
+
+/// Custom sprites of the [`SoftwareCursor`] for the requested cursor icons, with the pixel that points.
+/// Filled from the asset manifest, icons without a sprite show the default cursor art.
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct CursorSprites(pub HashMap<SystemCursorIcon, (Handle<Image>, Vec2)>);
+impl CursorSprites {
+    /// The icons a mapping can be defined for
+    const ICONS: [SystemCursorIcon; 14] = [
+        SystemCursorIcon::Default, SystemCursorIcon::Pointer, SystemCursorIcon::Grab, SystemCursorIcon::Grabbing,
+        SystemCursorIcon::Copy, SystemCursorIcon::Move, SystemCursorIcon::Text, SystemCursorIcon::Crosshair,
+        SystemCursorIcon::Help, SystemCursorIcon::Wait, SystemCursorIcon::Progress, SystemCursorIcon::NotAllowed,
+        SystemCursorIcon::ZoomIn, SystemCursorIcon::ZoomOut,
+    ];
+    /// This system reads the mapping from the asset cache.
+    fn load_system(mut sprites: ResMut<CursorSprites>, cache: Res<AssetCache>) {
+        let mapping: HashMap<_, _> = Self::ICONS.into_iter()
+            .filter_map(|icon| Some((icon, cache.cursor_sprite(cursor_icon_name(icon))?)))
+            .collect();
+        for name in cache.cursor_icons() {
+            if !Self::ICONS.iter().any(|icon| cursor_icon_name(*icon) == name) { warn!("Unknown cursor icon '{name}' in the UI manifest"); }
+        }
+        if sprites.0 != mapping { sprites.0 = mapping; }
+    }
+    /// This system swaps the cursor art for the icon that won the [`CursorRequests`].
+    fn system(
+        sprites: Res<CursorSprites>,
+        requests: Res<CursorRequests>,
+        images: Res<Assets<Image>>,
+        mut cursors: Query<(Entity, &mut Sprite, Option<&DefaultCursorSprite>), With<SoftwareCursor>>,
+        cameras: UiCameras,
+        mut commands: Commands,
+    ) {
+        let icon = requests.icon();
+        for (entity, mut sprite, default) in &mut cursors {
+            // Remember the art the cursor was spawned with, so unmapped icons can go back to it
+            let Some(default) = default else {
+                commands.entity(entity).insert(DefaultCursorSprite { image: sprite.image.clone(), atlas: sprite.texture_atlas.clone(), anchor: sprite.anchor });
+                continue;
+            };
+            // Only the cursor in the window of the mouse shows the requested icon
+            let icon = if cameras.has_mouse(entity) { icon } else { SystemCursorIcon::Default };
+            match sprites.0.get(&icon) {
+                Some((image, hotspot)) => {
+                    // The hotspot is in pixels from the top left, the anchor is relative to the center
+                    let size = images.get(image).map_or(Vec2::ONE, |image| image.size_f32());
+                    let anchor = Anchor::Custom(Vec2::new(hotspot.x / size.x - 0.5, 0.5 - hotspot.y / size.y));
+                    if sprite.image != *image { sprite.image = image.clone(); }
+                    if sprite.texture_atlas.is_some() { sprite.texture_atlas = None; }
+                    if sprite.anchor != anchor { sprite.anchor = anchor; }
+                },
+                None => {
+                    if sprite.image != default.image { sprite.image = default.image.clone(); }
+                    if sprite.texture_atlas.is_none() { sprite.texture_atlas = default.atlas.clone(); }
+                    if sprite.anchor != default.anchor { sprite.anchor = default.anchor; }
+                },
+            }
+        }
+    }
+    /// This system hides the OS cursor while custom sprites are mapped and the window is focused.
+    fn os_cursor_system(sprites: Res<CursorSprites>, mut windows: Query<&mut Window, With<PrimaryWindow>>) {
+        for mut window in &mut windows {
+            let visible = sprites.0.is_empty() || !window.focused;
+            if window.cursor_options.visible != visible { window.cursor_options.visible = visible; }
+        }
+    }
+}
+
+/// Collects the cursor icons widgets want this frame and picks one, the highest priority wins and then
+/// the topmost [`UiLayer`]. Nodes with [`OnHoverSetCursor`] request their icon with [`CursorRequests::HOVER`]
+/// while hovered. Without any request the cursor goes back to the default icon.
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct CursorRequests {
+    requests: Vec<(SystemCursorIcon, i32, Entity)>,
+    icon: SystemCursorIcon,
+}
+impl CursorRequests {
+    /// Priority of the icon of a hovered node
+    pub const HOVER: i32 = 0;
+    /// Priority of a widget in a special state, like a disabled one
+    pub const STATE: i32 = 10;
+    /// Priority of an interaction in progress, like resizing
+    pub const INTERACTION: i32 = 20;
+    /// Priority of a drag in progress
+    pub const DRAG: i32 = 30;
+    /// Requests the icon for this frame on behalf of the source entity
+    pub fn request(&mut self, icon: SystemCursorIcon, priority: i32, source: Entity) {
+        self.requests.push((icon, priority, source));
+    }
+    /// Returns the icon that won the last frame
+    pub fn icon(&self) -> SystemCursorIcon {
+        self.icon
+    }
+    /// This system requests the icon of the nearest hovered node with [`OnHoverSetCursor`].
+    fn hover_system(mut requests: ResMut<CursorRequests>, hover: Res<HoverMap>, query: Query<&OnHoverSetCursor>) {
+        let nearest = hover.get(&PointerId::Mouse)
+            .and_then(|hovered| hovered.iter().filter_map(|(entity, hit)| Some((*entity, query.get(*entity).ok()?, hit.depth))).min_by(|a, b| a.2.total_cmp(&b.2)));
+        if let Some((entity, request, _)) = nearest {
+            requests.request(request.cursor, CursorRequests::HOVER, entity);
+        }
+    }
+    /// This system picks the winner of the frame and clears the requests.
+    fn resolve_system(mut requests: ResMut<CursorRequests>, layers: UiLayers, mut windows: Query<&mut CursorIcon, With<Window>>) {
+        // Earlier requests win ties, so the result does not depend on the sort
+        let icon = requests.requests.iter().enumerate()
+            .max_by_key(|(index, (_, priority, source))| (*priority, layers.layer_of(*source), std::cmp::Reverse(*index)))
+            .map_or(SystemCursorIcon::Default, |(_, (icon, ..))| *icon);
+        requests.requests.clear();
+        if requests.icon != icon { requests.icon = icon; }
+
+        // The window icon drives the atlas of the software cursor and the OS cursor
+        for mut window in &mut windows {
+            let window_icon = CursorIcon::System(icon);
+            if *window != window_icon { *window = window_icon; }
+        }
+    }
+}
+
+/// How the [`SoftwareCursor`] moves while it shows one icon, it turns around the pixel that points
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CursorMotion {
+    /// Radians the cursor sways by while idle
+    pub sway: f32,
+    /// How much the cursor grows and shrinks while idle, as a fraction of its size
+    pub bob: f32,
+    /// How much a click squashes the cursor, as a fraction of its size
+    pub squash: f32,
+    /// Turns per second, for the busy icons
+    pub spin: f32,
+}
+impl CursorMotion {
+    /// The subtle motion of the pointing icons
+    pub const IDLE: CursorMotion = CursorMotion { sway: 0.05, bob: 0.03, squash: 0.2, spin: 0.0 };
+    /// The spinning of the waiting icons
+    pub const BUSY: CursorMotion = CursorMotion { sway: 0.0, bob: 0.0, squash: 0.0, spin: 1.0 };
+}
+
+/// The [`CursorMotion`] of each cursor icon, icons without one stay still. The motion follows the icon
+/// that won the [`CursorRequests`], so mapped [`CursorSprites`] move the way their icon does.
+/// Under reduced motion the busy icons pulse in place instead of spinning, the others stay still.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct CursorMotions(pub HashMap<SystemCursorIcon, CursorMotion>);
+impl Default for CursorMotions {
+    fn default() -> Self {
+        Self(HashMap::from_iter([
+            (SystemCursorIcon::Default, CursorMotion::IDLE),
+            (SystemCursorIcon::Pointer, CursorMotion::IDLE),
+            (SystemCursorIcon::Wait, CursorMotion::BUSY),
+            (SystemCursorIcon::Progress, CursorMotion::BUSY),
+        ]))
+    }
+}
+impl CursorMotions {
+    /// Seconds one idle sway takes
+    const PERIOD: f32 = 3.0;
+    /// Seconds the squash of a click takes
+    const SQUASH: f32 = 0.15;
+    /// How much the busy icons grow and shrink under reduced motion, as a fraction of their size
+    const PULSE: f32 = 0.12;
+    /// Replace the motion of the icon with a new one.
+    pub fn with(mut self, icon: SystemCursorIcon, motion: CursorMotion) -> Self {
+        self.0.insert(icon, motion);
+        self
+    }
+    /// This system sways, squashes and spins or pulses the cursors by the motion of the icon they show.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        motions: Res<CursorMotions>,
+        requests: Res<CursorRequests>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        mut cursors: Query<(Entity, &mut Transform, Option<&mut CursorMotionState>), With<SoftwareCursor>>,
+        cameras: UiCameras,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+    ) {
+        for (entity, mut transform, state) in &mut cursors {
+            // The position belongs to Lunex, only the rotation and the scale are animated from the spawned scale
+            let Some(mut state) = state else {
+                commands.entity(entity).insert(CursorMotionState { scale: transform.scale, icon: SystemCursorIcon::Default, clock: 0.0, squash: 0.0 });
+                continue;
+            };
+            // The same icon the sprite shows, each one starts its motion over
+            let icon = if cameras.has_mouse(entity) { requests.icon() } else { SystemCursorIcon::Default };
+            if state.icon != icon {
+                state.icon = icon;
+                state.clock = 0.0;
+                state.squash = 0.0;
+            }
+            let motion = motions.0.get(&icon).copied().unwrap_or_default();
+            let scale = motion_scale(&reduced);
+            state.clock += time.delta_secs();
+            state.squash = (state.squash - time.delta_secs()).max(0.0);
+            if mouse.just_pressed(MouseButton::Left) && motion.squash > 0.0 { state.squash = CursorMotions::SQUASH; }
+
+            let phase = state.clock / CursorMotions::PERIOD * std::f32::consts::TAU;
+            // The spin tells the app is working, under reduced motion it becomes a pulse that does not turn
+            let spin = state.clock * motion.spin * std::f32::consts::TAU;
+            let (turn, pulse) = if reduced.0 && motion.spin != 0.0 { (0.0, 1.0 + spin.sin() * CursorMotions::PULSE) } else { (spin, 1.0) };
+            let angle = turn + phase.sin() * motion.sway * scale;
+            let bob = pulse * (1.0 + (2.0 * phase).sin() * motion.bob * scale);
+            let squash = (state.squash / CursorMotions::SQUASH * std::f32::consts::PI).sin() * motion.squash * scale;
+
+            let rotation = Quat::from_rotation_z(-angle);
+            let target = state.scale * Vec3::new(bob * (1.0 + squash), bob * (1.0 - squash), 1.0);
+            if transform.rotation != rotation { transform.rotation = rotation; }
+            if transform.scale != target { transform.scale = target; }
+        }
+    }
+}
+
+/// Set this while the app is busy, like while the saves are scanned, to request the spinning wait icon
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct CursorBusy(pub bool);
+impl CursorBusy {
+    /// This system requests the wait icon on every cursor while busy.
+    fn system(busy: Res<CursorBusy>, cursors: Query<Entity, With<SoftwareCursor>>, mut requests: ResMut<CursorRequests>) {
+        if !busy.0 { return; }
+        for cursor in &cursors { requests.request(SystemCursorIcon::Wait, CursorRequests::STATE, cursor); }
+    }
+}
+
+/// The scale the [`SoftwareCursor`] was spawned with and its running [`CursorMotion`]
+#[derive(Component)]
+struct CursorMotionState {
+    scale: Vec3,
+    /// The icon the motion is for
+    icon: SystemCursorIcon,
+    /// Seconds the icon has been shown
+    clock: f32,
+    /// Seconds left of the click squash
+    squash: f32,
+}
+
+/// The art the [`SoftwareCursor`] was spawned with
+#[derive(Component, Clone, PartialEq, Debug)]
+struct DefaultCursorSprite {
+    image: Handle<Image>,
+    atlas: Option<TextureAtlas>,
+    anchor: Anchor,
+}
+
+
+/// Plugin resolving the [`CursorRequests`], swapping the cursor art with [`CursorSprites`] and moving it by the [`CursorMotions`]
+pub(crate) struct CursorSpritesPlugin;
+impl Plugin for CursorSpritesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CursorSprites>();
+        app.init_resource::<CursorRequests>();
+        app.init_resource::<CursorMotions>();
+        app.init_resource::<CursorBusy>();
+        app.add_systems(Update, (
+            CursorSprites::load_system.run_if(resource_exists_and_changed::<AssetCache>),
+            CursorRequests::hover_system,
+            CursorBusy::system,
+            // During gameplay the window cursor belongs to the grab
+            CursorSprites::os_cursor_system.run_if(in_state(CursorMode::Menu)),
+        ).chain());
+        // Resolved after all widgets had their say in Update
+        app.add_systems(PostUpdate, (CursorRequests::resolve_system, CursorSprites::system, CursorMotions::system).chain().before(TransformSystem::TransformPropagate));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::SystemState, prelude::*, render::camera::{ManualTextureViewHandle, RenderTarget}, window::SystemCursorIcon};
+    use bevy_lunex::{SoftwareCursor, UiSourceCamera};
+    use game_vfx::ReducedMotion;
+
+    use crate::*;
+
+    /// Spawns a UI camera rendering into the texture view with a software cursor under it, returns the camera and the cursor.
+    /// The pointer of the test app is over the texture view 0.
+    fn spawn_cursor(test: &mut UiTestApp, view: u32) -> (Entity, Entity) {
+        let camera = test.world_mut().spawn((Camera { target: RenderTarget::TextureView(ManualTextureViewHandle(view)), ..default() }, UiSourceCamera::<0>)).id();
+        let cursor = test.world_mut().spawn((SoftwareCursor::new(), Sprite::default())).set_parent(camera).id();
+        (camera, cursor)
+    }
+
+    /// The icon the cursor moves for, none before its first update
+    fn cursor_icon(test: &UiTestApp, cursor: Entity) -> Option<SystemCursorIcon> {
+        test.world().get::<CursorMotionState>(cursor).map(|state| state.icon)
+    }
+
+    #[test]
+    fn buttons_work_without_a_cursor() {
+        let mut test = UiTestApp::new();
+        let button = test.spawn_button("Play");
+
+        test.click(button);
+        assert_eq!(test.assert_event::<MainButtonClicked>(), button);
+        assert_eq!(test.world().resource::<CursorRequests>().icon(), SystemCursorIcon::Pointer);
+    }
+
+    #[test]
+    fn only_the_cursor_in_the_window_of_the_mouse_shows_the_icon() {
+        let mut test = UiTestApp::new();
+        let (camera, here) = spawn_cursor(&mut test, 0);
+        let (_, other) = spawn_cursor(&mut test, 1);
+        let button = test.spawn_button("Play");
+
+        test.click(button);
+        assert_eq!(test.assert_event::<MainButtonClicked>(), button);
+        assert_eq!(cursor_icon(&test, here), Some(SystemCursorIcon::Pointer));
+        assert_eq!(cursor_icon(&test, other), Some(SystemCursorIcon::Default));
+
+        let mut state = SystemState::<UiCameras>::new(test.world_mut());
+        let cameras = state.get(test.world());
+        assert_eq!(cameras.camera_of(here), Some(camera));
+        assert!(cameras.has_mouse(here) && !cameras.has_mouse(other), "The mouse is not only over the window of the first cursor");
+    }
+
+    #[test]
+    fn busy_cursor_pulses_without_turning_under_reduced_motion() {
+        let mut test = UiTestApp::new();
+        test.world_mut().insert_resource(ReducedMotion(true));
+        let (_, cursor) = spawn_cursor(&mut test, 0);
+        let button = test.spawn_button("Play");
+        test.hover(button);
+        test.world_mut().insert_resource(CursorBusy(true));
+
+        let mut scales = Vec::new();
+        for _ in 0..30 {
+            test.advance(UiTestApp::FRAME);
+            let transform = test.world().get::<Transform>(cursor).unwrap();
+            assert_eq!(transform.rotation, Quat::IDENTITY, "The busy cursor turned under reduced motion");
+            scales.push(transform.scale.x);
+        }
+        assert_eq!(cursor_icon(&test, cursor), Some(SystemCursorIcon::Wait));
+        assert!(scales.iter().any(|scale| (scale - scales[0]).abs() > 0.01), "The busy cursor does not pulse");
+    }
+}
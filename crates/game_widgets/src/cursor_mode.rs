@@ -0,0 +1,63 @@
+use bevy::{prelude::*, window::{CursorGrabMode, PrimaryWindow}};
+use bevy_lunex::*;
+use game_preferences::platform;
+
+use crate::*;
+
+
+/// Who owns the cursor. Screens set this state instead of changing the [`Window`] cursor themselves.
+#[derive(States, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum CursorMode {
+    /// The cursor is free and the [`SoftwareCursor`] points at the user interface
+    #[default]
+    Menu,
+    /// The cursor is grabbed by the window and hidden, like in a game or a pause-less camera view
+    Gameplay,
+}
+impl CursorMode {
+    /// This system grabs the window cursor during gameplay. The grab is released while the window
+    /// is not focused and acquired again only after the user clicks back into it. Where the grab needs
+    /// a click, it waits for one after each menu too.
+    fn grab_system(
+        mode: Res<State<CursorMode>>,
+        mut windows: Query<&mut Window, With<PrimaryWindow>>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        mut released: Local<bool>,
+    ) {
+        let Ok(mut window) = windows.get_single_mut() else { return; };
+        if !window.focused { *released = true; }
+        if *released && window.focused && mouse.just_pressed(MouseButton::Left) { *released = false; }
+        if *mode.get() == CursorMode::Menu { *released = platform::GRAB_NEEDS_CLICK; }
+
+        let grabbed = *mode.get() == CursorMode::Gameplay && !*released;
+        let grab = if grabbed { CursorGrabMode::Locked } else { CursorGrabMode::None };
+        if window.cursor_options.grab_mode != grab { window.cursor_options.grab_mode = grab; }
+        if grabbed && window.cursor_options.visible { window.cursor_options.visible = false; }
+        if !grabbed && *mode.get() == CursorMode::Gameplay && !window.cursor_options.visible { window.cursor_options.visible = true; }
+    }
+    /// This system hides the software cursor in gameplay.
+    fn hide_system(mut cursors: Query<&mut Visibility, With<SoftwareCursor>>) {
+        for mut cursor in &mut cursors { cursor.set_if_neq(Visibility::Hidden); }
+    }
+    /// This system shows the software cursor again at the center of the screen when a menu opens.
+    fn show_system(touch: Res<TouchMode>, mut windows: Query<&mut Window, With<PrimaryWindow>>, mut cursors: Query<&mut Visibility, With<SoftwareCursor>>) {
+        if let Some(mut window) = windows.get_single_mut().ok().filter(|_| platform::CAN_MOVE_CURSOR) {
+            let center = window.size() / 2.0;
+            window.set_cursor_position(Some(center));
+        }
+        let visibility = if touch.is_active() { Visibility::Hidden } else { Visibility::Inherited };
+        for mut cursor in &mut cursors { cursor.set_if_neq(visibility); }
+    }
+}
+
+
+/// Plugin with the [`CursorMode`] state
+pub(crate) struct CursorModePlugin;
+impl Plugin for CursorModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<CursorMode>();
+        app.add_systems(OnEnter(CursorMode::Gameplay), CursorMode::hide_system);
+        app.add_systems(OnExit(CursorMode::Gameplay), CursorMode::show_system);
+        app.add_systems(Update, CursorMode::grab_system);
+    }
+}
@@ -0,0 +1,254 @@
+use bevy::{prelude::*, window::{CursorEntered, SystemCursorIcon, WindowFocused}};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// What is being dragged, targets decide from it if they accept the drop
+#[derive(Reflect, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DragPayload {
+    /// The kind of the dragged thing, like `item` or `save`
+    pub kind: String,
+    /// Identifies the thing within its kind
+    pub id: u64,
+}
+impl DragPayload {
+    /// Creates new instance
+    pub fn new(kind: impl Into<String>, id: u64) -> Self {
+        Self { kind: kind.into(), id }
+    }
+}
+
+/// Makes the node draggable, a ghost of it follows the pointer during the drag
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct Draggable {
+    pub payload: DragPayload,
+}
+impl Draggable {
+    /// Creates new instance
+    pub fn new(payload: DragPayload) -> Self {
+        Self { payload }
+    }
+}
+
+/// Makes the node accept drops of [`Draggable`] nodes, it is highlighted while a valid payload hovers it
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct DropTarget {
+    /// Returns if the payload can be dropped here
+    pub accepts: fn(&DragPayload) -> bool,
+}
+impl DropTarget {
+    /// Creates new instance accepting payloads passing the filter
+    pub fn new(accepts: fn(&DragPayload) -> bool) -> Self {
+        Self { accepts }
+    }
+    /// Creates new instance accepting everything
+    pub fn any() -> Self {
+        Self { accepts: |_| true }
+    }
+}
+
+/// Sent when a payload is dropped on a [`DropTarget`] accepting it
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct Dropped {
+    pub payload: DragPayload,
+    pub source: Entity,
+    pub target: Entity,
+}
+
+/// Sent when a drag ends anywhere but on a [`DropTarget`] accepting it
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct DragCancelled {
+    pub payload: DragPayload,
+    pub source: Entity,
+}
+
+/// The drag in progress
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct DragState {
+    drag: Option<ActiveDrag>,
+}
+impl DragState {
+    /// Returns the dragged entity and its payload
+    pub fn current(&self) -> Option<(Entity, &DragPayload)> {
+        self.drag.as_ref().map(|drag| (drag.source, &drag.payload))
+    }
+    /// This system starts the drags, spawning the ghost and ending any previous drag.
+    fn start_system(
+        mut commands: Commands,
+        mut state: ResMut<DragState>,
+        mut events: EventReader<Pointer<DragStart>>,
+        draggables: Query<(&Draggable, &GlobalTransform, &Dimension)>,
+    ) {
+        for event in events.read() {
+            let Ok((draggable, transform, dimension)) = draggables.get(event.target) else { continue; };
+            if let Some(previous) = state.drag.take() { previous.cleanup(&mut commands); }
+
+            let ghost = commands.spawn((
+                ThemedColor::alpha(ThemeRole::Primary, 0.35),
+                Sprite { custom_size: Some(dimension.0), ..default() },
+                Transform::from_translation(transform.translation().truncate().extend(900.0)),
+                // The ghost must not cover the targets below the pointer
+                PickingBehavior::IGNORE,
+            )).id();
+            state.drag = Some(ActiveDrag {
+                source: event.target,
+                payload: draggable.payload.clone(),
+                ghost,
+                grab: transform.translation().truncate(),
+                highlight: None,
+                dropped: false,
+            });
+        }
+    }
+    /// This system moves the ghost with the pointer.
+    fn move_system(
+        state: Res<DragState>,
+        mut events: EventReader<Pointer<Drag>>,
+        cameras: UiCameras,
+        mut transforms: Query<&mut Transform>,
+    ) {
+        let Some(drag) = &state.drag else { events.clear(); return; };
+        for event in events.read().filter(|event| event.target == drag.source) {
+            let Some((camera, camera_transform)) = cameras.for_target(&event.pointer_location.target) else { continue; };
+            // The distance is measured in the viewport, so it is converted to the world through the camera
+            let (Ok(start), Ok(now)) = (
+                camera.viewport_to_world_2d(camera_transform, event.pointer_location.position - event.distance),
+                camera.viewport_to_world_2d(camera_transform, event.pointer_location.position),
+            ) else { continue; };
+            if let Ok(mut transform) = transforms.get_mut(drag.ghost) {
+                transform.translation = (drag.grab + now - start).extend(transform.translation.z);
+            }
+        }
+    }
+    /// This system highlights the targets accepting the payload while it hovers them.
+    fn hover_system(
+        mut commands: Commands,
+        mut state: ResMut<DragState>,
+        mut enters: EventReader<Pointer<DragEnter>>,
+        mut leaves: EventReader<Pointer<DragLeave>>,
+        targets: Query<&DropTarget>,
+    ) {
+        let Some(drag) = &mut state.drag else {
+            enters.clear();
+            leaves.clear();
+            return;
+        };
+        for event in leaves.read() {
+            if drag.highlight.is_some_and(|(target, _)| target == event.target) {
+                if let Some((_, highlight)) = drag.highlight.take() { commands.entity(highlight).despawn_recursive(); }
+            }
+        }
+        for event in enters.read() {
+            if event.dragged != drag.source { continue; }
+            let Ok(target) = targets.get(event.target) else { continue; };
+            if !(target.accepts)(&drag.payload) { continue; }
+            if let Some((_, highlight)) = drag.highlight.take() { commands.entity(highlight).despawn_recursive(); }
+
+            let mut highlight = Entity::PLACEHOLDER;
+            commands.entity(event.target).with_children(|ui| {
+                highlight = ui.spawn((
+                    UiLayout::window().full().pack(),
+                    UiDepth::Add(1.0),
+                    ThemedColor::alpha(ThemeRole::Accent, 0.25),
+                    Sprite::default(),
+                    PickingBehavior::IGNORE,
+                )).id();
+            });
+            drag.highlight = Some((event.target, highlight));
+        }
+    }
+    /// This system sends [`Dropped`] or [`DragCancelled`] once the pointer is released.
+    fn end_system(
+        mut commands: Commands,
+        mut state: ResMut<DragState>,
+        mut drops: EventReader<Pointer<DragDrop>>,
+        mut ends: EventReader<Pointer<DragEnd>>,
+        targets: Query<&DropTarget>,
+        mut dropped: EventWriter<Dropped>,
+        mut cancelled: EventWriter<DragCancelled>,
+    ) {
+        let Some(drag) = &mut state.drag else {
+            drops.clear();
+            ends.clear();
+            return;
+        };
+        for event in drops.read() {
+            if event.dropped != drag.source || drag.dropped { continue; }
+            if targets.get(event.target).is_ok_and(|target| (target.accepts)(&drag.payload)) {
+                drag.dropped = true;
+                dropped.send(Dropped { payload: drag.payload.clone(), source: drag.source, target: event.target });
+            }
+        }
+        if !ends.read().any(|event| event.target == drag.source) { return; }
+        let Some(drag) = state.drag.take() else { return; };
+        if !drag.dropped { cancelled.send(DragCancelled { payload: drag.payload.clone(), source: drag.source }); }
+        drag.cleanup(&mut commands);
+    }
+    /// This system cancels the drag when the window loses focus, or when the cursor comes back with the
+    /// button already released outside the window.
+    fn window_system(
+        mut commands: Commands,
+        mut state: ResMut<DragState>,
+        mut entered: EventReader<CursorEntered>,
+        mut focused: EventReader<WindowFocused>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        mut cancelled: EventWriter<DragCancelled>,
+    ) {
+        let lost = focused.read().any(|event| !event.focused);
+        let released = entered.read().count() > 0 && !mouse.pressed(MouseButton::Left);
+        if !lost && !released { return; }
+        let Some(drag) = state.drag.take() else { return; };
+        if !drag.dropped { cancelled.send(DragCancelled { payload: drag.payload.clone(), source: drag.source }); }
+        drag.cleanup(&mut commands);
+    }
+    /// This system shows the grabbing cursor for the whole drag, even over nodes requesting other icons.
+    fn cursor_system(state: Res<DragState>, mut requests: ResMut<CursorRequests>) {
+        if let Some(drag) = &state.drag { requests.request(SystemCursorIcon::Grabbing, CursorRequests::DRAG, drag.source); }
+    }
+}
+
+/// The entities of the drag in progress
+#[derive(Clone, PartialEq, Debug)]
+struct ActiveDrag {
+    source: Entity,
+    payload: DragPayload,
+    ghost: Entity,
+    /// Where the source was when the drag started
+    grab: Vec2,
+    /// The hovered target and its highlight
+    highlight: Option<(Entity, Entity)>,
+    dropped: bool,
+}
+impl ActiveDrag {
+    /// Despawns the ghost and the highlight
+    fn cleanup(self, commands: &mut Commands) {
+        if let Some(mut ghost) = commands.get_entity(self.ghost) { ghost.despawn(); }
+        if let Some((_, highlight)) = self.highlight {
+            if let Some(highlight) = commands.get_entity(highlight) { highlight.despawn_recursive(); }
+        }
+    }
+}
+
+
+/// Plugin with the drag and drop of [`Draggable`] nodes onto [`DropTarget`] nodes
+pub(crate) struct DragDropPlugin;
+impl Plugin for DragDropPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DragState>();
+        app.register_type::<Draggable>();
+        app.add_event::<Dropped>();
+        app.add_event::<DragCancelled>();
+        // A drag in progress still has to end if its node goes away
+        app.add_systems(Update, (
+            DragState::start_system,
+            DragState::move_system,
+            DragState::hover_system,
+            DragState::end_system,
+            DragState::window_system,
+            DragState::cursor_system,
+        ).chain().run_if(any_with_component::<Draggable>.or(|state: Res<DragState>| state.current().is_some())));
+    }
+}
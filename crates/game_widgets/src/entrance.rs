@@ -0,0 +1,196 @@
+use bevy::{prelude::*, transform::TransformSystem, utils::HashMap, window::PrimaryWindow};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// How the children of an [`EntranceAnimation`] come in
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum EntranceKind {
+    /// From off the left side of the window
+    #[default]
+    SlideLeft,
+    /// From transparent
+    Fade,
+    /// From scaled down
+    Scale,
+}
+
+/// Insert this on a container, like the box of the menu buttons, to animate its children in one after another.
+/// Each child waits the stagger after the previous one and is [`UiInert`] until its own entrance finished.
+/// The children are only offset on top of their layout, removing the component or navigating away mid
+/// entrance leaves them exactly where the layout puts them. The component removes itself once done.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct EntranceAnimation {
+    pub kind: EntranceKind,
+    /// Seconds between the starts of two children
+    pub stagger: f32,
+    /// Seconds the entrance of one child takes
+    pub duration: f32,
+    pub easing: Easing,
+}
+impl EntranceAnimation {
+    /// The size a [`EntranceKind::Scale`] child starts at
+    const SCALE: f32 = 0.6;
+    /// Creates new instance
+    pub fn new(kind: EntranceKind, stagger: f32) -> Self {
+        Self { kind, stagger, duration: 0.3, easing: Easing::CubicOut }
+    }
+    /// Replace the default duration with a new one.
+    pub fn duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+    /// Replace the default easing with a new one.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// This system plays the entrances on top of the computed layout and cleans up the finished ones.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &EntranceAnimation, Option<&mut EntranceState>, &Children)>,
+        layouts: Query<(), With<UiLayout>>,
+        descendants: Query<&Children>,
+        mut transforms: Query<&mut Transform>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<&mut TextColor>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+    ) {
+        let width = windows.get_single().map_or(1920.0, |window| window.width());
+        for (container, entrance, state, children) in &mut query {
+            // Under reduced motion all children fade in together, quickly
+            let kind = if motion_scale(&reduced) == 0.0 { EntranceKind::Fade } else { entrance.kind };
+            let stagger = entrance.stagger * motion_scale(&reduced);
+
+            // Start in the same frame the children first show, so they never flash at their final place
+            let mut fresh = None;
+            let state = match state {
+                Some(state) => state.into_inner(),
+                None => {
+                    let items = children.iter().copied().filter(|child| layouts.contains(*child)).map(|entity| {
+                        commands.entity(entity).insert(UiInert);
+                        AnimatedNode::new(entity, Tween::new(0.0, 1.0, reduced.duration(entrance.duration)).easing(entrance.easing))
+                    }).collect();
+                    fresh.insert(EntranceState { elapsed: 0.0, items })
+                },
+            };
+            state.elapsed += time.delta_secs();
+
+            for (index, item) in state.items.iter_mut().enumerate() {
+                let started = state.elapsed - index as f32 * stagger;
+                let done = item.tween.is_done();
+                let progress = if started > 0.0 { item.tween.tick(started - item.tween.elapsed) } else { 0.0 };
+                if done { continue; }
+
+                let (slide, alpha, scale) = match kind {
+                    EntranceKind::SlideLeft => (-width * (1.0 - progress), 1.0, 1.0),
+                    EntranceKind::Fade => (0.0, progress, 1.0),
+                    EntranceKind::Scale => (0.0, 1.0, EntranceAnimation::SCALE.interpolate(&1.0, progress)),
+                };
+                item.apply(slide, alpha, scale, &descendants, &mut transforms, &mut sprites, &mut texts);
+                if item.tween.is_done() { commands.entity(item.entity).remove::<UiInert>(); }
+            }
+
+            if state.items.iter().all(|item| item.tween.is_done()) {
+                commands.entity(container).remove::<(EntranceAnimation, EntranceState)>();
+            } else if let Some(state) = fresh {
+                commands.entity(container).insert(state);
+            }
+        }
+    }
+    /// This system puts the children back whose entrance was removed mid way.
+    fn cancel_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut EntranceState), Without<EntranceAnimation>>,
+        descendants: Query<&Children>,
+        mut transforms: Query<&mut Transform>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<&mut TextColor>,
+    ) {
+        for (container, mut state) in &mut query {
+            for item in state.items.iter_mut().filter(|item| !item.tween.is_done()) {
+                item.apply(0.0, 1.0, 1.0, &descendants, &mut transforms, &mut sprites, &mut texts);
+                commands.entity(item.entity).remove::<UiInert>();
+            }
+            commands.entity(container).remove::<EntranceState>();
+        }
+    }
+}
+
+/// The progress of a running [`EntranceAnimation`]
+#[derive(Component)]
+struct EntranceState {
+    /// Seconds since the container started
+    elapsed: f32,
+    items: Vec<AnimatedNode>,
+}
+
+/// A node animated on top of its layout, like a child of an [`EntranceAnimation`]
+pub(crate) struct AnimatedNode {
+    pub(crate) entity: Entity,
+    pub(crate) tween: Tween<f32>,
+    /// The slide added to the translation in the last frame
+    applied: f32,
+    /// The translation written in the last frame, a different one means the layout moved the child
+    written: Option<Vec3>,
+    /// The alpha of every faded node before the entrance
+    alphas: HashMap<Entity, f32>,
+}
+impl AnimatedNode {
+    /// Creates new instance, nothing is applied yet
+    pub(crate) fn new(entity: Entity, tween: Tween<f32>) -> Self {
+        Self { entity, tween, applied: 0.0, written: None, alphas: HashMap::new() }
+    }
+    /// Writes the slide, the alpha factor and the scale on top of the layout, all neutral restores the child
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn apply(
+        &mut self,
+        slide: f32,
+        alpha: f32,
+        scale: f32,
+        descendants: &Query<&Children>,
+        transforms: &mut Query<&mut Transform>,
+        sprites: &mut Query<&mut Sprite>,
+        texts: &mut Query<&mut TextColor>,
+    ) {
+        if let Ok(mut transform) = transforms.get_mut(self.entity) {
+            let base = if Some(transform.translation) == self.written { transform.translation.x - self.applied } else { transform.translation.x };
+            transform.translation.x = base + slide;
+            transform.scale = Vec3::new(scale, scale, transform.scale.z);
+            self.applied = slide;
+            self.written = Some(transform.translation);
+        }
+
+        // Nodes built during the entrance, like button internals, are faded from when they appear
+        if alpha == 1.0 && self.alphas.is_empty() { return; }
+        for entity in std::iter::once(self.entity).chain(descendants.iter_descendants(self.entity)) {
+            if let Ok(mut sprite) = sprites.get_mut(entity) {
+                let original = *self.alphas.entry(entity).or_insert(sprite.color.alpha());
+                sprite.color.set_alpha(original * alpha);
+            } else if let Ok(mut text) = texts.get_mut(entity) {
+                let original = *self.alphas.entry(entity).or_insert(text.0.alpha());
+                text.0.set_alpha(original * alpha);
+            }
+        }
+        if alpha == 1.0 { self.alphas.clear(); }
+    }
+}
+
+
+/// Plugin with the [`EntranceAnimation`] of containers
+pub(crate) struct EntrancePlugin;
+impl Plugin for EntrancePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<EntranceKind>();
+        // After the layout wrote the transforms, so the offsets are never overwritten mid entrance
+        app.add_systems(PostUpdate, (
+            EntranceAnimation::cancel_system,
+            EntranceAnimation::system,
+        ).chain().after(UiSystems::Compute).before(TransformSystem::TransformPropagate));
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::{prelude::*, transform::TransformSystem, window::PrimaryWindow};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// How a [`DespawnWithAnimation`] tree leaves
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum ExitKind {
+    /// To transparent
+    #[default]
+    Fade,
+    /// Off the left side of the window
+    SlideLeft,
+    /// To scaled down and transparent
+    Scale,
+}
+
+/// Insert this instead of despawning a tree, like a screen or a modal, to animate it out first. The tree is
+/// [`UiInert`] right away so its ghost can not be clicked, once the animation completes it is despawned
+/// recursively and [`DespawnAnimationFinished`] is sent.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct DespawnWithAnimation {
+    pub kind: ExitKind,
+    /// Seconds the animation takes
+    pub duration: f32,
+}
+impl Default for DespawnWithAnimation {
+    fn default() -> Self {
+        Self { kind: ExitKind::Fade, duration: 0.25 }
+    }
+}
+impl DespawnWithAnimation {
+    /// The size a [`ExitKind::Scale`] tree ends at
+    const SCALE: f32 = 0.8;
+    /// Creates new instance
+    pub fn new(kind: ExitKind, duration: f32) -> Self {
+        Self { kind, duration }
+    }
+    /// This system plays the exits on top of the computed layout and despawns the finished trees.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &DespawnWithAnimation, Option<&mut ExitState>)>,
+        descendants: Query<&Children>,
+        mut transforms: Query<&mut Transform>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<&mut TextColor>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+        mut finished: EventWriter<DespawnAnimationFinished>,
+    ) {
+        let width = windows.get_single().map_or(1920.0, |window| window.width());
+        for (entity, exit, state) in &mut query {
+            // Under reduced motion every exit is a quick fade
+            let kind = if motion_scale(&reduced) == 0.0 { ExitKind::Fade } else { exit.kind };
+            let mut fresh = None;
+            let node = match state {
+                Some(state) => &mut state.into_inner().0,
+                None => {
+                    commands.entity(entity).insert(UiInert);
+                    &mut fresh.insert(ExitState(AnimatedNode::new(entity, Tween::new(0.0, 1.0, reduced.duration(exit.duration)).easing(Easing::SineInOut)))).0
+                },
+            };
+
+            let progress = node.tween.tick(time.delta_secs());
+            let (slide, alpha, scale) = match kind {
+                ExitKind::Fade => (0.0, 1.0 - progress, 1.0),
+                ExitKind::SlideLeft => (-width * progress, 1.0, 1.0),
+                ExitKind::Scale => (0.0, 1.0 - progress, 1.0_f32.interpolate(&DespawnWithAnimation::SCALE, progress)),
+            };
+            node.apply(slide, alpha, scale, &descendants, &mut transforms, &mut sprites, &mut texts);
+
+            if node.tween.is_done() {
+                commands.entity(entity).despawn_recursive();
+                finished.send(DespawnAnimationFinished { entity });
+            } else if let Some(state) = fresh {
+                commands.entity(entity).insert(state);
+            }
+        }
+    }
+}
+
+/// Sent when the tree of a [`DespawnWithAnimation`] finished its animation and was despawned
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DespawnAnimationFinished {
+    pub entity: Entity,
+}
+
+/// The progress of a running [`DespawnWithAnimation`]
+#[derive(Component)]
+struct ExitState(AnimatedNode);
+
+
+/// Plugin with the [`DespawnWithAnimation`] exits
+pub(crate) struct ExitPlugin;
+impl Plugin for ExitPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ExitKind>();
+        app.add_event::<DespawnAnimationFinished>();
+        // After the layout wrote the transforms, so the offsets are never overwritten mid exit
+        app.add_systems(PostUpdate, DespawnWithAnimation::system.after(UiSystems::Compute).before(TransformSystem::TransformPropagate));
+    }
+}
@@ -0,0 +1,395 @@
+use bevy::{a11y::{accesskit::Action, AccessibilityNode, ActionRequest, Focus}, prelude::*};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Marks a widget that can be focused by gamepad navigation or with Tab
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct Focusable {
+    /// Disabled widgets are skipped
+    pub disabled: bool,
+    /// If left and right adjust the value of the widget instead of moving the focus, like on sliders
+    pub adjustable: bool,
+    /// If the widget handles Tab and Space itself while focused, like text inputs inserting spaces
+    pub captures_keys: bool,
+}
+impl Focusable {
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Skip this widget when moving the focus.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+    /// Left and right trigger [`FocusAdjust`] instead of moving the focus.
+    pub fn adjustable(mut self) -> Self {
+        self.adjustable = true;
+        self
+    }
+    /// Tab and Space are left to the widget while it is focused.
+    pub fn captures_keys(mut self) -> Self {
+        self.captures_keys = true;
+        self
+    }
+}
+
+/// Overrides the Tab order of a [`Focusable`]. Widgets are sorted by this value, widgets
+/// without it count as 0 and ties keep the spawn order.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[reflect(Component)]
+pub struct TabIndex(pub i32);
+
+/// The order in which the [`Focusable`] was spawned
+#[derive(Component, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct FocusSequence(u64);
+
+/// This event is triggered on the focused widget when South, Enter or Space is pressed
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FocusActivated;
+
+/// This event is triggered on the focused [`Focusable::adjustable`] widget, -1 for left and 1 for right
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FocusAdjust(pub i32);
+
+/// Sent when East or the [`UiNavInput`] back is pressed, screens should go back to the previous one
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UiBack;
+
+/// Sent when the focused widget changes, in any way
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FocusChanged {
+    pub old: Option<Entity>,
+    pub new: Option<Entity>,
+}
+
+/// Insert this on the root of a screen or dialog. While it exists only focusable widgets inside it can be
+/// focused, and once it is despawned the focus returns to the widget that was focused before it opened.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct FocusScope;
+
+/// The focused widget, the rectangles of all focusable widgets and their Tab order
+#[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct UiFocus {
+    /// If hovering a widget with the pointer focuses it
+    pub hover_sync: bool,
+    current: Option<Entity>,
+    reported: Option<Entity>,
+    index: Vec<(Entity, Rect)>,
+    order: Vec<Entity>,
+    /// Open scopes with the widget focused before each opened
+    scopes: Vec<(Entity, Option<Entity>)>,
+}
+impl Default for UiFocus {
+    fn default() -> Self {
+        Self {
+            hover_sync: false,
+            current: None,
+            reported: None,
+            index: Vec::new(),
+            order: Vec::new(),
+            scopes: Vec::new(),
+        }
+    }
+}
+impl UiFocus {
+    /// Seconds before a held stick starts repeating
+    const REPEAT_DELAY: f32 = 0.4;
+    /// Seconds between the repeated moves of a held stick
+    const REPEAT_RATE: f32 = 0.12;
+    /// Pixels kept between a widget scrolled into view and the edge of its viewport
+    const SCROLL_MARGIN: f32 = 16.0;
+    /// Seconds a widget takes to scroll into view
+    const SCROLL_DURATION: f32 = 0.25;
+    /// Returns the focused widget
+    pub fn current(&self) -> Option<Entity> {
+        self.current
+    }
+    /// Focuses the widget, it may be spawned in the same frame
+    pub fn set(&mut self, entity: Entity) {
+        self.current = Some(entity);
+    }
+    /// Leaves nothing focused
+    pub fn clear(&mut self) {
+        self.current = None;
+    }
+    /// The rectangles of the widgets that can be focused now
+    pub(crate) fn rects(&self) -> &[(Entity, Rect)] {
+        &self.index
+    }
+    /// Returns the nearest widget in the direction, or the top left one if nothing is focused
+    fn find(&self, direction: Vec2) -> Option<Entity> {
+        let Some(from) = self.current.and_then(|current| self.index.iter().find(|(entity, _)| *entity == current)) else {
+            let key = |rect: &Rect| (rect.center().y, -rect.center().x);
+            return self.index.iter().max_by(|(_, a), (_, b)| {
+                let ((ay, ax), (by, bx)) = (key(a), key(b));
+                ay.total_cmp(&by).then(ax.total_cmp(&bx))
+            }).map(|(entity, _)| *entity);
+        };
+        self.index.iter().filter(|(entity, _)| *entity != from.0).filter_map(|(entity, rect)| {
+            let offset = rect.center() - from.1.center();
+            if offset.dot(direction) <= 0.0 { return None; }
+            // Measure the gap between the edges, so wide and tall widgets are not penalized
+            let gap = (offset.abs() - (rect.half_size() + from.1.half_size())).max(Vec2::ZERO);
+            let along = gap.dot(direction.abs());
+            let across = (offset - direction * offset.dot(direction)).length();
+            Some((*entity, along + across * 2.0))
+        }).min_by(|(_, a), (_, b)| a.total_cmp(b)).map(|(entity, _)| entity)
+    }
+    /// Returns the next widget in the Tab order, or the previous one when going back
+    fn step(&self, back: bool) -> Option<Entity> {
+        let len = self.order.len();
+        if len == 0 { return None; }
+        let next = match self.current.and_then(|current| self.order.iter().position(|entity| *entity == current)) {
+            Some(i) if back => (i + len - 1) % len,
+            Some(i) => (i + 1) % len,
+            None if back => len - 1,
+            None => 0,
+        };
+        Some(self.order[next])
+    }
+    /// This system numbers the newly spawned focusable widgets in spawn order.
+    fn sequence_system(mut commands: Commands, query: Query<Entity, Added<Focusable>>, mut counter: Local<u64>) {
+        for entity in &query {
+            commands.entity(entity).insert(FocusSequence(*counter));
+            *counter += 1;
+        }
+    }
+    /// This system remembers the focus when a scope opens and restores it when the scope is despawned.
+    fn scope_system(mut focus: ResMut<UiFocus>, opened: Query<Entity, Added<FocusScope>>, mut closed: RemovedComponents<FocusScope>) {
+        for entity in &opened {
+            let current = focus.current;
+            focus.scopes.push((entity, current));
+        }
+        for entity in closed.read() {
+            let Some(i) = focus.scopes.iter().position(|(scope, _)| *scope == entity) else { continue; };
+            let (_, previous) = focus.scopes.remove(i);
+            // Only the topmost scope owns the focus, closing one below it changes nothing
+            if i == focus.scopes.len() { focus.current = previous; }
+        }
+    }
+    /// This system rebuilds the index when focusable widgets are spawned, despawned, moved or hidden.
+    #[allow(clippy::type_complexity, clippy::too_many_arguments)]
+    fn index_system(
+        mut focus: ResMut<UiFocus>,
+        query: Query<(Entity, &Focusable, &GlobalTransform, &Dimension, &InheritedVisibility, Option<&TabIndex>, Option<&FocusSequence>)>,
+        changed: Query<(), (With<Focusable>, Or<(Changed<Focusable>, Changed<GlobalTransform>, Changed<Dimension>, Changed<InheritedVisibility>, Changed<TabIndex>, Changed<FocusSequence>)>)>,
+        mut removed: RemovedComponents<Focusable>,
+        scopes: Query<(), Or<(Added<FocusScope>, Changed<FocusScope>)>>,
+        mut closed: RemovedComponents<FocusScope>,
+        focusables: Query<&Focusable>,
+        parents: Query<&Parent>,
+        layers: UiLayers,
+    ) {
+        if changed.is_empty() && scopes.is_empty() && removed.read().count() == 0 && closed.read().count() == 0 && !focus.is_changed() { return; }
+
+        // Only the widgets inside the topmost scope take part
+        let scope = focus.scopes.last().map(|(scope, _)| *scope);
+        let in_scope = |entity: Entity| scope.is_none_or(|scope| parents.iter_ancestors(entity).any(|ancestor| ancestor == scope));
+        let mut entries: Vec<_> = query.iter()
+            .filter(|(entity, focusable, _, _, visibility, ..)| !focusable.disabled && visibility.get() && in_scope(*entity) && !layers.is_blocked(*entity))
+            .map(|(entity, _, transform, dimension, _, tab, sequence)| {
+                (entity, Rect::from_center_size(transform.translation().truncate(), dimension.0), (tab.copied().unwrap_or_default(), sequence.map_or(u64::MAX, |sequence| sequence.0)))
+            })
+            .collect();
+        entries.sort_by_key(|(.., key)| *key);
+        let order: Vec<Entity> = entries.iter().map(|(entity, ..)| *entity).collect();
+        let index = entries.into_iter().map(|(entity, rect, _)| (entity, rect)).collect();
+
+        // When the focused widget is despawned or disabled, advance to the next remaining one in the old order
+        if let Some(current) = focus.current {
+            if focusables.get(current).is_none_or(|focusable| focusable.disabled) {
+                let position = focus.order.iter().position(|entity| *entity == current).unwrap_or(focus.order.len());
+                focus.current = focus.order[position..].iter().chain(&focus.order[..position]).find(|entity| order.contains(entity)).copied();
+            }
+        }
+        focus.bypass_change_detection().order = order;
+        focus.bypass_change_detection().index = index;
+    }
+    /// This system focuses the widgets hovered by the pointer if enabled.
+    fn hover_system(mut focus: ResMut<UiFocus>, mut events: EventReader<Pointer<Over>>, focusables: Query<&Focusable>) {
+        for event in events.read() {
+            if focus.hover_sync && focusables.get(event.target).is_ok_and(|focusable| !focusable.disabled) && focus.current != Some(event.target) {
+                focus.current = Some(event.target);
+            }
+        }
+    }
+    /// This system sends [`FocusChanged`] after the focus moved.
+    fn change_system(mut focus: ResMut<UiFocus>, mut events: EventWriter<FocusChanged>) {
+        if focus.reported == focus.current { return; }
+        events.send(FocusChanged { old: focus.reported, new: focus.current });
+        focus.bypass_change_detection().reported = focus.current;
+    }
+    /// This system performs the actions requested by assistive technology, invoking a widget
+    /// triggers [`FocusActivated`] on it, the same path the gamepad and keyboard take.
+    fn action_system(mut commands: Commands, mut focus: ResMut<UiFocus>, mut requests: EventReader<ActionRequest>, focusables: Query<&Focusable>) {
+        for request in requests.read() {
+            let Ok(target) = Entity::try_from_bits(request.target.0) else { continue; };
+            match request.action {
+                Action::Click => commands.trigger_targets(FocusActivated, target),
+                Action::Focus if focusables.get(target).is_ok_and(|focusable| !focusable.disabled) => focus.current = Some(target),
+                _ => {},
+            }
+        }
+    }
+    /// This system moves the accessibility focus along with the focus and announces the newly focused widget.
+    fn accessibility_system(
+        mut events: EventReader<FocusChanged>,
+        focus: Option<ResMut<Focus>>,
+        nodes: Query<&AccessibilityNode>,
+        mut announce: EventWriter<Announce>,
+    ) {
+        let Some(changed) = events.read().last() else { return; };
+        if let Some(mut focus) = focus {
+            if focus.0 != changed.new { focus.0 = changed.new; }
+        }
+        if let Some(label) = changed.new.and_then(|new| nodes.get(new).ok()).and_then(|node| node.label()) {
+            announce.send(Announce::new(label).key("focus"));
+        }
+    }
+    /// This system scrolls the newly focused widget into view of the [`Scrollable`] viewports it is inside of.
+    fn scroll_system(mut commands: Commands, focus: Res<UiFocus>, mut events: EventReader<FocusChanged>, containers: ScrollContainers, reduced: Res<ReducedMotion>) {
+        let Some(new) = events.read().last().and_then(|changed| changed.new) else { return; };
+        let Some((_, rect)) = focus.index.iter().find(|(entity, _)| *entity == new) else { return; };
+        for (container, delta) in containers.reveal(new, *rect, UiFocus::SCROLL_MARGIN) {
+            commands.entity(container).insert(ScrollAnimation::new(delta, UiFocus::SCROLL_DURATION * motion_scale(&reduced)));
+        }
+    }
+    /// This system moves the focus with the d-pad or left stick and forwards the activate and back buttons,
+    /// or the [`UiNavInput`] actions when they are bound.
+    fn input_system(
+        mut commands: Commands,
+        mut focus: ResMut<UiFocus>,
+        nav: UiNavInput,
+        focusables: Query<&Focusable>,
+        cursor: Res<VirtualCursor>,
+        mut back: EventWriter<UiBack>,
+        mut held: Local<(Vec2, f32)>,
+        time: Res<Time>,
+    ) {
+        let UiNavFrame { mut direction, held: stick, activate, back: go_back } = nav.read();
+
+        // The virtual cursor clicks with South instead
+        if activate && !cursor.active {
+            if let Some(current) = focus.current { commands.trigger_targets(FocusActivated, current); }
+        }
+        if go_back { back.send(UiBack); }
+
+        // Snap the stick to the main axis and repeat while it is held
+        if stick != Vec2::ZERO {
+            let snapped = if stick.x.abs() > stick.y.abs() { Vec2::new(stick.x.signum(), 0.0) } else { Vec2::new(0.0, stick.y.signum()) };
+            if held.0 != snapped {
+                *held = (snapped, UiFocus::REPEAT_DELAY);
+                direction = snapped;
+            } else {
+                held.1 -= time.delta_secs();
+                if held.1 <= 0.0 {
+                    held.1 = UiFocus::REPEAT_RATE;
+                    direction = snapped;
+                }
+            }
+        } else {
+            held.0 = Vec2::ZERO;
+        }
+        if direction == Vec2::ZERO { return; }
+
+        // Adjustable widgets take the horizontal input themselves
+        if let Some(current) = focus.current {
+            if direction.y == 0.0 && focusables.get(current).is_ok_and(|focusable| focusable.adjustable) {
+                commands.trigger_targets(FocusAdjust(direction.x as i32), current);
+                return;
+            }
+        }
+        if let Some(next) = focus.find(direction) {
+            focus.current = Some(next);
+            commands.send_event(PlayUiSound(UiSoundKind::Focus));
+        }
+    }
+    /// This system moves the focus with Tab and Shift+Tab and activates the focused widget with Enter or Space.
+    fn keyboard_system(
+        mut commands: Commands,
+        mut focus: ResMut<UiFocus>,
+        keys: Res<ButtonInput<KeyCode>>,
+        focusables: Query<&Focusable>,
+        nav: UiNavInput,
+    ) {
+        // Widgets like text inputs keep the keys, so typing is not interrupted
+        let captured = focus.current.and_then(|current| focusables.get(current).ok()).is_some_and(|focusable| focusable.captures_keys);
+        if keys.just_pressed(KeyCode::Tab) && !captured {
+            let back = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+            if let Some(next) = focus.step(back) {
+                focus.current = Some(next);
+                commands.send_event(PlayUiSound(UiSoundKind::Focus));
+            }
+        }
+        // The bound actions activate with Enter themselves
+        let enter = !nav.uses_actions() && keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter]);
+        if enter || (keys.just_pressed(KeyCode::Space) && !captured) {
+            if let Some(current) = focus.current { commands.trigger_targets(FocusActivated, current); }
+        }
+    }
+    /// This system keeps the focus ring around the focused widget.
+    fn ring_system(
+        mut commands: Commands,
+        focus: Res<UiFocus>,
+        mut rings: Query<(&mut Transform, &mut Sprite, &mut Visibility), With<FocusRing>>,
+    ) {
+        let rect = focus.current.and_then(|current| focus.index.iter().find(|(entity, _)| *entity == current)).map(|(_, rect)| *rect);
+        let Ok((mut transform, mut sprite, mut visibility)) = rings.get_single_mut() else {
+            commands.spawn((
+                FocusRing,
+                ThemedColor::alpha(ThemeRole::Accent, 0.15),
+                Sprite::default(),
+                Transform::default(),
+                Visibility::Hidden,
+            ));
+            return;
+        };
+        let Some(rect) = rect else {
+            visibility.set_if_neq(Visibility::Hidden);
+            return;
+        };
+        // Draw it above the widgets, padded a little
+        transform.translation = rect.center().extend(500.0);
+        sprite.custom_size = Some(rect.size() + Vec2::splat(8.0));
+        visibility.set_if_neq(Visibility::Visible);
+    }
+}
+
+/// The translucent highlight drawn over the focused widget
+#[derive(Component)]
+pub struct FocusRing;
+
+
+/// Plugin with gamepad and keyboard navigation between [`Focusable`] widgets
+pub(crate) struct FocusPlugin;
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiFocus>();
+        app.register_type::<Focusable>();
+        app.register_type::<TabIndex>();
+        app.register_type::<FocusScope>();
+        app.register_type::<UiFocus>();
+        app.add_event::<UiBack>();
+        app.add_event::<FocusChanged>();
+        app.add_systems(Update, (
+            UiFocus::sequence_system,
+            UiFocus::scope_system,
+            UiFocus::index_system,
+            UiFocus::hover_system,
+            UiFocus::action_system.run_if(on_event::<ActionRequest>),
+            UiFocus::input_system,
+            UiFocus::keyboard_system,
+            UiFocus::change_system,
+            UiFocus::accessibility_system,
+            UiFocus::scroll_system,
+            UiFocus::ring_system,
+        ).chain());
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::{asset::RenderAssetUsages, prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}, sprite::Anchor};
+use bevy_lunex::*;
+use game_vfx::*;
+use rand::Rng;
+
+use crate::*;
+
+
+/// Insert this on a [`MainButton`] or any sprite node to glitch it while hovered, for the Bevypunk look.
+/// A faint scanline overlay covers the image and every interval a few frames of horizontal slices jump
+/// sideways. Only the sprite anchors of its own nodes are moved, so the hover layout of the button is
+/// left alone. Reduced motion turns it off.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct GlitchOnHover {
+    /// Strength of the slice offsets and the scanlines in range 0.0 to 1.0
+    pub intensity: f32,
+    /// Seconds between the glitch bursts, varied a little so they do not tick
+    pub interval: f32,
+}
+impl Default for GlitchOnHover {
+    fn default() -> Self {
+        Self { intensity: 0.5, interval: 0.7 }
+    }
+}
+impl GlitchOnHover {
+    /// Seconds one burst lasts, a few frames
+    const BURST: f32 = 0.08;
+    /// The vertical bands of the slices, center and height in percent of the image
+    const SLICES: [(f32, f32); 3] = [(22.0, 8.0), (51.0, 5.0), (74.0, 10.0)];
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Replace the default intensity with a new one.
+    pub fn intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity.clamp(0.0, 1.0);
+        self
+    }
+    /// Replace the default interval with a new one.
+    pub fn interval(mut self, interval: f32) -> Self {
+        self.interval = interval.max(GlitchOnHover::BURST);
+        self
+    }
+    /// This system spawns the scanlines and the slices under the image once the button is built.
+    fn build_system(
+        mut commands: Commands,
+        query: Query<(Entity, &GlitchOnHover, Option<&MainButtonControl>, Has<MainButton>), Without<GlitchRig>>,
+        mut scanlines: Local<Option<Handle<Image>>>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
+        for (entity, glitch, control, is_button) in &query {
+            // Wait for the internals of the button
+            if is_button && control.is_none() { continue; }
+            let image = control.map_or(entity, |control| control.image());
+            let texture = scanlines.get_or_insert_with(|| images.add(scanline_texture())).clone();
+
+            let mut rig = GlitchRig { image, scanlines: Entity::PLACEHOLDER, slices: Vec::new(), hovered: false, clock: 0.0, burst: 0.0, anchor: None };
+            commands.entity(image).with_children(|ui| {
+                rig.scanlines = ui.spawn((
+                    UiLayout::window().full().pack(),
+                    UiDepth::Add(0.2),
+                    Sprite {
+                        image: texture.clone(),
+                        color: Color::BLACK.with_alpha(0.15 + 0.35 * glitch.intensity),
+                        image_mode: SpriteImageMode::Tiled { tile_x: true, tile_y: true, stretch_value: 1.0 },
+                        ..default()
+                    },
+                    PickingBehavior::IGNORE,
+                    Visibility::Hidden,
+                )).id();
+                for (y, height) in GlitchOnHover::SLICES {
+                    rig.slices.push(ui.spawn((
+                        UiLayout::window().y(Rl(y)).anchor(Anchor::CenterLeft).size(Rl((100.0, height))).pack(),
+                        UiDepth::Add(0.25),
+                        Sprite::default(),
+                        PickingBehavior::IGNORE,
+                        Visibility::Hidden,
+                    )).id());
+                }
+            });
+            commands.entity(entity).insert(rig);
+            commands.trigger(RecomputeUiLayout);
+        }
+    }
+    /// This system runs the bursts of the hovered glitches and hides everything once they are left.
+    fn system(
+        mut query: Query<(&GlitchOnHover, &mut GlitchRig)>,
+        mut sprites: Query<(&mut Sprite, &mut Visibility)>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+    ) {
+        let mut rng = rand::rng();
+        for (glitch, mut rig) in &mut query {
+            let active = rig.hovered && motion_scale(&reduced) > 0.0 && glitch.intensity > 0.0;
+            if !active && rig.clock == 0.0 && rig.burst == 0.0 && rig.anchor.is_none() { continue; }
+
+            if active {
+                rig.clock += time.delta_secs();
+                if rig.clock >= glitch.interval {
+                    rig.clock = rng.random_range(-0.3..0.3) * glitch.interval;
+                    rig.burst = GlitchOnHover::BURST;
+                }
+            } else {
+                rig.clock = 0.0;
+                rig.burst = 0.0;
+            }
+            let bursting = rig.burst > 0.0;
+            rig.burst = (rig.burst - time.delta_secs()).max(0.0);
+
+            if let Ok((_, mut visibility)) = sprites.get_mut(rig.scanlines) {
+                visibility.set_if_neq(if active { Visibility::Inherited } else { Visibility::Hidden });
+            }
+
+            // The image color is animated by the button, the slices copy it every frame they show
+            let Ok((mut image, _)) = sprites.get_mut(rig.image) else { continue; };
+            let color = image.color;
+            if bursting {
+                let original = *rig.anchor.get_or_insert(image.anchor);
+                image.anchor = Anchor::Custom(original.as_vec() + Vec2::X * rng.random_range(-0.02..0.02) * glitch.intensity);
+            } else if let Some(original) = rig.anchor.take() {
+                image.anchor = original;
+            }
+
+            for slice in &rig.slices {
+                let Ok((mut sprite, mut visibility)) = sprites.get_mut(*slice) else { continue; };
+                let shown = bursting && rng.random_bool(0.7);
+                visibility.set_if_neq(if shown { Visibility::Inherited } else { Visibility::Hidden });
+                if !shown { continue; }
+                sprite.color = color.with_alpha(color.alpha() * 0.8);
+                sprite.anchor = Anchor::Custom(Vec2::X * rng.random_range(-0.08..0.08) * glitch.intensity);
+            }
+        }
+    }
+    /// This system despawns the nodes of removed glitches and rebuilds the ones of restyled buttons.
+    fn cleanup_system(mut commands: Commands, mut removed: RemovedComponents<GlitchOnHover>, query: Query<(Entity, &GlitchRig, Option<&MainButtonControl>)>) {
+        for entity in removed.read() {
+            let Ok((_, rig, _)) = query.get(entity) else { continue; };
+            for node in std::iter::once(rig.scanlines).chain(rig.slices.iter().copied()) {
+                commands.entity(node).despawn_recursive();
+            }
+            commands.entity(entity).remove::<GlitchRig>();
+        }
+        // The old nodes went down with the old image
+        for (entity, rig, control) in &query {
+            if control.is_some_and(|control| control.image() != rig.image) { commands.entity(entity).remove::<GlitchRig>(); }
+        }
+    }
+}
+
+/// The nodes and the state of a built [`GlitchOnHover`]
+#[derive(Component)]
+struct GlitchRig {
+    image: Entity,
+    scanlines: Entity,
+    slices: Vec<Entity>,
+    hovered: bool,
+    /// Seconds since the last burst
+    clock: f32,
+    /// Seconds left of the running burst
+    burst: f32,
+    /// The anchor of the image before the burst moved it
+    anchor: Option<Anchor>,
+}
+
+/// Creates a tiny texture of dark rows, tiled over the image as scanlines
+fn scanline_texture() -> Image {
+    let mut data = Vec::new();
+    for alpha in [255, 0, 0] { data.extend_from_slice(&[255, 255, 255, alpha]); }
+    Image::new(
+        Extent3d { width: 1, height: 3, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+
+/// Plugin with the [`GlitchOnHover`] effect
+pub(crate) struct GlitchPlugin;
+impl Plugin for GlitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(|trigger: Trigger<Pointer<Over>>, mut query: Query<&mut GlitchRig>, layers: UiLayers| {
+            if layers.is_blocked(trigger.entity()) { return; }
+            if let Ok(mut rig) = query.get_mut(trigger.entity()) { rig.hovered = true; }
+        });
+        app.add_observer(|trigger: Trigger<Pointer<Out>>, mut query: Query<&mut GlitchRig>| {
+            if let Ok(mut rig) = query.get_mut(trigger.entity()) { rig.hovered = false; }
+        });
+        app.add_systems(Update, (
+            GlitchOnHover::cleanup_system,
+            GlitchOnHover::build_system,
+            GlitchOnHover::system,
+        ).chain().after(SandboxedUiSystems).run_if(any_with_component::<GlitchOnHover>.or(any_component_removed::<GlitchOnHover>)));
+    }
+}
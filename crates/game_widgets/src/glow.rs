@@ -0,0 +1,157 @@
+use bevy::{asset::RenderAssetUsages, prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}, sprite::Anchor};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Insert this on a node to draw the eye to it, like a "NEW" menu item or the suggested button of a dialog.
+/// A soft glow a little larger than the node is spawned behind it and its alpha breathes on the period.
+/// The glow is a child node, so it follows the node as its layout animates. It rests while the node is
+/// hovered, the hover highlight takes over, and stays still under reduced motion.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct PulseGlow {
+    pub color: Color,
+    /// Seconds of one breath
+    pub period: f32,
+    /// The alpha at the peak of the breath in range 0.0 to 1.0
+    pub strength: f32,
+}
+impl PulseGlow {
+    /// How far the glow reaches past the node, in percent of its height
+    const SPREAD: f32 = 30.0;
+    /// Size of the glow texture border in pixels, the soft part
+    const BORDER: u32 = 24;
+    /// Creates new instance
+    pub fn new(color: Color) -> Self {
+        Self { color, period: 2.0, strength: 0.6 }
+    }
+    /// Replace the default period with a new one.
+    pub fn period(mut self, period: f32) -> Self {
+        self.period = period;
+        self
+    }
+    /// Replace the default strength with a new one.
+    pub fn strength(mut self, strength: f32) -> Self {
+        self.strength = strength.clamp(0.0, 1.0);
+        self
+    }
+    /// This system spawns the glow behind new nodes.
+    fn build_system(
+        mut commands: Commands,
+        query: Query<(Entity, &PulseGlow), Without<PulseGlowRig>>,
+        mut texture: Local<Option<Handle<Image>>>,
+        mut images: ResMut<Assets<Image>>,
+    ) {
+        for (entity, glow) in &query {
+            let texture = texture.get_or_insert_with(|| images.add(glow_texture(PulseGlow::BORDER))).clone();
+            let mut node = Entity::PLACEHOLDER;
+            commands.entity(entity).with_children(|ui| {
+                node = ui.spawn((
+                    Name::new("Pulse Glow"),
+                    UiLayout::window()
+                        .pos(Rl(50.0))
+                        .anchor(Anchor::Center)
+                        .size((Rl(100.0) + Rh(PulseGlow::SPREAD * 2.0), Rl(100.0) + Rh(PulseGlow::SPREAD * 2.0)))
+                        .pack(),
+                    UiDepth::Add(-0.5),
+                    Sprite {
+                        image: texture,
+                        color: glow.color.with_alpha(0.0),
+                        image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(PulseGlow::BORDER as f32), ..default() }),
+                        ..default()
+                    },
+                    PickingBehavior::IGNORE,
+                )).id();
+            });
+            commands.entity(entity).insert(PulseGlowRig { node, hovered: false, clock: 0.0, alpha: 0.0 });
+            commands.trigger(RecomputeUiLayout);
+        }
+    }
+    /// This system breathes the glows, fading them out while their node is hovered.
+    fn system(
+        mut query: Query<(&PulseGlow, &mut PulseGlowRig, Option<&MainButtonControl>)>,
+        mut sprites: Query<&mut Sprite>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+    ) {
+        for (glow, mut rig, control) in &mut query {
+            let hovered = rig.hovered || control.is_some_and(|control| control.transition > 0.0);
+            let target = if hovered {
+                0.0
+            } else if motion_scale(&reduced) == 0.0 || glow.period <= 0.0 {
+                glow.strength
+            } else {
+                rig.clock = (rig.clock + time.delta_secs()) % glow.period;
+                let breath = 0.5 - 0.5 * (std::f32::consts::TAU * rig.clock / glow.period).cos();
+                glow.strength * (0.25 + 0.75 * breath)
+            };
+
+            // Ease in and out of the hover instead of popping
+            rig.alpha += (target - rig.alpha) * (time.delta_secs() * 12.0).min(1.0);
+            let Ok(mut sprite) = sprites.get_mut(rig.node) else { continue; };
+            let color = glow.color.with_alpha(glow.color.alpha() * rig.alpha);
+            if sprite.color != color { sprite.color = color; }
+        }
+    }
+    /// This system despawns the glows of removed components.
+    fn cleanup_system(mut commands: Commands, mut removed: RemovedComponents<PulseGlow>, query: Query<&PulseGlowRig>) {
+        for entity in removed.read() {
+            let Ok(rig) = query.get(entity) else { continue; };
+            commands.entity(rig.node).despawn_recursive();
+            commands.entity(entity).remove::<PulseGlowRig>();
+        }
+    }
+}
+
+/// The glow node and the breathing state of a [`PulseGlow`]
+#[derive(Component)]
+struct PulseGlowRig {
+    node: Entity,
+    hovered: bool,
+    /// Seconds into the current breath
+    clock: f32,
+    /// The shown alpha, eased towards the breath
+    alpha: f32,
+}
+
+/// Creates a white square fading out towards its edges, sliced so the soft border keeps its size on any node
+fn glow_texture(border: u32) -> Image {
+    let size = border * 2 + 2;
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let edge = x.min(y).min(size - 1 - x).min(size - 1 - y) as f32 / border as f32;
+            let t = edge.min(1.0);
+            let alpha = t * t * (3.0 - 2.0 * t);
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.0) as u8]);
+        }
+    }
+    Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+
+/// Plugin with the [`PulseGlow`] highlights
+pub(crate) struct PulseGlowPlugin;
+impl Plugin for PulseGlowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(|trigger: Trigger<Pointer<Over>>, mut query: Query<&mut PulseGlowRig>, layers: UiLayers| {
+            if layers.is_blocked(trigger.entity()) { return; }
+            if let Ok(mut rig) = query.get_mut(trigger.entity()) { rig.hovered = true; }
+        });
+        app.add_observer(|trigger: Trigger<Pointer<Out>>, mut query: Query<&mut PulseGlowRig>| {
+            if let Ok(mut rig) = query.get_mut(trigger.entity()) { rig.hovered = false; }
+        });
+        app.add_systems(Update, (
+            PulseGlow::cleanup_system,
+            PulseGlow::build_system,
+            PulseGlow::system,
+        ).chain().after(SandboxedUiSystems).run_if(any_with_component::<PulseGlow>.or(any_component_removed::<PulseGlow>)));
+    }
+}
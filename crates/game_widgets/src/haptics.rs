@@ -0,0 +1,139 @@
+use std::time::Duration;
+
+use bevy::{input::{gamepad::{GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadRumbleIntensity, GamepadRumbleRequest}, mouse::MouseMotion}, prelude::*};
+use game_preferences::*;
+
+use crate::*;
+
+
+/// A rumble pattern of the user interface
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HapticPulse {
+    /// A light tick when the focus moves
+    Tick,
+    /// A firmer pulse when a widget is activated
+    Confirm,
+    /// Two short buzzes when something can not be done
+    Error,
+}
+impl HapticPulse {
+    /// The strength at full intensity and the duration of a single buzz in seconds
+    fn buzz(&self) -> (f32, f32) {
+        match self {
+            HapticPulse::Tick => (0.15, 0.04),
+            HapticPulse::Confirm => (0.45, 0.08),
+            HapticPulse::Error => (0.6, 0.07),
+        }
+    }
+}
+
+/// Rumbles the gamepad that was used last on user interface feedback. The rumble follows the
+/// [`PlayUiSound`] events and focus changes, so widgets do not need to request it themselves.
+#[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct UiHaptics {
+    pub enabled: bool,
+    /// Multiplier of all pulses in range 0.0 to 1.0
+    pub intensity: f32,
+    /// The gamepad that produced input most recently, cleared when the mouse is used
+    gamepad: Option<Entity>,
+    /// The time the current buzz ends and the second buzz of an error if pending
+    busy_until: f32,
+    pending: Option<(f32, HapticPulse)>,
+}
+impl Default for UiHaptics {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.0,
+            gamepad: None,
+            busy_until: 0.0,
+            pending: None,
+        }
+    }
+}
+impl UiHaptics {
+    /// Pause between the two buzzes of an error
+    const GAP: f32 = 0.06;
+    /// Starts a single buzz, replacing the running one so they never add up into a long vibration
+    fn buzz(&mut self, pulse: HapticPulse, now: f32, requests: &mut EventWriter<GamepadRumbleRequest>) {
+        let Some(gamepad) = self.gamepad else { return; };
+        let (strength, duration) = pulse.buzz();
+        requests.send(GamepadRumbleRequest::Stop { gamepad });
+        requests.send(GamepadRumbleRequest::Add {
+            gamepad,
+            duration: Duration::from_secs_f32(duration),
+            intensity: GamepadRumbleIntensity::weak_motor(strength * self.intensity.clamp(0.0, 1.0)),
+        });
+        self.busy_until = now + duration;
+    }
+    /// This system tracks which gamepad was used last.
+    fn input_system(
+        mut haptics: ResMut<UiHaptics>,
+        mut buttons: EventReader<GamepadButtonChangedEvent>,
+        mut axes: EventReader<GamepadAxisChangedEvent>,
+        mut motion: EventReader<MouseMotion>,
+    ) {
+        let used = buttons.read().map(|event| event.entity).chain(axes.read().filter(|event| event.value.abs() > 0.5).map(|event| event.entity)).last();
+        if let Some(gamepad) = used {
+            if haptics.gamepad != Some(gamepad) { haptics.gamepad = Some(gamepad); }
+        } else if motion.read().count() > 0 && haptics.gamepad.is_some() {
+            haptics.gamepad = None;
+        }
+    }
+    /// This system turns the feedback into rumble pulses.
+    fn system(
+        mut haptics: ResMut<UiHaptics>,
+        mut focus: EventReader<FocusChanged>,
+        mut sounds: EventReader<PlayUiSound>,
+        mut requests: EventWriter<GamepadRumbleRequest>,
+        time: Res<Time>,
+    ) {
+        let now = time.elapsed_secs();
+        let strongest = focus.read().filter(|event| event.new.is_some()).map(|_| HapticPulse::Tick)
+            .chain(sounds.read().filter_map(|PlayUiSound(kind)| match kind {
+                UiSoundKind::Click | UiSoundKind::ToggleOn | UiSoundKind::ToggleOff => Some(HapticPulse::Confirm),
+                UiSoundKind::Error => Some(HapticPulse::Error),
+                _ => None,
+            }))
+            .max_by_key(|pulse| *pulse as u8);
+        if !haptics.enabled || haptics.gamepad.is_none() {
+            haptics.pending = None;
+            return;
+        }
+
+        // Only the strongest pulse of the frame plays, a tick never cuts off a running confirm
+        if let Some(pulse) = strongest {
+            if pulse != HapticPulse::Tick || now >= haptics.busy_until {
+                haptics.buzz(pulse, now, &mut requests);
+                haptics.pending = (pulse == HapticPulse::Error).then(|| (now + pulse.buzz().1 + UiHaptics::GAP, pulse));
+            }
+        }
+        if let Some((at, pulse)) = haptics.pending {
+            if now >= at {
+                haptics.pending = None;
+                haptics.buzz(pulse, now, &mut requests);
+            }
+        }
+    }
+    /// This system applies the haptics settings.
+    fn settings_system(settings: Res<GameSettings>, mut haptics: ResMut<UiHaptics>) {
+        haptics.enabled = settings.haptics;
+        haptics.intensity = settings.haptics_intensity;
+    }
+}
+
+
+/// Plugin with the [`UiHaptics`] feedback
+pub(crate) struct UiHapticsPlugin;
+impl Plugin for UiHapticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiHaptics>();
+        app.register_type::<UiHaptics>();
+        app.add_systems(Update, (
+            UiHaptics::settings_system.run_if(resource_changed::<GameSettings>),
+            UiHaptics::input_system,
+            UiHaptics::system,
+        ).chain());
+    }
+}
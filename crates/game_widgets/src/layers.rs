@@ -0,0 +1,152 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// The stacking layer of a root tree, higher layers are drawn and picked above lower ones.
+/// Trees without one are on layer 0.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[reflect(Component)]
+pub struct UiLayer(pub u32);
+
+/// Insert this on a root tree with [`UiLayer`], like a modal, to stop all interaction with the layers below it.
+/// A dimmed backdrop catching the pointer is spawned under its content for you.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct UiBlocking;
+impl UiBlocking {
+    /// This system spawns the backdrop of newly added blocking trees and announces them.
+    fn backdrop_system(mut commands: Commands, query: Query<(Entity, Option<&Name>), Added<UiBlocking>>, mut announce: EventWriter<Announce>) {
+        for (entity, name) in &query {
+            announce.send(Announce::new(name.map_or("Dialog".to_string(), |name| name.to_string())).assertive());
+            commands.entity(entity).with_children(|ui| {
+                ui.spawn((
+                    UiLayout::window().full().pack(),
+                    ThemedColor::alpha(ThemeRole::Background, 0.6),
+                    Sprite::default(),
+                    UiBackdrop,
+                ));
+            });
+        }
+    }
+}
+
+/// The backdrop of a [`UiBlocking`] tree, it is pickable so the pointer does not reach the layers below
+#[derive(Component)]
+pub struct UiBackdrop;
+
+/// Insert this on a node to ignore interaction with it and everything under it, like while it animates in
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct UiInert;
+
+/// Tells on which [`UiLayer`] nodes are and if interaction with them is blocked
+#[derive(SystemParam)]
+pub struct UiLayers<'w, 's> {
+    layers: Query<'w, 's, (&'static UiLayer, Has<UiBlocking>, &'static InheritedVisibility)>,
+    inert: Query<'w, 's, (), With<UiInert>>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+impl UiLayers<'_, '_> {
+    /// Returns the layer of the tree the entity belongs to
+    pub fn layer_of(&self, entity: Entity) -> u32 {
+        std::iter::once(entity).chain(self.parents.iter_ancestors(entity))
+            .find_map(|entity| self.layers.get(entity).ok())
+            .map_or(0, |(layer, ..)| layer.0)
+    }
+    /// Returns the topmost visible blocking layer
+    pub fn blocking(&self) -> Option<u32> {
+        self.layers.iter().filter(|(_, blocking, visibility)| *blocking && visibility.get()).map(|(layer, ..)| layer.0).max()
+    }
+    /// If the entity is below the topmost blocking layer or [`UiInert`], its events should be ignored
+    pub fn is_blocked(&self, entity: Entity) -> bool {
+        if std::iter::once(entity).chain(self.parents.iter_ancestors(entity)).any(|entity| self.inert.contains(entity)) { return true; }
+        self.blocking().is_some_and(|top| self.layer_of(entity) < top)
+    }
+}
+
+
+/// Plugin with the [`UiLayer`] blocking
+pub(crate) struct UiLayerPlugin;
+impl Plugin for UiLayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<UiLayer>();
+        app.register_type::<UiBlocking>();
+        app.register_type::<UiInert>();
+        app.add_systems(Update, UiBlocking::backdrop_system.run_if(any_with_component::<UiBlocking>));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::SystemState, prelude::*};
+    use bevy_lunex::{Dimension, UiLayoutRoot};
+
+    use crate::*;
+
+    /// Opens a blocking modal on the layer above the buttons of the test app, returns its root
+    fn open_modal(test: &mut UiTestApp) -> Entity {
+        // Nothing propagates the visibility without rendering, so the modal is visible from the start
+        let modal = test.world_mut().spawn((
+            UiLayoutRoot::new_2d(),
+            Dimension(Vec2::new(1280.0, 720.0)),
+            UiLayer(1),
+            UiBlocking,
+            InheritedVisibility::VISIBLE,
+            Name::new("Quit Dialog"),
+        )).id();
+        test.advance(UiTestApp::FRAME);
+        modal
+    }
+
+    #[test]
+    fn modal_spawns_its_backdrop() {
+        let mut test = UiTestApp::new();
+        let modal = open_modal(&mut test);
+
+        let children = test.world().get::<Children>(modal).expect("The modal has no backdrop");
+        assert!(children.iter().any(|child| test.world().get::<UiBackdrop>(*child).is_some()), "The modal has no backdrop");
+    }
+
+    #[test]
+    fn modal_blocks_the_buttons_below() {
+        let mut test = UiTestApp::new();
+        test.watch::<HoverIntended>();
+        test.world_mut().insert_resource(HoverIntent { delay: 0.0 });
+        let button = test.spawn_button("Play");
+        let modal = open_modal(&mut test);
+
+        // The pointer reaches the button as if it went through the backdrop, the button must ignore it
+        test.click(button);
+        test.advance(UiTestApp::FRAME);
+        test.assert_no_event::<MainButtonClicked>();
+        test.assert_no_event::<HoverIntended>();
+        let control = test.world().get::<MainButtonControl>(button).unwrap();
+        assert!(!control.hovered && control.animation_direction <= 0.0, "The button below the modal was hovered");
+        assert_eq!(control.transition, 0.0, "The button below the modal animated");
+
+        // Once the modal is closed the button works again
+        test.world_mut().entity_mut(modal).despawn_recursive();
+        test.unhover();
+        test.click(button);
+        assert_eq!(test.assert_event::<MainButtonClicked>(), button);
+    }
+
+    #[test]
+    fn modal_only_blocks_lower_layers() {
+        let mut test = UiTestApp::new();
+        let below = test.world_mut().spawn(Name::new("Menu")).id();
+        let modal = open_modal(&mut test);
+        let dialog = test.world_mut().spawn(Name::new("Quit")).set_parent(modal).id();
+
+        let mut state = SystemState::<UiLayers>::new(test.world_mut());
+        let layers = state.get(test.world());
+        assert_eq!(layers.blocking(), Some(1));
+        assert_eq!((layers.layer_of(below), layers.layer_of(dialog)), (0, 1));
+        assert!(layers.is_blocked(below), "The menu below the modal is not blocked");
+        assert!(!layers.is_blocked(dialog), "The content of the modal is blocked by itself");
+    }
+}
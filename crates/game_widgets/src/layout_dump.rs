@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use bevy_lunex::*;
+use game_preferences::platform;
+use serde::Serialize;
+
+
+/// What Lunex computed for a node and its descendants, captured for debugging or for comparing
+/// layouts across code changes. Entities are left out so captures of different runs compare equal.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct UiLayoutSnapshot {
+    /// The names from the root to the node joined by `/`, unnamed nodes use their index like `#2`
+    pub path: String,
+    /// The parameters of the [`UiLayout`], if the node has one
+    pub layout: Option<String>,
+    /// The computed rectangle as left, bottom, width and height in world units
+    pub rect: Option<[f32; 4]>,
+    /// The sRGB color of the sprite or of the text
+    pub color: Option<[f32; 4]>,
+    /// The content of the text
+    pub text: Option<String>,
+    pub children: Vec<UiLayoutSnapshot>,
+}
+impl UiLayoutSnapshot {
+    /// Captures the tree under the entity, returns `None` if it does not exist
+    pub fn capture(world: &World, root: Entity) -> Option<Self> {
+        let name = world.get::<Name>(root).map_or("#0".to_string(), |name| name.to_string());
+        UiLayoutSnapshot::capture_node(world, root, name)
+    }
+    /// Captures the node under the path and its children
+    fn capture_node(world: &World, entity: Entity, path: String) -> Option<Self> {
+        let node = world.get_entity(entity).ok()?;
+        let rect = node.get::<GlobalTransform>().zip(node.get::<Dimension>()).map(|(transform, dimension)| {
+            let rect = Rect::from_center_size(transform.translation().truncate(), dimension.0);
+            [rect.min.x, rect.min.y, rect.width(), rect.height()]
+        });
+        let color = node.get::<Sprite>().map(|sprite| sprite.color)
+            .or_else(|| node.get::<TextColor>().map(|color| color.0))
+            .map(|color| color.to_srgba().to_f32_array());
+        let text = node.get::<Text2d>().map(|text| text.0.clone());
+
+        let children = node.get::<Children>().map(|children| {
+            children.iter().enumerate().filter_map(|(index, child)| {
+                let name = world.get::<Name>(*child).map_or(format!("#{index}"), |name| name.to_string());
+                UiLayoutSnapshot::capture_node(world, *child, format!("{path}/{name}"))
+            }).collect()
+        }).unwrap_or_default();
+
+        Some(Self {
+            path,
+            layout: node.get::<UiLayout>().map(|layout| format!("{layout:?}")),
+            rect,
+            color,
+            text,
+            children,
+        })
+    }
+    /// Returns the snapshot as pretty JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// This system writes all root trees to a timestamped JSON file when F10 is pressed.
+    fn dump_system(world: &mut World) {
+        if !world.resource::<ButtonInput<KeyCode>>().just_pressed(KeyCode::F10) { return; }
+
+        let mut roots = world.query_filtered::<Entity, With<UiLayoutRoot>>();
+        let snapshots: Vec<UiLayoutSnapshot> = roots.iter(world).collect::<Vec<_>>().into_iter()
+            .filter_map(|root| UiLayoutSnapshot::capture(world, root))
+            .collect();
+        let json = serde_json::to_string_pretty(&snapshots).unwrap_or_default();
+
+        // Platforms without a file system get it logged instead
+        let path = format!("ui_layout_{}.json", platform::unix_seconds());
+        match platform::export(&path, &json) {
+            Ok(()) => info!("Dumped {} UI trees to '{path}'", snapshots.len()),
+            Err(error) => warn!("Failed to dump the UI layout to '{path}': {error}"),
+        }
+    }
+}
+
+
+/// Plugin with the developer layout dump
+pub struct LayoutDumpPlugin;
+impl Plugin for LayoutDumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Last, UiLayoutSnapshot::dump_system);
+    }
+}
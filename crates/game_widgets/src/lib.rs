@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use game_loading::*;
+
+mod animated_icon;
+mod announce;
+mod audio;
+mod auto_width;
+mod button_group;
+mod camera_transition;
+mod capture;
+mod click_burst;
+mod color_picker;
+mod contrast_checker;
+mod cursor;
+mod cursor_mode;
+mod drag_drop;
+mod entrance;
+mod exit;
+mod focus;
+mod glitch;
+mod glow;
+mod haptics;
+mod layers;
+mod layout_dump;
+mod main_button;
+mod menu;
+mod metrics;
+mod nav_input;
+mod neon_flicker;
+mod parallax;
+mod pool;
+mod recorder;
+mod reveal;
+mod rich_text;
+mod sandboxed;
+mod scroll;
+mod shake;
+mod style;
+mod stylesheet;
+mod text_queue;
+mod theme_editor;
+mod touch;
+mod typography;
+mod ui_action;
+#[cfg(any(test, feature = "test-utils"))]
+mod ui_test;
+mod virtual_cursor;
+mod world_surface;
+
+pub use animated_icon::*;
+pub use announce::*;
+pub use audio::*;
+pub use auto_width::*;
+pub use button_group::*;
+pub use camera_transition::*;
+pub use capture::*;
+pub use click_burst::*;
+pub use color_picker::*;
+pub use contrast_checker::*;
+pub use cursor::*;
+pub use cursor_mode::*;
+pub use drag_drop::*;
+pub use entrance::*;
+pub use exit::*;
+pub use focus::*;
+pub use glitch::*;
+pub use glow::*;
+pub use haptics::*;
+pub use layers::*;
+pub use layout_dump::*;
+pub use main_button::*;
+pub use menu::*;
+pub use metrics::*;
+pub use nav_input::*;
+pub use neon_flicker::*;
+pub use parallax::*;
+pub use pool::*;
+pub use recorder::*;
+pub use reveal::*;
+pub use rich_text::*;
+pub use sandboxed::*;
+pub use scroll::*;
+pub use shake::*;
+pub use style::*;
+pub use stylesheet::*;
+pub use text_queue::*;
+pub use theme_editor::*;
+pub use touch::*;
+pub use typography::*;
+pub use ui_action::*;
+#[cfg(any(test, feature = "test-utils"))]
+pub use ui_test::*;
+pub use virtual_cursor::*;
+pub use world_surface::*;
+
+
+/// Plugin with the systems for our widgets
+pub struct WidgetsPlugin;
+impl Plugin for WidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((UiStylePlugin, CursorSpritesPlugin, CursorModePlugin, DragDropPlugin, FocusPlugin, UiHapticsPlugin, SandboxedUiPlugin, ScrollPlugin, UiNodePoolPlugin, UiTextUpdateQueuePlugin, TouchPlugin, UiLayerPlugin, VirtualCursorPlugin, WindowMetricsPlugin, MenuPlugin));
+        app.add_plugins((UiAudioPlugin, UiAnnouncerPlugin, StyleSheetPlugin, CapturePlugin, UiWorldSurfacePlugin, GlitchPlugin, PulseGlowPlugin, ShakePlugin, EntrancePlugin, ExitPlugin, ParallaxPlugin, ClickBurstPlugin, AnimatedIconPlugin, NeonFlickerPlugin, CameraTransitionPlugin));
+        app.add_plugins((RevealSequencePlugin, ButtonGroupPlugin, RichTextPlugin, AutoWidthPlugin));
+        #[cfg(feature = "leafwing")]
+        app.add_plugins(UiNavActionPlugin);
+        // Developer tools are left out of release builds
+        #[cfg(debug_assertions)]
+        app.add_plugins((ThemeEditorPlugin, ContrastCheckerPlugin, LayoutDumpPlugin));
+        app.add_event::<MainButtonClicked>();
+        app.add_event::<MainButtonAnimationEvent>();
+        app.init_resource::<HoverIntent>();
+        app.init_resource::<HoverArbiter>();
+        app.register_type::<MainButton>();
+        app.register_type::<MainButtonStyle>();
+        app.register_type::<MainButtonVariant>();
+        app.register_type::<MainButtonControl>();
+        app.register_type::<HoverIntent>();
+        app.register_type::<UiAnimationTiming>();
+        app.register_type::<ColorPicker>();
+        app.init_resource::<MainButtonWake>();
+        app.init_resource::<MainButtonAway>();
+        // The timing is chosen once, the schedules can not change later
+        let timing = app.world().get_resource::<UiAnimationTiming>().copied().unwrap_or_default();
+        app.insert_resource(timing);
+        app.register_widget::<MainButton>();
+        app.register_widget::<ColorPicker>();
+        app.add_systems(Update, (
+            MainButton::localize_system,
+            MainButton::restyle_system,
+            MainButton::variant_system,
+        ).chain().before(SandboxedUiSystems).run_if(any_with_component::<MainButtonControl>));
+        match timing {
+            UiAnimationTiming::Variable => {
+                app.add_systems(Update, (
+                    MainButton::style_system.run_if(resource_changed::<UiStyle>.or(any_match_filter::<Changed<ResolvedStyle>>)),
+                    HoverArbiter::system,
+                    MainButtonControl::wake_system,
+                ).chain().after(SandboxedUiSystems).before(SandboxWidgetSystems).run_if(any_with_component::<MainButtonControl>));
+                app.add_systems(Update, (
+                    MainButtonControl::apply_system,
+                    MainButtonControl::window_system,
+                    MainButtonControl::hover_system,
+                ).chain().after(SandboxWidgetSystems).run_if(any_with_component::<MainButtonControl>));
+            },
+            UiAnimationTiming::Fixed => {
+                app.add_systems(FixedUpdate, (
+                    HoverArbiter::system,
+                    MainButtonControl::wake_system,
+                ).chain().before(SandboxWidgetSystems).run_if(any_with_component::<MainButtonControl>));
+                app.add_systems(Update, (
+                    MainButton::style_system.run_if(resource_changed::<UiStyle>.or(any_match_filter::<Changed<ResolvedStyle>>)),
+                    MainButtonControl::interpolate_system,
+                    MainButtonControl::apply_system,
+                    MainButtonControl::window_system,
+                    MainButtonControl::hover_system,
+                ).chain().after(SandboxedUiSystems).run_if(any_with_component::<MainButtonControl>));
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::any::{type_name, TypeId};
+
+    use bevy::{prelude::*, reflect::{ReflectRef, TypeRegistry}};
+    use game_vfx::AnimateColor;
+
+    use crate::*;
+
+    /// Panics if the component is not registered for reflection
+    #[track_caller]
+    fn assert_component<T: Component>(registry: &TypeRegistry) {
+        let registration = registry.get(TypeId::of::<T>()).unwrap_or_else(|| panic!("{} is not registered", type_name::<T>()));
+        assert!(registration.data::<ReflectComponent>().is_some(), "{} is not reflected as a component", type_name::<T>());
+    }
+
+    /// Panics if the resource is not registered for reflection
+    #[track_caller]
+    fn assert_resource<T: Resource>(registry: &TypeRegistry) {
+        let registration = registry.get(TypeId::of::<T>()).unwrap_or_else(|| panic!("{} is not registered", type_name::<T>()));
+        assert!(registration.data::<ReflectResource>().is_some(), "{} is not reflected as a resource", type_name::<T>());
+    }
+
+    #[test]
+    fn widget_types_are_registered() {
+        let test = UiTestApp::new();
+        let registry = test.world().resource::<AppTypeRegistry>().read();
+
+        assert_component::<MainButton>(&registry);
+        assert_component::<MainButtonStyle>(&registry);
+        assert_component::<MainButtonVariant>(&registry);
+        assert_component::<MainButtonControl>(&registry);
+        assert_component::<ColorPicker>(&registry);
+        assert_component::<AnimateColor>(&registry);
+        assert_component::<Focusable>(&registry);
+        assert_component::<UiLayer>(&registry);
+        assert_component::<UiBlocking>(&registry);
+        assert_component::<UiReveal>(&registry);
+        assert_component::<Draggable>(&registry);
+        assert_component::<Scrollable>(&registry);
+        assert_resource::<HoverIntent>(&registry);
+        assert_resource::<UiAnimationTiming>(&registry);
+        assert_resource::<UiFocus>(&registry);
+        assert_resource::<TouchMode>(&registry);
+        assert_resource::<VirtualCursor>(&registry);
+    }
+
+    #[test]
+    fn registered_types_are_found_by_name() {
+        let test = UiTestApp::new();
+        let registry = test.world().resource::<AppTypeRegistry>().read();
+
+        for (name, id) in [("MainButton", TypeId::of::<MainButton>()), ("MainButtonControl", TypeId::of::<MainButtonControl>()), ("HoverIntent", TypeId::of::<HoverIntent>())] {
+            let registration = registry.get_with_short_type_path(name).unwrap_or_else(|| panic!("{name} is not found by name"));
+            assert_eq!(registration.type_id(), id, "{name} found the wrong type");
+        }
+    }
+
+    #[test]
+    fn built_button_is_read_through_reflection() {
+        let mut test = UiTestApp::new();
+        let button = test.spawn_button("Play");
+        let registry = test.world().resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let reflect = registry.get_with_short_type_path("MainButtonControl").and_then(|registration| registration.data::<ReflectComponent>()).expect("MainButtonControl is not reflected");
+        let control = reflect.reflect(test.world().entity(button)).expect("The built button has no MainButtonControl");
+        let ReflectRef::Struct(control) = control.reflect_ref() else { panic!("MainButtonControl is not reflected as a struct") };
+        assert_eq!(control.field("transition").and_then(|field| field.try_downcast_ref::<f32>()), Some(&0.0));
+        // The entity fields stay readable, like for an inspector
+        assert_eq!(control.field("root").and_then(|field| field.try_downcast_ref::<Entity>()), Some(&button));
+    }
+}
@@ -0,0 +1,1245 @@
+use bevy::{a11y::{accesskit::{Action, Node, Role}, AccessibilityNode}, asset::RenderAssetUsages, ecs::system::{EntityCommands, SystemParam, SystemParamItem}, picking::{backend::HitData, focus::HoverMap, pointer::{Location, PointerId, PointerLocation}}, prelude::*, render::render_resource::{Extent3d, TextureDimension, TextureFormat}, sprite::Anchor, utils::HashMap, window::{CursorEntered, CursorLeft, SystemCursorIcon, WindowFocused}};
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Main menu button with a sliding highlight, decrypting label and an optional hint on the right side.
+/// Spawn it on an entity with [`UiLayout`] and the internals will be built for you.
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct MainButton {
+    /// The label of the button
+    pub text: String,
+    /// Small text displayed next to the arrow
+    pub hint: String,
+    /// Smaller second line under the label, none if empty
+    pub subtitle: String,
+    /// Where the label and the second line are placed
+    pub align: MainButtonAlign,
+    /// If the button ignores any interaction
+    pub disabled: bool,
+    /// If only the image and the lines are built, without the hint and the arrow, like for grids of thousands of buttons
+    pub compact: bool,
+}
+impl MainButton {
+    /// Creates new instance
+    pub fn new(text: impl std::fmt::Display) -> Self {
+        Self {
+            text: text.to_string(),
+            hint: String::new(),
+            subtitle: String::new(),
+            align: MainButtonAlign::Left,
+            disabled: false,
+            compact: false,
+        }
+    }
+    /// Replace the default empty hint with a new one.
+    pub fn hint(mut self, hint: impl std::fmt::Display) -> Self {
+        self.hint = hint.to_string();
+        self
+    }
+    /// Replace the default empty second line with a new one.
+    pub fn subtitle(mut self, subtitle: impl std::fmt::Display) -> Self {
+        self.subtitle = subtitle.to_string();
+        self
+    }
+    /// Replace the default left alignment with a new one.
+    pub fn align(mut self, align: MainButtonAlign) -> Self {
+        self.align = align;
+        self
+    }
+    /// Replace the default enabled state with a new one.
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
+    /// Replace the default full internals with a new one, compact buttons are built without the hint and the arrow.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+    /// Color of the image for the current state
+    fn image_color(&self, variant: MainButtonVariant) -> ThemedColor {
+        match variant {
+            MainButtonVariant::Primary if self.disabled => ThemedColor::alpha(ThemeRole::Primary, 0.05),
+            MainButtonVariant::Primary => ThemedColor::new(vec![
+                (UiBase::id(), ThemeRole::Primary, 0.15),
+                (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
+            ]),
+            // Only the border shows until the hover fills it in
+            MainButtonVariant::Ghost if self.disabled => ThemedColor::alpha(ThemeRole::PrimaryHover, 0.0),
+            MainButtonVariant::Ghost => ThemedColor::new(vec![
+                (UiBase::id(), ThemeRole::PrimaryHover, 0.0),
+                (UiHover::id(), ThemeRole::PrimaryHover, 0.4)
+            ]),
+            MainButtonVariant::Danger if self.disabled => ThemedColor::alpha(ThemeRole::Danger, 0.05),
+            MainButtonVariant::Danger => ThemedColor::new(vec![
+                (UiBase::id(), ThemeRole::Danger, 0.3),
+                (UiHover::id(), ThemeRole::Danger, 1.2)
+            ]),
+        }
+    }
+    /// Color of the ghost border and the danger stripe for the current state
+    fn edge_color(&self, variant: MainButtonVariant) -> ThemedColor {
+        let (idle, hover) = match variant {
+            MainButtonVariant::Danger => (ThemeRole::Warning, ThemeRole::Warning),
+            _ => (ThemeRole::Primary, ThemeRole::PrimaryHover),
+        };
+        if self.disabled { return ThemedColor::alpha(idle, 0.05); }
+        ThemedColor::new(vec![
+            (UiBase::id(), idle, 0.6),
+            (UiHover::id(), hover, 1.2)
+        ])
+    }
+    /// Color of the label for the current state
+    fn text_color(&self) -> ThemedColor {
+        if self.disabled { return ThemedColor::alpha(ThemeRole::TextDim, 0.5); }
+        ThemedColor::new(vec![
+            (UiBase::id(), ThemeRole::Text, 1.0),
+            (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
+        ])
+    }
+    /// Color of the second line for the current state
+    fn subtitle_color(&self) -> ThemedColor {
+        if self.disabled { return ThemedColor::alpha(ThemeRole::TextDim, 0.3); }
+        ThemedColor::new(vec![
+            (UiBase::id(), ThemeRole::TextDim, 1.0),
+            (UiHover::id(), ThemeRole::PrimaryHover, 0.9)
+        ])
+    }
+    /// Color of the hint and the arrow for the current state
+    fn hint_color(&self) -> ThemedColor {
+        if self.disabled { return ThemedColor::alpha(ThemeRole::Accent, 0.05); }
+        ThemedColor::new(vec![
+            (UiBase::id(), ThemeRole::Accent, 0.2),
+            (UiHover::id(), ThemeRole::PrimaryHover, 1.2)
+        ])
+    }
+    /// Layout of the image sliding on hover, the ghost border stays in place
+    fn image_layout(motion: &ButtonMotion, variant: MainButtonVariant) -> UiLayout {
+        let slide = if variant == MainButtonVariant::Ghost { 0.0 } else { motion.slide };
+        UiLayout::new(vec![
+            (UiBase::id(), UiLayout::window().full()),
+            (UiHover::id(), UiLayout::window().x(Rl(slide)).full())
+        ])
+    }
+    /// Layout and size of the label, or of the second line, for the alignment. The label moves up to make room for the second line.
+    fn line_layout(&self, second: bool) -> (UiLayout, UiTextSize) {
+        let (x, anchor): (UiValue<f32>, Anchor) = match self.align {
+            MainButtonAlign::Left => (Rh(40.0).into(), Anchor::CenterLeft),
+            MainButtonAlign::Center => (Rl(50.0).into(), Anchor::Center),
+            MainButtonAlign::Right => (Rl(80.0).into(), Anchor::CenterRight),
+        };
+        let (y, size) = match (self.subtitle.is_empty(), second) {
+            (true, _) => (50.0, 60.0),
+            (false, false) => (38.0, 46.0),
+            (false, true) => (74.0, 28.0),
+        };
+        // For text always use window layout to position it
+        (UiLayout::window().x(x).y(Rl(y)).anchor(anchor).pack(), UiTextSize::from(Rh(size)))
+    }
+    /// What screen readers are told about the button, invoking it activates the button like a click
+    fn accessibility_node(&self) -> AccessibilityNode {
+        let mut node = Node::new(Role::Button);
+        node.set_label(self.text.as_str());
+        let description: Vec<&str> = [self.subtitle.as_str(), self.hint.as_str()].into_iter().filter(|line| !line.is_empty()).collect();
+        if !description.is_empty() { node.set_description(description.join(", ")); }
+        if self.disabled { node.set_disabled(); }
+        node.add_action(Action::Focus);
+        node.add_action(Action::Click);
+        AccessibilityNode(node)
+    }
+    /// This observer sends [`MainButtonClicked`] if the button is enabled and not covered by a blocking layer.
+    fn click<E: Event>(trigger: Trigger<E>, query: Query<&MainButton>, layers: UiLayers, mut commands: Commands) {
+        if layers.is_blocked(trigger.entity()) { return; }
+        let Ok(button) = query.get(trigger.entity()) else { return; };
+        if button.disabled {
+            commands.send_event(PlayUiSound(UiSoundKind::Error));
+            commands.entity(trigger.entity()).insert(Shake::default());
+        } else {
+            commands.trigger_targets(MainButtonClicked, trigger.entity());
+            commands.send_event(PlayUiSound(UiSoundKind::Click));
+        }
+    }
+    /// This system shows the entry of the [`LocalizedText`] on the button as its label.
+    pub(crate) fn localize_system(mut query: Query<(&mut MainButton, &LocalizedText), Changed<LocalizedText>>) {
+        for (mut button, localized) in &mut query {
+            let Some(text) = localized.text() else { continue; };
+            if button.text != text { button.text = text.to_string(); }
+        }
+    }
+    /// This system applies an edited [`UiStyle`] or a newly resolved [`ResolvedStyle`] to already built buttons.
+    pub(crate) fn style_system(
+        mut commands: Commands,
+        style: Res<UiStyle>,
+        reduced: Res<ReducedMotion>,
+        assets: Option<Res<AssetCache>>,
+        query: Query<(&MainButtonControl, Option<Ref<ResolvedStyle>>)>,
+        mut fonts: Query<(&mut TextFont, Option<&mut LocalizedText>)>,
+    ) {
+        let Some(assets) = assets else { return; };
+        for (control, resolved) in &query {
+            let restyled = resolved.as_ref().is_some_and(|resolved| resolved.is_changed());
+            if !style.is_changed() && !restyled && !reduced.is_changed() { continue; }
+            let (motion, role) = resolved.map_or((style.button, TypographyRole::Body), |resolved| (resolved.motion, resolved.font));
+            let motion = motion.scaled(motion_scale(&reduced));
+            commands.entity(control.image).insert((
+                MainButton::image_layout(&motion, control.variant),
+                UiHover::new().forward_speed(motion.forward_speed()).backward_speed(motion.backward_speed()),
+            ));
+            let label = Typography::role(role, &assets, &style);
+            let subtitle = control.subtitle.map(|subtitle| (subtitle, style.typography.hint));
+            let small = control.hint.map(|hint| (hint, style.typography.hint)).into_iter().chain(control.arrow.map(|arrow| (arrow, style.typography.heading)));
+            for (entity, size) in std::iter::once((control.text, label.font_size)).chain(small).chain(subtitle) {
+                if let Ok((mut font, _)) = fonts.get_mut(entity) {
+                    if font.font_size != size { font.font_size = size; }
+                }
+            }
+            // The label follows the font of its locale, the role only replaces its fallback
+            if let Ok((_, Some(mut localized))) = fonts.get_mut(control.text) {
+                if restyled { localized.set_default_font(label.font); }
+            }
+        }
+        commands.trigger(RecomputeUiLayout);
+    }
+    /// This system restyles already built buttons when their properties change.
+    pub(crate) fn restyle_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &MainButton, &mut MainButtonControl), Changed<MainButton>>,
+        mut animators: Query<&mut TextAnimator>,
+        mut texts: ResMut<UiTextUpdateQueue>,
+        focus: Res<UiFocus>,
+        mut announce: EventWriter<Announce>,
+    ) {
+        for (entity, button, mut control) in &mut query {
+            // A second line appearing or going away moves the label, so the internals are built again, the same for the compact ones
+            if control.subtitle.is_some() == button.subtitle.is_empty() || control.hint.is_some() == button.compact {
+                if let Some(image) = commands.get_entity(control.image) { image.despawn_recursive(); }
+                commands.entity(entity).remove::<MainButtonControl>();
+                continue;
+            }
+            // The lines are placed again in the new alignment, the rest of the tree stays
+            if control.align != button.align {
+                commands.entity(control.text).insert(button.line_layout(false));
+                if let Some(subtitle) = control.subtitle { commands.entity(subtitle).insert(button.line_layout(true)); }
+                control.align = button.align;
+            }
+
+            // The colors follow the button on the next update of MainButtonControl
+            commands.entity(entity).insert((Focusable::new().disabled(button.disabled), button.accessibility_node()));
+
+            if let Ok(mut animator) = animators.get_mut(control.text) {
+                let label = button.text.to_ascii_uppercase();
+                if animator.text() != label {
+                    // A label changing under the focus is the new state of the setting the button cycles
+                    if focus.current() == Some(entity) { announce.send(Announce::new(&button.text).key(entity)); }
+                    *animator = TextAnimator::new(label).function(decryption_animation).duration(1.0);
+                }
+            }
+            // Only the button the user interacts with updates its hint right away
+            let hint = button.hint.to_ascii_uppercase();
+            let urgent = control.hovered || focus.current() == Some(entity);
+            if let Some(entity) = control.hint {
+                if urgent { texts.urgent(entity, hint); } else { texts.queue(entity, hint); }
+            }
+            if let Some(subtitle) = control.subtitle {
+                if urgent { texts.urgent(subtitle, button.subtitle.clone()); } else { texts.queue(subtitle, button.subtitle.clone()); }
+            }
+        }
+    }
+    /// This system tears down the internals of buttons whose variant changed, so they are built again with the new one.
+    /// The button entity keeps its observers, so nothing listening to its clicks notices.
+    pub(crate) fn variant_system(
+        mut commands: Commands,
+        query: Query<(&MainButtonControl, Option<&MainButtonVariant>)>,
+        changed: Query<Entity, Changed<MainButtonVariant>>,
+        mut removed: RemovedComponents<MainButtonVariant>,
+    ) {
+        for entity in changed.iter().chain(removed.read()) {
+            let Ok((control, variant)) = query.get(entity) else { continue; };
+            if control.variant == variant.copied().unwrap_or_default() { continue; }
+            if let Some(image) = commands.get_entity(control.image) { image.despawn_recursive(); }
+            commands.entity(entity).remove::<MainButtonControl>();
+        }
+    }
+}
+
+impl SandboxedUi for MainButton {
+    type Control = MainButtonControl;
+    fn internals(control: &MainButtonControl) -> Vec<Entity> {
+        vec![control.image]
+    }
+    fn teardown(entity: &mut EntityCommands) {
+        entity.remove::<PickingBehavior>();
+    }
+}
+
+impl SandboxWidget for MainButton {
+    type BuildParam = MainButtonBuildParam<'static, 'static>;
+    type UpdateParam = MainButtonUpdateParam<'static, 'static>;
+    /// Spawns the image with the gradient, the edges and the ghost, the lines, the hint and the arrow unless compact
+    fn build(&self, ui: &mut WidgetBuilder, param: &mut SystemParamItem<Self::BuildParam>, assets: &AssetCache) -> MainButtonControl {
+        let entity = ui.entity();
+        let (button_style, variant, resolved) = param.options.get(entity).unwrap_or_default();
+        let variant = variant.copied().unwrap_or_default();
+        let (motion, font) = resolved.map_or((param.style.button, TypographyRole::Body), |resolved| (resolved.motion, resolved.font));
+        let motion = motion.scaled(motion_scale(&param.reduced));
+
+        // Use the sprite sheet only if both the style and the cache provide it
+        let atlas = button_style.and_then(|style| style.atlas_frames).zip(assets.button_atlas.clone());
+
+        // The gradient is layered from a faded copy of the button image, shared by all buttons, it replaces the primary fill only
+        let gradient = button_style.and_then(|style| style.gradient_stops).filter(|_| variant == MainButtonVariant::Primary).and_then(|stops| {
+            let mask = match param.masks.get(&assets.button.id()) {
+                Some(mask) => mask.clone(),
+                None => {
+                    let source = gradient_mask(param.images.get(&assets.button)?)?;
+                    let mask = param.images.add(source);
+                    param.masks.insert(assets.button.id(), mask.clone());
+                    mask
+                },
+            };
+            Some((mask, stops))
+        });
+        let theme = param.resolver.theme_for(entity);
+        let style: &UiStyle = &param.style;
+
+        let mut ghost = None;
+        let mut overlay = None;
+        let mut edges = Vec::new();
+        let mut text = Entity::PLACEHOLDER;
+        let mut subtitle = None;
+        let mut hint = None;
+        let mut arrow = None;
+
+        // Disabled buttons stay pickable, so clicking them is rejected with a shake instead of doing nothing
+        ui.insert((
+            Focusable::new().disabled(self.disabled),
+            self.accessibility_node(),
+        ));
+
+        // Spawn the image
+        let image = ui.spawn(None, (
+            // You can define layouts for multiple states
+            MainButton::image_layout(&motion, variant),
+            // Like this you can enable a state
+            UiHover::new().forward_speed(motion.forward_speed()).backward_speed(motion.backward_speed()),
+            // The colors are animated by MainButtonControl, so start with the base one
+            Sprite {
+                image: assets.button.clone(),
+                color: self.image_color(variant).sample(&theme, 0.0, ColorSpace::Srgb),
+                texture_atlas: atlas.clone().map(|(frames, layout)| TextureAtlas { layout, index: frames[0] }),
+                // Here we enable sprite slicing
+                image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
+                ..default()
+            },
+            // Make sure it does not cover the bounding zone of parent
+            PickingBehavior::IGNORE,
+            // Stay hidden until the first layout pass placed the internals
+            UiReveal,
+            Visibility::Hidden,
+        )).with_children(|ui| {
+
+            // Spawn the top stop of the gradient, fading into the image below
+            if let Some((mask, (idle, _))) = &gradient {
+                overlay = Some(ui.spawn((
+                    UiLayout::window().full().pack(),
+                    UiDepth::Add(0.05),
+                    Sprite {
+                        image: mask.clone(),
+                        color: idle[0],
+                        texture_atlas: atlas.clone().map(|(frames, layout)| TextureAtlas { layout, index: frames[0] }),
+                        image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
+                        ..default()
+                    },
+                    PickingBehavior::IGNORE,
+                )).id());
+            }
+
+            // Spawn the border of the ghost or the stripe of the danger variant
+            for layout in variant.edge_layouts() {
+                edges.push(ui.spawn((
+                    layout,
+                    UiDepth::Add(0.05),
+                    Sprite { color: self.edge_color(variant).sample(&theme, 0.0, ColorSpace::Srgb), ..default() },
+                    PickingBehavior::IGNORE,
+                )).id());
+            }
+
+            // Spawn the sprite showing the previous frame while crossfading
+            if let Some((frames, layout)) = &atlas {
+                ghost = Some(ui.spawn((
+                    UiLayout::window().full().pack(),
+                    UiDepth::Add(0.1),
+                    Sprite {
+                        image: assets.button.clone(),
+                        texture_atlas: Some(TextureAtlas { layout: layout.clone(), index: frames[0] }),
+                        image_mode: SpriteImageMode::Sliced(TextureSlicer { border: BorderRect::square(32.0), ..default() }),
+                        ..default()
+                    },
+                    Visibility::Hidden,
+                    PickingBehavior::IGNORE,
+                )).id());
+            }
+
+            // Spawn the text
+            let label = Typography::role(font, assets, style);
+            let mut label_entity = ui.spawn((
+                // You can control the size of the text with the layout
+                self.line_layout(false),
+                TextColor(self.text_color().sample(&theme, 0.0, ColorSpace::Srgb)),
+                // You can attach text like this
+                Text2d::default(),
+                TextAnimator::new(self.text.to_ascii_uppercase()).function(decryption_animation).duration(1.0),
+                label.text_font(),
+                LocalizedText::default(),
+                // Make sure it does not cover the bounding zone of parent
+                PickingBehavior::IGNORE,
+            ));
+            // The preset shadow keeps the label readable on the background
+            if let Some(shadow) = label.shadow { label_entity.insert(shadow); }
+            text = label_entity.id();
+
+            // Spawn the second line
+            if !self.subtitle.is_empty() {
+                subtitle = Some(ui.spawn((
+                    self.line_layout(true),
+                    TextColor(self.subtitle_color().sample(&theme, 0.0, ColorSpace::Srgb)),
+                    Text2d::new(&self.subtitle),
+                    Typography::hint(assets, style).text_font(),
+                    LocalizedText::default(),
+                    PickingBehavior::IGNORE,
+                )).id());
+            }
+
+            // Compact buttons end with the lines
+            if self.compact { return; }
+
+            // Spawn the hint
+            hint = Some(ui.spawn((
+                UiLayout::window().pos(Rl((82.0, 50.0))).anchor(Anchor::CenterRight).pack(),
+                TextColor(self.hint_color().sample(&theme, 0.0, ColorSpace::Srgb)),
+                UiTextSize::from(Rh(40.0)),
+                Text2d::new(self.hint.to_ascii_uppercase()),
+                Typography::hint(assets, style).text_font(),
+                LocalizedText::default(),
+                PickingBehavior::IGNORE,
+            )).id());
+
+            // Spawn the fluff
+            arrow = Some(ui.spawn((
+                // For text always use window layout to position it
+                UiLayout::window().pos(Rl((90.0, 50.0))).anchor(Anchor::CenterRight).pack(),
+                TextColor(self.hint_color().sample(&theme, 0.0, ColorSpace::Srgb)),
+                // You can control the size of the text
+                UiTextSize::from(Rh(60.0)),
+                // You can attach text like this
+                Text2d::new("<-"),
+                Typography::heading(assets, style).text_font(),
+                PickingBehavior::IGNORE,
+            )).id());
+        }).id();
+
+        MainButtonControl {
+            root: entity,
+            variant, align: self.align, image, edges, text, subtitle, hint, arrow, ghost,
+            gradient: overlay.zip(gradient.map(|(_, stops)| stops)),
+            color_targets: Vec::new(),
+            atlas_frames: atlas.map(|(frames, _)| frames),
+            transition: 0.0,
+            tween: Tween::rest(0.0),
+            animation_direction: 0.0,
+            pressed: false,
+            intent: None,
+            settled: false,
+            hovered: false,
+            hover_source: None,
+            awake: false,
+            intended: false,
+            pending: Vec::new(),
+            ticks: [0.0; 2],
+            completed: true,
+            phases: Vec::new(),
+        }
+    }
+    /// Attaches the Lunex hover state following the confirmed hover, the press tracking and the clicks
+    fn observers(widget: &mut EntityCommands) {
+        widget.observe(hover_set::<HoverIntended, true>).observe(hover_set::<Pointer<Out>, false>)
+
+            // Track the interaction state for our own animations
+            .observe(|trigger: Trigger<HoverIntended>, mut query: Query<&mut MainButtonControl>, mut sound: EventWriter<PlayUiSound>| {
+                let Ok(mut control) = query.get_mut(trigger.entity()) else { return; };
+                control.animation_direction = 1.0;
+                sound.send(PlayUiSound(control.variant.hover_sound()));
+            })
+            .observe(|trigger: Trigger<Pointer<Down>>, mut query: Query<&mut MainButtonControl>, layers: UiLayers| {
+                if layers.is_blocked(trigger.entity()) { return; }
+                if let Ok(mut control) = query.get_mut(trigger.entity()) { control.pressed = true; control.settled = false; }
+            })
+            .observe(|trigger: Trigger<Pointer<Up>>, mut query: Query<&mut MainButtonControl>| {
+                if let Ok(mut control) = query.get_mut(trigger.entity()) { control.pressed = false; control.settled = false; }
+            })
+
+            // Forward the click or the gamepad activation only if the button is enabled
+            .observe(MainButton::click::<Pointer<Click>>)
+            .observe(MainButton::click::<FocusActivated>);
+    }
+    /// Records the pointer so the [`HoverArbiter`] arms the hover intent, sweeping over the menu does not flash every button.
+    /// Only the arbiter confirms the intent, so of overlapping buttons only the topmost starts animating in.
+    /// A disabled button is still entered for its clicks, but never animates in.
+    fn on_hover(&self, control: &mut MainButtonControl, hover: &WidgetHover) {
+        if hover.hovered {
+            if self.disabled { return; }
+            control.hovered = true;
+            control.hover_source = Some((hover.pointer, hover.location.clone(), hover.hit.clone()));
+        } else {
+            control.animation_direction = -1.0;
+            control.pressed = false;
+            control.intent = None;
+            control.settled = false;
+            control.hovered = false;
+            control.hover_source = None;
+        }
+    }
+    /// Advances the hover transition and picks the new colors, the results are applied by [`MainButtonControl::apply_system`].
+    /// Settled buttons are skipped until they are hovered, focused, pressed, edited or re-themed.
+    fn update(&self, widget: &WidgetUpdate, control: &mut MainButtonControl, param: &SystemParamItem<Self::UpdateParam>) {
+        let resolved = param.resolved.get(widget.entity).ok();
+        let restyled = resolved.as_ref().is_some_and(|resolved| resolved.is_changed());
+        // Under reduced motion the hover is an instant state change
+        let motion = resolved.map_or(param.style.button, |resolved| resolved.motion).scaled(motion_scale(&param.reduced));
+
+        // Same speeds as the Lunex hover state of the internals, a focused or current button looks hovered
+        let hovered = control.animation_direction > 0.0 || param.focus.current() == Some(widget.entity) || param.current.contains(widget.entity);
+        let (target, speed) = if hovered { (1.0, motion.forward_speed()) } else { (0.0, motion.backward_speed()) };
+        if control.settled && control.tween.to == target && !control.intended && !param.wake.0 && !restyled && !widget.changed {
+            if control.awake { control.awake = false; }
+            // The fixed timing blended the last tick with the one before, the settled button rests on the last
+            if control.ticks[0] != control.ticks[1] { control.ticks[0] = control.ticks[1]; }
+            if control.transition != control.ticks[1] { control.transition = control.ticks[1]; }
+            return;
+        }
+        control.awake = true;
+        // Turning around before the end starts the other phase, the one left never completes
+        if control.tween.to != target {
+            control.tween.towards(target, speed);
+            control.completed = false;
+            control.phases.push(if hovered { MainButtonAnimationPhase::HoverStarted } else { MainButtonAnimationPhase::UnhoverStarted });
+        }
+        let value = control.tween.tick(widget.delta);
+        control.ticks = [control.ticks[1], value];
+        control.transition = value;
+
+        // Animate towards the colors of the current state whenever they change, which happens
+        // on hover, on unhover and while the theme is being switched
+        let targets = control.color_targets(self, &param.resolver.theme_for(widget.entity), hovered);
+        if targets != control.color_targets {
+            let duration = if hovered { motion.hover_in } else { motion.hover_out };
+            for (target, color) in &targets {
+                if control.color_targets.contains(&(*target, *color)) { continue; }
+                control.pending.push((*target, AnimateColor::new(*color, duration).space(param.interpolation.0)));
+            }
+            control.color_targets = targets;
+        }
+
+        // Without a sprite sheet the button is settled once the transition ends, the ghost needs one more frame
+        let done = control.tween.is_done();
+        if control.settled != done { control.settled = done; }
+        if done && !control.completed {
+            control.completed = true;
+            control.phases.push(if target > 0.0 { MainButtonAnimationPhase::HoverCompleted } else { MainButtonAnimationPhase::UnhoverCompleted });
+        }
+    }
+}
+
+/// What the build of a [`MainButton`] reads besides the button
+#[derive(SystemParam)]
+pub struct MainButtonBuildParam<'w, 's> {
+    resolver: ThemeResolver<'w, 's>,
+    style: Res<'w, UiStyle>,
+    reduced: Res<'w, ReducedMotion>,
+    images: ResMut<'w, Assets<Image>>,
+    /// The gradient masks by the button image they fade
+    masks: Local<'s, HashMap<AssetId<Image>, Handle<Image>>>,
+    options: Query<'w, 's, (Option<&'static MainButtonStyle>, Option<&'static MainButtonVariant>, Option<&'static ResolvedStyle>)>,
+}
+
+/// What the update of a [`MainButton`] reads besides the button and its control
+#[derive(SystemParam)]
+pub struct MainButtonUpdateParam<'w, 's> {
+    resolver: ThemeResolver<'w, 's>,
+    style: Res<'w, UiStyle>,
+    reduced: Res<'w, ReducedMotion>,
+    interpolation: Res<'w, ColorInterpolation>,
+    focus: Res<'w, UiFocus>,
+    wake: Res<'w, MainButtonWake>,
+    resolved: Query<'w, 's, Ref<'static, ResolvedStyle>>,
+    current: Query<'w, 's, (), With<ButtonCurrent>>,
+}
+
+/// If every button is updated this frame, even the settled ones
+#[derive(Resource, Default)]
+pub(crate) struct MainButtonWake(bool);
+
+/// If the cursor left the window or the window lost focus, picking keeps the last hits until the cursor is back
+#[derive(Resource, Default)]
+pub(crate) struct MainButtonAway(bool);
+
+/// Optional style of a [`MainButton`], insert it together with the button
+#[derive(Component, Reflect, Clone, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct MainButtonStyle {
+    /// Sprite sheet frames for idle, hover and pressed states. Requires `button` atlas in the asset manifest.
+    pub atlas_frames: Option<[usize; 3]>,
+    /// Top and bottom colors of the image for idle and hover states
+    pub gradient_stops: Option<([Color; 2], [Color; 2])>,
+}
+impl MainButtonStyle {
+    /// Creates new instance
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Switch the image between these sprite sheet frames instead of only tinting it.
+    pub fn atlas(mut self, idle: usize, hover: usize, pressed: usize) -> Self {
+        self.atlas_frames = Some([idle, hover, pressed]);
+        self
+    }
+    /// Fill the image with a vertical gradient from top to bottom color instead of a flat tint.
+    pub fn gradient(mut self, idle: [Color; 2], hover: [Color; 2]) -> Self {
+        self.gradient_stops = Some((idle, hover));
+        self
+    }
+}
+
+/// Where the label of a [`MainButton`] and its second line are placed, changing it moves them without a rebuild
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum MainButtonAlign {
+    /// After the inset on the left side
+    #[default]
+    Left,
+    Center,
+    /// Before the hint on the right side
+    Right,
+}
+
+/// Optional look of a [`MainButton`], insert it together with the button or later to rebuild its internals
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[reflect(Component)]
+pub enum MainButtonVariant {
+    /// The filled sliced image sliding on hover
+    #[default]
+    Primary,
+    /// Transparent with only a thin border, filling in on hover
+    Ghost,
+    /// The danger fill with a warning stripe on the edge and its own hover sound
+    Danger,
+}
+impl MainButtonVariant {
+    /// Layouts of the border lines or the stripe drawn on the image
+    fn edge_layouts(&self) -> Vec<UiLayout> {
+        match self {
+            MainButtonVariant::Primary => Vec::new(),
+            MainButtonVariant::Ghost => vec![
+                UiLayout::window().size((Rl(100.0), Rh(4.0))).pack(),
+                UiLayout::window().y(Rl(100.0)).anchor(Anchor::BottomLeft).size((Rl(100.0), Rh(4.0))).pack(),
+                UiLayout::window().size((Rh(4.0), Rl(100.0))).pack(),
+                UiLayout::window().x(Rl(100.0)).anchor(Anchor::TopRight).size((Rh(4.0), Rl(100.0))).pack(),
+            ],
+            MainButtonVariant::Danger => vec![UiLayout::window().size((Rh(12.0), Rl(100.0))).pack()],
+        }
+    }
+    /// The sound played once the hover is confirmed
+    fn hover_sound(&self) -> UiSoundKind {
+        if *self == MainButtonVariant::Danger { UiSoundKind::DangerHover } else { UiSoundKind::Hover }
+    }
+}
+
+/// Creates a copy of the image fading out from top to bottom, returns `None` for formats other than RGBA8
+fn gradient_mask(image: &Image) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if !matches!(format, TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm) { return None; }
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let mut data = image.data.clone();
+    for y in 0..height {
+        let fade = 1.0 - y as f32 / height.saturating_sub(1).max(1) as f32;
+        for x in 0..width {
+            let alpha = &mut data[(y * width + x) * 4 + 3];
+            *alpha = (*alpha as f32 * fade) as u8;
+        }
+    }
+
+    let mut mask = Image::new(
+        Extent3d { width: width as u32, height: height as u32, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    mask.sampler = image.sampler.clone();
+    Some(mask)
+}
+
+/// Holds the internal entities and the interaction state of a built [`MainButton`].
+/// The entities are private, so the inspector shows them without letting them be replaced.
+#[derive(Component, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Component)]
+pub struct MainButtonControl {
+    /// The button entity, the source of its [`MainButtonAnimationEvent`]
+    root: Entity,
+    variant: MainButtonVariant,
+    /// The alignment the lines are placed in
+    align: MainButtonAlign,
+    image: Entity,
+    /// The ghost border lines or the danger stripe
+    edges: Vec<Entity>,
+    text: Entity,
+    /// The second line under the label
+    subtitle: Option<Entity>,
+    /// The hint and the arrow, compact buttons have neither
+    hint: Option<Entity>,
+    arrow: Option<Entity>,
+    ghost: Option<Entity>,
+    gradient: Option<(Entity, ([Color; 2], [Color; 2]))>,
+    color_targets: Vec<(Entity, Color)>,
+    atlas_frames: Option<[usize; 3]>,
+    /// The hover transition in range 0.0 to 1.0
+    pub transition: f32,
+    tween: Tween<f32>,
+    /// 1.0 when moving towards hover, -1.0 when moving back
+    pub animation_direction: f32,
+    /// If the pointer is pressed down on the button
+    pub pressed: bool,
+    /// Seconds left until the hover is confirmed, while the pointer rests on the button
+    intent: Option<f32>,
+    /// If nothing is left to animate, so the update can skip the button
+    settled: bool,
+    /// If a pointer is over the button now, set on enter and cleared on leave
+    pub hovered: bool,
+    /// The pointer that entered, where and what it hit
+    #[reflect(ignore)]
+    hover_source: Option<(PointerId, Location, HitData)>,
+    /// If the last update did not skip the button
+    awake: bool,
+    /// The hover intent elapsed and waits to be confirmed
+    intended: bool,
+    /// Color animations computed by the update and started by the apply
+    pending: Vec<(Entity, AnimateColor)>,
+    /// The transition at the previous and the last update, blended between in the fixed timing
+    ticks: [f32; 2],
+    /// If the transition reached the target it last started towards
+    completed: bool,
+    /// Phases reached by the update and sent by the apply
+    phases: Vec<MainButtonAnimationPhase>,
+}
+impl MainButtonControl {
+    /// The entity of the button itself
+    pub fn root(&self) -> Entity {
+        self.root
+    }
+    /// The entity of the button image
+    pub fn image(&self) -> Entity {
+        self.image
+    }
+    /// The entity of the button label
+    pub fn text(&self) -> Entity {
+        self.text
+    }
+    /// The entity of the second line under the label, if the button has one
+    pub fn subtitle(&self) -> Option<Entity> {
+        self.subtitle
+    }
+    /// The entity of the button hint, if the button is not compact
+    pub fn hint(&self) -> Option<Entity> {
+        self.hint
+    }
+    /// The variant the internals were built for
+    pub fn variant(&self) -> MainButtonVariant {
+        self.variant
+    }
+    /// The colors the internals should have in the idle or hover state
+    fn color_targets(&self, button: &MainButton, theme: &UiTheme, hovered: bool) -> Vec<(Entity, Color)> {
+        let t = if hovered { 1.0 } else { 0.0 };
+        let mut targets = Vec::with_capacity(6 + self.edges.len());
+        match self.gradient {
+            // The image holds the bottom stop and the overlay the top one
+            Some((overlay, (idle, hover))) if !button.disabled => {
+                targets.push((self.image, if t > 0.0 { hover[1] } else { idle[1] }));
+                targets.push((overlay, if t > 0.0 { hover[0] } else { idle[0] }));
+            },
+            // Disabled buttons fall back to the flat tint
+            gradient => {
+                targets.push((self.image, button.image_color(self.variant).sample(theme, t, ColorSpace::Srgb)));
+                if let Some((overlay, _)) = gradient { targets.push((overlay, Color::NONE)); }
+            },
+        }
+        for edge in &self.edges { targets.push((*edge, button.edge_color(self.variant).sample(theme, t, ColorSpace::Srgb))); }
+        targets.push((self.text, button.text_color().sample(theme, t, ColorSpace::Srgb)));
+        if let Some(subtitle) = self.subtitle { targets.push((subtitle, button.subtitle_color().sample(theme, t, ColorSpace::Srgb))); }
+        for entity in self.hint.iter().chain(&self.arrow) { targets.push((*entity, button.hint_color().sample(theme, t, ColorSpace::Srgb))); }
+        targets
+    }
+    /// The idle and hover colors of each internal entity
+    pub(crate) fn endpoints(&self, button: &MainButton, theme: &UiTheme) -> Vec<(Entity, [Color; 2])> {
+        let hover = self.color_targets(button, theme, true);
+        self.color_targets(button, theme, false).into_iter().zip(hover).map(|((entity, idle), (_, hover))| (entity, [idle, hover])).collect()
+    }
+    /// This system requests the pointer cursor while the button is hovered. The enter and leave events can
+    /// be missed, like when the window loses focus or the button moves under a still cursor, so the hover
+    /// is compared with the [`HoverMap`] here and the missing events are triggered.
+    pub(crate) fn hover_system(
+        mut commands: Commands,
+        hover: Res<HoverMap>,
+        query: Query<(Entity, &MainButtonControl)>,
+        pointers: Query<(&PointerId, &PointerLocation)>,
+        arbiter: Res<HoverArbiter>,
+        away: Res<MainButtonAway>,
+        mut requests: ResMut<CursorRequests>,
+    ) {
+        for (entity, control) in &query {
+            let Some((pointer, location, hit)) = &control.hover_source else { continue; };
+            if hover.get(pointer).is_some_and(|hovered| hovered.contains_key(&entity)) {
+                requests.request(SystemCursorIcon::Pointer, CursorRequests::HOVER, entity);
+            } else {
+                commands.trigger_targets(Pointer::new(entity, *pointer, location.clone(), Out { hit: hit.clone() }), entity);
+            }
+        }
+
+        // The stale hits of a cursor outside the window would enter the buttons it left again
+        if away.0 { return; }
+        // Walk the hovered nodes instead of all buttons, there are far fewer of them
+        for (pointer, location) in &pointers {
+            let (Some(hovered), Some(location)) = (hover.get(pointer), location.location()) else { continue; };
+            for (entity, hit) in hovered {
+                let Ok((_, control)) = query.get(*entity) else { continue; };
+                // Only the topmost button is entered, the others under the pointer wait for their turn
+                if control.hovered || arbiter.winner != Some(*entity) { continue; }
+                commands.trigger_targets(Pointer::new(*entity, *pointer, location.clone(), Over { hit: hit.clone() }), *entity);
+            }
+        }
+    }
+    /// This system leaves the hovered buttons when the cursor leaves the window or the window loses focus,
+    /// picking sends no leave then. They are not entered again until the cursor or the focus is back.
+    /// A press released outside the window is dropped once the cursor is back.
+    pub(crate) fn window_system(
+        mut commands: Commands,
+        mut left: EventReader<CursorLeft>,
+        mut entered: EventReader<CursorEntered>,
+        mut focused: EventReader<WindowFocused>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        mut query: Query<(Entity, &mut MainButtonControl)>,
+        mut away: ResMut<MainButtonAway>,
+    ) {
+        let (mut lost, mut back) = (left.read().count() > 0, entered.read().count() > 0);
+        let released = back && !mouse.pressed(MouseButton::Left);
+        for event in focused.read() {
+            if event.focused { back = true; } else { lost = true; }
+        }
+        // Leaving wins when both happen in one frame, the buttons wait for the next enter or focus
+        let outside = lost || (away.0 && !back);
+        if away.0 != outside { away.0 = outside; }
+        if !lost && !released { return; }
+        for (entity, mut control) in &mut query {
+            if released && control.pressed {
+                control.pressed = false;
+                control.settled = false;
+            }
+            if !lost { continue; }
+            let Some((pointer, location, hit)) = control.hover_source.clone() else { continue; };
+            commands.trigger_targets(Pointer::new(entity, pointer, location, Out { hit }), entity);
+        }
+    }
+    /// This system blends the transition between the last two fixed ticks for rendering.
+    pub(crate) fn interpolate_system(time: Res<Time<Fixed>>, mut query: Query<&mut MainButtonControl>) {
+        let t = time.overstep_fraction();
+        for mut control in &mut query {
+            if !control.awake { continue; }
+            let [previous, current] = control.ticks;
+            let value = previous + (current - previous) * t;
+            if control.transition != value { control.transition = value; }
+        }
+    }
+    /// The atlas frame matching the current state
+    fn frame(&self, frames: [usize; 3]) -> usize {
+        if self.pressed { frames[2] } else if self.transition >= 0.5 { frames[1] } else { frames[0] }
+    }
+    /// This system wakes every button when the theme, the style or the reduced motion changed, overrides apply to descendants too.
+    pub(crate) fn wake_system(
+        resolver: ThemeResolver,
+        style: Res<UiStyle>,
+        reduced: Res<ReducedMotion>,
+        changed: Query<(), Or<(Changed<ThemeOverride>, Changed<ThemeRules>)>>,
+        mut removed: RemovedComponents<ThemeOverride>,
+        mut removed_rules: RemovedComponents<ThemeRules>,
+        mut wake: ResMut<MainButtonWake>,
+    ) {
+        let woken = resolver.is_changed() || style.is_changed() || reduced.is_changed() || !changed.is_empty() || removed.read().count() > 0 || removed_rules.read().count() > 0;
+        if wake.0 != woken { wake.0 = woken; }
+    }
+    /// This system starts the color animations, confirms the hover intents, sends the animation phases and switches the sprite sheet frames.
+    /// Buttons whose control did not change since the last run, like the settled ones, are skipped.
+    pub(crate) fn apply_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut MainButtonControl), Changed<MainButtonControl>>,
+        mut sprites: Query<(&mut Sprite, &mut Visibility)>,
+        mut phases: EventWriter<MainButtonAnimationEvent>,
+    ) {
+        // The color animations of all buttons are started with one batched command
+        let mut animations = Vec::new();
+        for (entity, mut control) in &mut query {
+            // A fixed tick can settle the button after it reached a phase, so they are sent first
+            if !control.phases.is_empty() {
+                let source = control.root;
+                phases.send_batch(control.phases.drain(..).map(|phase| MainButtonAnimationEvent { source, phase }));
+            }
+            if !control.awake { continue; }
+            if control.intended {
+                control.intended = false;
+                commands.trigger_targets(HoverIntended, entity);
+            }
+            animations.append(&mut control.pending);
+
+            let (Some(frames), Some(ghost)) = (control.atlas_frames, control.ghost) else { continue; };
+            let frame = control.frame(frames);
+            let Ok((sprite, _)) = sprites.get(control.image) else { continue; };
+            let previous = sprite.texture_atlas.as_ref().map(|atlas| atlas.index);
+            let color = sprite.color;
+
+            // Leave the previous frame half visible on top for one frame so it does not pop
+            if let Ok((mut ghost_sprite, mut visibility)) = sprites.get_mut(ghost) {
+                if previous.is_some_and(|previous| previous != frame) {
+                    if let Some(atlas) = &mut ghost_sprite.texture_atlas { atlas.index = previous.unwrap_or(frame); }
+                    ghost_sprite.color = color.with_alpha(color.alpha() * 0.5);
+                    *visibility = Visibility::Inherited;
+                    control.settled = false;
+                } else if *visibility != Visibility::Hidden {
+                    *visibility = Visibility::Hidden;
+                }
+            }
+            for entity in std::iter::once(control.image).chain(control.gradient.map(|(overlay, _)| overlay)) {
+                let Ok((mut sprite, _)) = sprites.get_mut(entity) else { continue; };
+                if let Some(atlas) = &mut sprite.texture_atlas { if atlas.index != frame { atlas.index = frame; } }
+            }
+        }
+        if !animations.is_empty() { commands.insert_batch(animations); }
+    }
+}
+
+/// Which schedule advances the widget animations, insert it before the widgets plugin to choose
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[reflect(Resource)]
+pub enum UiAnimationTiming {
+    /// Every frame in `Update` with the frame delta
+    #[default]
+    Variable,
+    /// In `FixedUpdate` with the fixed delta and blended between the ticks for rendering, for deterministic replays and headless tests.
+    /// The enter and leave events are kept in the control state until the next tick.
+    Fixed,
+}
+
+/// How long the pointer has to rest on a [`MainButton`] before it animates and plays the hover sound.
+/// Zero hovers instantly.
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct HoverIntent {
+    /// The delay in seconds
+    pub delay: f32,
+}
+impl Default for HoverIntent {
+    fn default() -> Self {
+        Self { delay: 0.05 }
+    }
+}
+
+/// The one [`MainButton`] the pointer hovers, the one on the highest [`UiLayer`] and then the highest depth.
+/// Buttons can overlap while their layouts animate and picking reports them in any order, so every other
+/// button under the pointer is left before the transitions advance and only this one looks hovered.
+/// It also confirms the [`HoverIntent`] of the winner, the other buttons never start animating in.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct HoverArbiter {
+    winner: Option<Entity>,
+}
+impl HoverArbiter {
+    /// The hovered button, if the pointer is over any that is not blocked
+    pub fn winner(&self) -> Option<Entity> {
+        self.winner
+    }
+    /// This system picks the topmost hovered button, arms and counts down its hover intent and leaves the others that entered,
+    /// ties go to the one spawned last.
+    pub(crate) fn system(
+        mut commands: Commands,
+        hover: Res<HoverMap>,
+        mut query: Query<(Entity, &mut MainButtonControl, &GlobalTransform)>,
+        layers: UiLayers,
+        mut arbiter: ResMut<HoverArbiter>,
+        intent: Res<HoverIntent>,
+        touch: Res<TouchMode>,
+        time: Res<Time>,
+    ) {
+        let previous = arbiter.winner;
+        let winner = hover.values().flat_map(|hovered| hovered.keys())
+            .filter_map(|entity| query.get(*entity).ok())
+            .filter(|(entity, ..)| !layers.is_blocked(*entity))
+            .map(|(entity, _, transform)| (layers.layer_of(entity), transform.translation().z, entity))
+            .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2)))
+            .map(|(.., entity)| entity);
+        if arbiter.winner != winner { arbiter.winner = winner; }
+
+        // Only the buttons under a pointer and the last winner can be hovered, the rest is not visited
+        let mut candidates: Vec<Entity> = hover.values().flat_map(|hovered| hovered.keys().copied()).chain(previous).collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        for entity in candidates {
+            let Ok((entity, mut control, _)) = query.get_mut(entity) else { continue; };
+            // Confirm the hover once the pointer stayed for the whole delay, the confirmation is sent by the apply
+            if Some(entity) == winner {
+                if control.hovered && control.intent.is_none() && !control.intended && control.animation_direction <= 0.0 {
+                    control.intent = Some(if touch.is_active() { 0.0 } else { intent.delay.max(0.0) });
+                }
+                if let Some(left) = control.intent {
+                    let left = left - time.delta_secs();
+                    control.intent = (left > 0.0).then_some(left);
+                    if left <= 0.0 { control.intended = true; }
+                }
+                continue;
+            }
+            if !control.hovered && control.animation_direction <= 0.0 { continue; }
+            let Some((pointer, location, hit)) = control.hover_source.clone() else { continue; };
+            commands.trigger_targets(Pointer::new(entity, pointer, location, Out { hit }), entity);
+        }
+    }
+}
+
+/// This event is triggered on a [`MainButton`] entity when the pointer rested on it for the [`HoverIntent`] delay
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverIntended;
+
+/// This event is triggered on a [`MainButton`] entity when it is clicked while enabled
+#[derive(Event, Clone, Copy, Debug)]
+pub struct MainButtonClicked;
+
+/// How far the hover transition of a [`MainButton`] got
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum MainButtonAnimationPhase {
+    /// The transition started towards the hover
+    HoverStarted,
+    /// The transition reached the hover, only if it was not turned around before
+    HoverCompleted,
+    /// The transition started back towards the idle state
+    UnhoverStarted,
+    /// The transition reached the idle state, only if it was not turned around before
+    UnhoverCompleted,
+}
+
+/// Sent once each time the hover transition of a [`MainButton`] starts or completes, like to show a tooltip
+/// or play a sound once the hover settled
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MainButtonAnimationEvent {
+    /// The button entity
+    pub source: Entity,
+    pub phase: MainButtonAnimationPhase,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, window::{CursorEntered, CursorLeft, WindowFocused}};
+    use bevy_lunex::{Dimension, UiLayoutRoot};
+    use game_vfx::ReducedMotion;
+
+    use crate::*;
+
+    #[test]
+    fn hover_is_confirmed_after_the_intent_delay() {
+        let mut test = UiTestApp::new();
+        test.watch::<Pointer<Over>>().watch::<HoverIntended>();
+        let button = test.spawn_button("Play");
+
+        test.hover(button);
+        assert_eq!(test.assert_event::<Pointer<Over>>(), button);
+        test.assert_no_event::<HoverIntended>();
+
+        test.advance(2.0 * HoverIntent::default().delay);
+        assert_eq!(test.assert_event::<HoverIntended>(), button);
+        assert!(test.world().get::<MainButtonControl>(button).unwrap().animation_direction > 0.0);
+    }
+
+    #[test]
+    fn compact_button_is_built_without_the_hint_and_the_arrow() {
+        let mut test = UiTestApp::new();
+        let button = test.spawn_button("Play");
+        let (image, _) = test.button_parts(button);
+        assert_eq!(test.world().get::<Children>(image).unwrap().len(), 3, "The full button is not built from the label, the hint and the arrow");
+
+        // Switching to compact builds the internals again
+        test.world_mut().entity_mut(button).insert(MainButton::new("Play").compact(true));
+        test.advance(3.0 * UiTestApp::FRAME);
+        let control = test.world().get::<MainButtonControl>(button).expect("The compact button was not built again");
+        assert_eq!(control.hint(), None);
+        let (image, text) = test.button_parts(button);
+        assert_eq!(test.world().get::<Children>(image).unwrap().to_vec(), [text], "The compact button has more than its label");
+
+        test.hover(button);
+        test.advance(0.5);
+        assert!(test.world().get::<MainButtonControl>(button).unwrap().transition > 0.0, "The compact button did not animate in");
+    }
+
+    #[test]
+    fn only_the_topmost_of_overlapping_buttons_animates_in() {
+        let mut test = UiTestApp::new();
+        test.watch::<HoverIntended>();
+        test.world_mut().insert_resource(HoverIntent { delay: 0.0 });
+
+        // The button above is spawned first and moved onto a higher layer, so spawn order alone would pick the wrong one
+        let above = test.spawn_button("Above");
+        let below = test.spawn_button("Below");
+        let layer = test.world_mut().spawn((UiLayoutRoot::new_2d(), Dimension(Vec2::new(1280.0, 720.0)), UiLayer(1))).id();
+        test.world_mut().entity_mut(above).set_parent(layer);
+        for button in [below, above] {
+            test.world_mut().entity_mut(button).insert(PickingBehavior { should_block_lower: false, is_hoverable: true });
+        }
+        test.advance(UiTestApp::FRAME);
+
+        // Both enter in the same frame, the one losing the arbitration must not start animating even for a frame
+        test.hover_all(&[below, above]);
+        assert_eq!(test.world().resource::<HoverArbiter>().winner(), Some(above), "The button on the lower layer won the hover");
+        test.advance(UiTestApp::FRAME);
+        for frame in 0..10 {
+            let animating: Vec<Entity> = [below, above].into_iter()
+                .filter(|button| test.world().get::<MainButtonControl>(*button).unwrap().animation_direction == 1.0)
+                .collect();
+            assert_eq!(animating, [above], "Not only the button above animates in on frame {frame}");
+            assert_eq!(test.world().get::<MainButtonControl>(below).unwrap().transition, 0.0, "The covered button moved towards the hover");
+            test.advance(UiTestApp::FRAME);
+        }
+        assert_eq!(test.assert_event::<HoverIntended>(), above);
+        test.assert_no_event::<HoverIntended>();
+        assert!(test.world().get::<MainButtonControl>(above).unwrap().transition > 0.0);
+    }
+
+    /// The animation phases sent so far
+    #[derive(Resource, Default)]
+    struct Phases(Vec<MainButtonAnimationPhase>);
+
+    /// Hovers a button for a second in updates of the frame, returns the transition after every update
+    fn hover_for_a_second(timing: UiAnimationTiming, frame: f32) -> (UiTestApp, Entity, Vec<f32>) {
+        let mut test = UiTestApp::with_timing(timing);
+        test.world_mut().init_resource::<Phases>();
+        test.app.add_systems(Last, |mut events: EventReader<MainButtonAnimationEvent>, mut phases: ResMut<Phases>| {
+            phases.0.extend(events.read().map(|event| event.phase));
+        });
+        let button = test.spawn_button("Play");
+
+        test.set_frame(frame);
+        test.hover(button);
+        let transitions = (0..(1.0 / frame).round() as usize).map(|_| {
+            test.advance(frame);
+            test.world().get::<MainButtonControl>(button).unwrap().transition
+        }).collect();
+        (test, button, transitions)
+    }
+
+    #[test]
+    fn hover_ends_the_same_at_5_and_200_ms_frames() {
+        for timing in [UiAnimationTiming::Variable, UiAnimationTiming::Fixed] {
+            let runs = [hover_for_a_second(timing, 0.005), hover_for_a_second(timing, 0.2)];
+            for (test, _, transitions) in &runs {
+                assert!(transitions.windows(2).all(|pair| pair[0] <= pair[1]), "The {timing:?} transition went back: {transitions:?}");
+                assert_eq!(transitions.last(), Some(&1.0), "The {timing:?} transition did not land on the hover");
+                assert_eq!(test.world().resource::<Phases>().0, [MainButtonAnimationPhase::HoverStarted, MainButtonAnimationPhase::HoverCompleted]);
+            }
+
+            let [(fast, fast_button, _), (slow, slow_button, _)] = &runs;
+            let (fast_image, fast_text) = fast.button_parts(*fast_button);
+            let (slow_image, slow_text) = slow.button_parts(*slow_button);
+            fast.assert_color_approx(fast_image, slow.world().get::<Sprite>(slow_image).unwrap().color);
+            fast.assert_color_approx(fast_text, slow.world().get::<TextColor>(slow_text).unwrap().0);
+        }
+    }
+
+    #[test]
+    fn reduced_motion_snaps_the_hover() {
+        for reduced in [false, true] {
+            let mut test = UiTestApp::new();
+            test.world_mut().insert_resource(HoverIntent { delay: 0.0 });
+            test.world_mut().insert_resource(ReducedMotion(reduced));
+            let button = test.spawn_button("Play");
+
+            // The hover is confirmed in the first update and the transition starts in the next
+            test.hover(button);
+            test.advance(UiTestApp::FRAME);
+            let transition = test.world().get::<MainButtonControl>(button).unwrap().transition;
+            if !reduced {
+                assert!(transition > 0.0 && transition < 1.0, "The hover did not animate, the transition is {transition}");
+                continue;
+            }
+            assert_eq!(transition, 1.0, "The hover did not snap under reduced motion");
+
+            // The colors started by the button snap once the animations run
+            test.advance(2.0 * UiTestApp::FRAME);
+            for (entity, color) in test.world().get::<MainButtonControl>(button).unwrap().color_targets.clone() {
+                test.assert_color_approx(entity, color);
+            }
+
+            test.unhover();
+            test.advance(UiTestApp::FRAME);
+            assert_eq!(test.world().get::<MainButtonControl>(button).unwrap().transition, 0.0, "The leave did not snap under reduced motion");
+        }
+    }
+
+    #[test]
+    fn leaving_the_window_unhovers_the_button() {
+        // Leaving sends false and coming back sends true
+        let leaves: [(&str, fn(&mut World, Entity, bool)); 2] = [
+            ("cursor", |world, window, back| if back { world.send_event(CursorEntered { window }); } else { world.send_event(CursorLeft { window }); }),
+            ("focus", |world, window, focused| { world.send_event(WindowFocused { window, focused }); }),
+        ];
+        for (name, leave) in leaves {
+            let mut test = UiTestApp::new();
+            let window = test.world_mut().spawn(Window::default()).id();
+            let button = test.spawn_button("Play");
+            test.hover(button);
+            test.advance(0.5);
+            assert_eq!(test.world().get::<MainButtonControl>(button).unwrap().transition, 1.0);
+
+            // Picking sends no leave and keeps hitting the button at the last position
+            leave(test.world_mut(), window, false);
+            test.advance(0.5);
+            let control = test.world().get::<MainButtonControl>(button).unwrap();
+            assert!(!control.hovered && control.animation_direction < 0.0, "The button stayed hovered once the {name} left");
+            assert_eq!(control.transition, 0.0, "The button did not animate back once the {name} left");
+
+            leave(test.world_mut(), window, true);
+            test.advance(0.5);
+            assert_eq!(test.world().get::<MainButtonControl>(button).unwrap().transition, 1.0, "The button was not entered again once the {name} was back");
+        }
+    }
+
+    #[test]
+    fn click_sends_clicked() {
+        let mut test = UiTestApp::new();
+        let button = test.spawn_button("Play");
+
+        test.click(button);
+        assert_eq!(test.assert_event::<MainButtonClicked>(), button);
+        test.assert_no_event::<MainButtonClicked>();
+    }
+
+    #[test]
+    fn disabled_button_is_not_clicked() {
+        let mut test = UiTestApp::new();
+        let button = test.spawn_button("Play");
+        test.world_mut().get_mut::<MainButton>(button).unwrap().disabled = true;
+        test.advance(UiTestApp::FRAME);
+
+        test.click(button);
+        test.assert_no_event::<MainButtonClicked>();
+        assert!(test.world().get::<Shake>(button).is_some(), "The disabled button did not shake");
+        let mut sounds = test.world().resource::<Events<PlayUiSound>>().iter_current_update_events();
+        assert!(sounds.any(|sound| sound.0 == UiSoundKind::Error), "The click was not rejected with the error sound");
+        assert!(test.world().get::<MainButtonControl>(button).unwrap().animation_direction <= 0.0, "The disabled button animated in");
+    }
+}
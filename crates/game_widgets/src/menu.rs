@@ -0,0 +1,478 @@
+use bevy::{a11y::{accesskit::{Node, Role}, AccessibilityNode}, asset::{io::Reader, AssetLoader, LoadContext}, ecs::system::EntityCommands, prelude::*, sprite::Anchor, utils::HashMap};
+use bevy_lunex::*;
+use game_loading::*;
+use serde::Deserialize;
+
+use crate::*;
+
+
+/// A menu declared in a `.menu.ron` file, spawn it with [`SpawnMenu`]. Editing the file patches every
+/// menu spawned from it in place, so the focus and the state of unchanged widgets survive. The nodes are
+/// checked out of the [`UiNodePool`], so opening a long menu again reuses the entities of the last one.
+#[derive(Asset, TypePath, Clone, PartialEq, Debug, Default)]
+pub struct MenuDefinition {
+    /// The nodes placed in the root of the menu
+    pub nodes: Vec<MenuNode>,
+}
+impl MenuDefinition {
+    /// Parses the menu from a RON string
+    pub fn parse(source: &str) -> Result<Self, MenuError> {
+        let source: MenuSource = ron::from_str(source).map_err(|error| MenuError::Parse(error.to_string()))?;
+        let nodes = source.nodes.into_iter().enumerate()
+            .map(|(index, node)| MenuNode::resolve(node, format!("nodes[{index}]")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { nodes })
+    }
+}
+
+/// A node of a [`MenuDefinition`] and the widget it spawns
+#[derive(Clone, PartialEq, Debug)]
+pub struct MenuNode {
+    /// Spawned as [`MenuNodeId`] if not empty, so the game can find the node
+    pub id: String,
+    pub kind: MenuNodeKind,
+    /// Position in percent of the parent
+    pub pos: Vec2,
+    /// Size in percent of the parent, text only uses the height
+    pub size: Vec2,
+    /// The point of the node placed at the position
+    pub anchor: Anchor,
+    pub children: Vec<MenuNode>,
+}
+impl MenuNode {
+    /// Converts the node as written, the path names it in the errors
+    fn resolve(value: ron::Value, path: String) -> Result<Self, MenuError> {
+        let error = |message: String| MenuError::Node { path: path.clone(), message };
+        let source: MenuNodeSource = value.into_rust().map_err(|e| error(e.to_string()))?;
+
+        let kind = match source.kind.as_str() {
+            "panel" => MenuNodeKind::Panel,
+            "button" => MenuNodeKind::Button { text: source.text, hint: source.hint, disabled: source.disabled, auto_width: source.auto_width },
+            "text" => MenuNodeKind::Text {
+                text: source.text,
+                style: match source.style.as_str() {
+                    "heading" => MenuTextStyle::Heading,
+                    "body" => MenuTextStyle::Body,
+                    "hint" => MenuTextStyle::Hint,
+                    "mono" => MenuTextStyle::Mono,
+                    style => return Err(error(format!("unknown text style '{style}', expected heading, body, hint or mono"))),
+                },
+            },
+            kind => return Err(error(format!("unknown kind '{kind}', expected panel, button or text"))),
+        };
+        let anchor = match source.anchor.as_str() {
+            "top_left" => Anchor::TopLeft,
+            "top_center" => Anchor::TopCenter,
+            "top_right" => Anchor::TopRight,
+            "center_left" => Anchor::CenterLeft,
+            "center" => Anchor::Center,
+            "center_right" => Anchor::CenterRight,
+            "bottom_left" => Anchor::BottomLeft,
+            "bottom_center" => Anchor::BottomCenter,
+            "bottom_right" => Anchor::BottomRight,
+            anchor => return Err(error(format!("unknown anchor '{anchor}'"))),
+        };
+        if matches!(kind, MenuNodeKind::Text { .. }) && !source.children.is_empty() {
+            return Err(error("text can not have children".into()));
+        }
+
+        let children = source.children.into_iter().enumerate()
+            .map(|(index, child)| MenuNode::resolve(child, format!("{path}.children[{index}]")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { id: source.id, kind, pos: source.pos.into(), size: source.size.into(), anchor, children })
+    }
+    /// The part of the [`MenuNodePath`] naming this node among its siblings
+    fn segment(&self, index: usize) -> String {
+        if self.id.is_empty() { format!("#{index}") } else { self.id.clone() }
+    }
+    /// Checks the node and its children out of the pool under the parent and notes their entities by path
+    #[allow(clippy::too_many_arguments)]
+    fn spawn(&self, commands: &mut Commands, pool: &mut UiNodePool, parent: Entity, assets: &AssetCache, style: &UiStyle, path: String, entities: &mut HashMap<String, Entity>) {
+        let entity = pool.checkout(commands, MenuNodePath(path.clone()));
+        commands.entity(parent).add_child(entity);
+        self.insert(&mut commands.entity(entity), assets, style);
+        entities.insert(path.clone(), entity);
+        for (index, child) in self.children.iter().enumerate() {
+            child.spawn(commands, pool, entity, assets, style, format!("{path}/{}", child.segment(index)), entities);
+        }
+    }
+    /// Inserts the components of the node, replacing the ones of an earlier version of it
+    fn insert(&self, entity: &mut EntityCommands, assets: &AssetCache, style: &UiStyle) {
+        let (pos, size) = (Rl((self.pos.x, self.pos.y)), Rl((self.size.x, self.size.y)));
+        match &self.kind {
+            MenuNodeKind::Panel => { entity.insert(UiLayout::window().pos(pos).anchor(self.anchor).size(size).pack()); },
+            MenuNodeKind::Button { text, hint, disabled, auto_width } => {
+                let layout = UiLayout::window().pos(pos).anchor(self.anchor).size(size);
+                entity.insert((layout.pack(), MainButton::new(text).hint(hint).disabled(*disabled)));
+                // The width of the node is the widest the button grows to
+                if *auto_width { entity.insert(AutoWidth::new(layout).unit(AutoWidthUnit::Relative).max(self.size.x)); } else { entity.remove::<AutoWidth>(); }
+            },
+            MenuNodeKind::Text { text, style: text_style } => {
+                let typography = match text_style {
+                    MenuTextStyle::Heading => Typography::heading(assets, style),
+                    MenuTextStyle::Body => Typography::body(assets, style),
+                    MenuTextStyle::Hint => Typography::hint(assets, style),
+                    MenuTextStyle::Mono => Typography::mono(assets, style),
+                };
+                let mut node = Node::new(if *text_style == MenuTextStyle::Heading { Role::Heading } else { Role::Label });
+                node.set_label(text.as_str());
+                entity.insert((
+                    // For text always use window layout to position it
+                    UiLayout::window().pos(pos).anchor(self.anchor).pack(),
+                    UiTextSize::from(Rh(self.size.y)),
+                    Text2d::new(text),
+                    typography.bundle(),
+                    AccessibilityNode(node),
+                ));
+            },
+        }
+        if !self.id.is_empty() { entity.insert(MenuNodeId(self.id.clone())); } else { entity.remove::<MenuNodeId>(); }
+    }
+    /// Patches the spawned nodes under the parent from the old to the new version. Nodes keep their
+    /// entity while their kind stays, nodes of another kind are respawned.
+    #[allow(clippy::too_many_arguments)]
+    fn patch(
+        commands: &mut Commands,
+        pool: &mut UiNodePool,
+        old: &[MenuNode],
+        new: &[MenuNode],
+        parent: Entity,
+        path: Option<&str>,
+        entities: &mut HashMap<String, Entity>,
+        assets: &AssetCache,
+        style: &UiStyle,
+    ) {
+        let join = |segment: String| match path {
+            Some(path) => format!("{path}/{segment}"),
+            None => segment,
+        };
+        let old: HashMap<String, &MenuNode> = old.iter().enumerate().map(|(index, node)| (join(node.segment(index)), node)).collect();
+        let mut kept = Vec::new();
+
+        for (index, node) in new.iter().enumerate() {
+            let path = join(node.segment(index));
+            let entity = entities.get(&path).copied();
+            match (old.get(&path), entity) {
+                (Some(previous), Some(entity)) if std::mem::discriminant(&previous.kind) == std::mem::discriminant(&node.kind) => {
+                    if **previous != *node { node.insert(&mut commands.entity(entity), assets, style); }
+                    MenuNode::patch(commands, pool, &previous.children, &node.children, entity, Some(&path), entities, assets, style);
+                },
+                (_, entity) => {
+                    if let Some(entity) = entity { MenuNode::release(commands, &path, entity, entities); }
+                    node.spawn(commands, pool, parent, assets, style, path.clone(), entities);
+                },
+            }
+            kept.push(path);
+        }
+
+        for path in old.keys().filter(|path| !kept.contains(path)) {
+            if let Some(entity) = entities.get(path).copied() { MenuNode::release(commands, path, entity, entities); }
+        }
+    }
+    /// Returns the node to the [`UiNodePool`] and forgets the paths of it and its children
+    fn release(commands: &mut Commands, path: &str, entity: Entity, entities: &mut HashMap<String, Entity>) {
+        let prefix = format!("{path}/");
+        entities.retain(|other, _| other != path && !other.starts_with(&prefix));
+        UiNodePool::release(commands, entity);
+    }
+}
+
+/// The widget a [`MenuNode`] spawns
+#[derive(Clone, PartialEq, Debug)]
+pub enum MenuNodeKind {
+    /// An empty node grouping its children
+    Panel,
+    /// A [`MainButton`], with an [`AutoWidth`] up to the width of the node if enabled
+    Button { text: String, hint: String, disabled: bool, auto_width: bool },
+    /// A line of text
+    Text { text: String, style: MenuTextStyle },
+}
+
+/// The [`Typography`] preset of a text [`MenuNode`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum MenuTextStyle {
+    Heading,
+    #[default]
+    Body,
+    Hint,
+    Mono,
+}
+
+/// The id of the [`MenuNode`] the entity was spawned from, observe [`MainButtonClicked`] and read this to react to menu buttons
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Hash, Debug)]
+#[reflect(Component)]
+pub struct MenuNodeId(pub String);
+
+/// Where the entity is in its menu, the ids of the nodes from the root joined by `/`.
+/// Nodes without an id use their index like `#2`. Reloads match the nodes by this path.
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Hash, Debug)]
+#[reflect(Component)]
+pub struct MenuNodePath(pub String);
+
+/// Send this event to spawn the menu in a new root tree, it is built once the menu and the user interface assets are loaded
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct SpawnMenu(pub Handle<MenuDefinition>);
+
+/// The root tree of a spawned [`MenuDefinition`], close it with [`MenuInstance::close`] so its nodes are reused
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct MenuInstance {
+    pub menu: Handle<MenuDefinition>,
+    /// The version of the menu the nodes were built from
+    built: Option<MenuDefinition>,
+    /// The built nodes by their [`MenuNodePath`]
+    entities: HashMap<String, Entity>,
+}
+impl MenuInstance {
+    /// Returns the node of the menu at the path, once built
+    pub fn node(&self, path: &str) -> Option<Entity> {
+        self.entities.get(path).copied()
+    }
+    /// Closes the menu, its root and all its nodes are returned to the [`UiNodePool`] for the next menu
+    pub fn close(commands: &mut Commands, menu: Entity) {
+        UiNodePool::release(commands, menu);
+    }
+}
+impl MenuInstance {
+    /// This system spawns the root trees of the requested menus.
+    fn spawn_system(mut commands: Commands, mut events: EventReader<SpawnMenu>, mut pool: ResMut<UiNodePool>) {
+        for SpawnMenu(menu) in events.read() {
+            pool.checkout(&mut commands, (
+                UiLayoutRoot::new_2d(),
+                UiFetchFromCamera::<0>,
+                MenuInstance { menu: menu.clone(), built: None, entities: HashMap::new() },
+            ));
+        }
+    }
+    /// This system builds the nodes of the menus that are loaded and not built yet.
+    fn build_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &mut MenuInstance)>,
+        menus: Res<Assets<MenuDefinition>>,
+        assets: Res<AssetCache>,
+        style: Res<UiStyle>,
+        mut pool: ResMut<UiNodePool>,
+    ) {
+        for (entity, mut instance) in &mut query {
+            if instance.built.is_some() { continue; }
+            let Some(menu) = menus.get(&instance.menu) else { continue; };
+            let mut entities = HashMap::new();
+            for (index, node) in menu.nodes.iter().enumerate() { node.spawn(&mut commands, &mut pool, entity, &assets, &style, node.segment(index), &mut entities); }
+            instance.built = Some(menu.clone());
+            instance.entities = entities;
+        }
+    }
+    /// This system patches the menus whose file changed. The focus follows a respawned node to its new entity.
+    #[allow(clippy::too_many_arguments)]
+    fn reload_system(
+        mut commands: Commands,
+        mut events: EventReader<AssetEvent<MenuDefinition>>,
+        mut query: Query<(Entity, &mut MenuInstance)>,
+        menus: Res<Assets<MenuDefinition>>,
+        assets: Res<AssetCache>,
+        style: Res<UiStyle>,
+        mut focus: ResMut<UiFocus>,
+        mut pool: ResMut<UiNodePool>,
+    ) {
+        for event in events.read() {
+            let AssetEvent::Modified { id } = event else { continue; };
+            let Some(menu) = menus.get(*id) else { continue; };
+            for (entity, mut instance) in &mut query {
+                if instance.menu.id() != *id { continue; }
+                let Some(built) = instance.built.take() else { continue; };
+                let focused = focus.current().and_then(|current| instance.entities.iter().find(|(_, entity)| **entity == current).map(|(path, _)| path.clone()));
+
+                let mut entities = std::mem::take(&mut instance.entities);
+                MenuNode::patch(&mut commands, &mut pool, &built.nodes, &menu.nodes, entity, None, &mut entities, &assets, &style);
+                if let Some(moved) = focused.and_then(|path| entities.get(&path).copied()) {
+                    if focus.current() != Some(moved) { focus.set(moved); }
+                }
+                instance.built = Some(menu.clone());
+                instance.entities = entities;
+                info!("Patched the reloaded menu");
+            }
+        }
+    }
+}
+
+/// Errors produced when a menu file is invalid
+#[derive(Clone, PartialEq, Debug)]
+pub enum MenuError {
+    /// The file could not be read
+    Io(String),
+    /// The file is not valid RON or has an unknown field
+    Parse(String),
+    /// The node at the path, like `nodes[0].children[2]`, is invalid
+    Node { path: String, message: String },
+}
+impl std::fmt::Display for MenuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MenuError::Io(error) => write!(f, "Menu could not be read: {error}"),
+            MenuError::Parse(error) => write!(f, "Menu could not be parsed: {error}"),
+            MenuError::Node { path, message } => write!(f, "Menu node '{path}' is invalid: {message}"),
+        }
+    }
+}
+impl std::error::Error for MenuError {}
+
+/// The menu file as written, nodes are kept as values so their errors can name the node
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MenuSource {
+    nodes: Vec<ron::Value>,
+}
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MenuNodeSource {
+    kind: String,
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    hint: String,
+    #[serde(default)]
+    disabled: bool,
+    #[serde(default)]
+    auto_width: bool,
+    #[serde(default = "MenuNodeSource::default_style")]
+    style: String,
+    #[serde(default)]
+    pos: (f32, f32),
+    #[serde(default = "MenuNodeSource::default_size")]
+    size: (f32, f32),
+    #[serde(default = "MenuNodeSource::default_anchor")]
+    anchor: String,
+    #[serde(default)]
+    children: Vec<ron::Value>,
+}
+impl MenuNodeSource {
+    fn default_style() -> String {
+        "body".into()
+    }
+    fn default_size() -> (f32, f32) {
+        (100.0, 100.0)
+    }
+    fn default_anchor() -> String {
+        "top_left".into()
+    }
+}
+
+/// Loads [`MenuDefinition`] from `.menu.ron` files
+#[derive(Default)]
+struct MenuLoader;
+impl AssetLoader for MenuLoader {
+    type Asset = MenuDefinition;
+    type Settings = ();
+    type Error = MenuError;
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), _context: &mut LoadContext<'_>) -> Result<MenuDefinition, MenuError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(|error| MenuError::Io(error.to_string()))?;
+        MenuDefinition::parse(&String::from_utf8_lossy(&bytes))
+    }
+    fn extensions(&self) -> &[&str] {
+        &["menu.ron"]
+    }
+}
+
+
+/// Plugin loading, spawning and hot-reloading the [`MenuDefinition`] files
+pub(crate) struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<MenuDefinition>();
+        app.register_asset_loader(MenuLoader);
+        app.register_type::<MenuNodeId>();
+        app.register_type::<MenuNodePath>();
+        app.add_event::<SpawnMenu>();
+        app.add_systems(Update, (
+            MenuInstance::spawn_system,
+            MenuInstance::reload_system.run_if(resource_exists::<AssetCache>),
+            MenuInstance::build_system.run_if(ui_assets_ready.or(ui_assets_failed)),
+        ).chain().before(SandboxedUiSystems));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, sprite::Anchor, utils::HashSet};
+    use bevy_lunex::{Dimension, UiColor, UiHover};
+
+    use crate::*;
+
+    /// A menu of the number of button rows, with the ids `row0`, `row1` and so on
+    fn rows(count: usize) -> MenuDefinition {
+        MenuDefinition { nodes: (0..count).map(|row| MenuNode {
+            id: format!("row{row}"),
+            kind: MenuNodeKind::Button { text: format!("Row {row}"), hint: String::new(), disabled: false, auto_width: false },
+            pos: Vec2::new(0.0, row as f32),
+            size: Vec2::new(100.0, 1.0),
+            anchor: Anchor::TopLeft,
+            children: Vec::new(),
+        }).collect() }
+    }
+
+    /// Spawns the menu and builds its buttons, returns its root
+    fn open(test: &mut UiTestApp, menu: &Handle<MenuDefinition>) -> Entity {
+        test.world_mut().send_event(SpawnMenu(menu.clone()));
+        test.advance(UiTestApp::FRAME);
+        let mut query = test.world_mut().query_filtered::<Entity, With<MenuInstance>>();
+        let root = query.single(test.world());
+        // There is no camera to fetch the size from
+        test.world_mut().entity_mut(root).insert(Dimension(Vec2::new(1280.0, 720.0)));
+        test.advance(2.0 * UiTestApp::FRAME);
+        root
+    }
+
+    /// The row buttons of the open menu
+    fn row_buttons(test: &UiTestApp, root: Entity, count: usize) -> Vec<Entity> {
+        let instance = test.world().get::<MenuInstance>(root).unwrap();
+        (0..count).map(|row| instance.node(&format!("row{row}")).unwrap_or_else(|| panic!("Row {row} was not built"))).collect()
+    }
+
+    /// The entity and all its descendants
+    fn subtree(world: &World, entity: Entity) -> HashSet<Entity> {
+        let mut subtree = vec![entity];
+        let mut index = 0;
+        while let Some(node) = subtree.get(index).copied() {
+            if let Some(children) = world.get::<Children>(node) { subtree.extend(children.iter().copied()); }
+            index += 1;
+        }
+        subtree.into_iter().collect()
+    }
+
+    #[test]
+    fn reopened_menu_reuses_its_entities_without_their_state() {
+        const ROWS: usize = 100;
+        let mut test = UiTestApp::new();
+        test.world_mut().insert_resource(HoverIntent { delay: 0.0 });
+        let menu = test.world_mut().resource_mut::<Assets<MenuDefinition>>().add(rows(ROWS));
+
+        // Leave the first row hovered when the menu closes
+        let root = open(&mut test, &menu);
+        let first = row_buttons(&test, root, ROWS);
+        let idle = test.world().get::<Sprite>(test.button_parts(first[1]).0).unwrap().color;
+        test.hover(first[0]);
+        test.advance(0.5);
+        assert!(test.world().get::<MainButtonControl>(first[0]).unwrap().transition > 0.0, "The row did not animate in");
+
+        let released = subtree(test.world(), root);
+        test.unhover();
+        MenuInstance::close(&mut test.world_mut().commands(), root);
+        test.world_mut().flush();
+        assert!(test.world().resource::<UiNodePool>().len() >= released.len(), "The closed menu was not pooled");
+
+        let root = open(&mut test, &menu);
+        assert!(released.contains(&root), "The root was spawned instead of reused");
+        for (row, button) in row_buttons(&test, root, ROWS).into_iter().enumerate() {
+            assert!(released.contains(&button), "Row {row} was spawned instead of reused");
+            // A row can reuse the entity of an internal, like the hovered image of the last menu
+            let world = test.world();
+            assert!(world.get::<UiColor>(button).is_none() && world.get::<UiHover>(button).is_none() && world.get::<Sprite>(button).is_none(), "Row {row} kept a component of its last life");
+            let control = world.get::<MainButtonControl>(button).unwrap();
+            assert!(!control.hovered && control.animation_direction <= 0.0 && control.transition == 0.0, "Row {row} kept the hover state");
+            test.assert_color_approx(test.button_parts(button).0, idle);
+        }
+    }
+}
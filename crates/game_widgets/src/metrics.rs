@@ -0,0 +1,101 @@
+use bevy::{prelude::*, utils::HashMap, window::{WindowResized, WindowScaleFactorChanged}};
+use bevy_lunex::*;
+
+
+/// Sent once per frame for each window that was resized or moved to a monitor with another scale factor.
+/// Anything derived from the window size or the scale factor should be derived again on it.
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct WindowMetricsChanged {
+    pub window: Entity,
+    /// The logical size of the window
+    pub size: Vec2,
+    pub scale_factor: f32,
+}
+impl WindowMetricsChanged {
+    /// This system merges the resize and the scale factor events of each window into one.
+    fn system(
+        mut commands: Commands,
+        mut resized: EventReader<WindowResized>,
+        mut rescaled: EventReader<WindowScaleFactorChanged>,
+        windows: Query<&Window>,
+        mut changed: EventWriter<WindowMetricsChanged>,
+    ) {
+        let mut metrics = HashMap::new();
+        for window in resized.read().map(|event| event.window).chain(rescaled.read().map(|event| event.window)) {
+            let Ok(state) = windows.get(window) else { continue; };
+            metrics.insert(window, WindowMetricsChanged { window, size: state.size(), scale_factor: state.scale_factor() });
+        }
+        if metrics.is_empty() { return; }
+        changed.send_batch(metrics.into_values());
+
+        // The text sizes of the nodes follow the window, so the layout has to be computed again
+        commands.trigger(RecomputeUiLayout);
+    }
+}
+
+
+/// Plugin sending [`WindowMetricsChanged`]
+pub(crate) struct WindowMetricsPlugin;
+impl Plugin for WindowMetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<WindowMetricsChanged>();
+        app.add_systems(PreUpdate, WindowMetricsChanged::system.after(bevy::input::InputSystem));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, window::{WindowResized, WindowResolution, WindowScaleFactorChanged}};
+    use bevy_lunex::Dimension;
+
+    use crate::*;
+
+    /// The metrics sent in the last update
+    fn sent(test: &UiTestApp) -> Vec<WindowMetricsChanged> {
+        test.world().resource::<Events<WindowMetricsChanged>>().iter_current_update_events().copied().collect()
+    }
+
+    #[test]
+    fn resize_and_rescale_send_one_event() {
+        let mut test = UiTestApp::new();
+        let window = test.world_mut().spawn(Window { resolution: WindowResolution::new(1280.0, 720.0), ..default() }).id();
+        test.advance(UiTestApp::FRAME);
+        assert!(sent(&test).is_empty(), "Metrics were sent without a change");
+
+        // Dragged to a 2x monitor and resized in the same frame
+        let mut state = test.world_mut().get_mut::<Window>(window).unwrap();
+        state.resolution.set_physical_resolution(1920, 1080);
+        state.resolution.set_scale_factor(2.0);
+        test.world_mut().send_event(WindowResized { window, width: 960.0, height: 540.0 });
+        test.world_mut().send_event(WindowScaleFactorChanged { window, scale_factor: 2.0 });
+        test.advance(UiTestApp::FRAME);
+        assert_eq!(sent(&test), [WindowMetricsChanged { window, size: Vec2::new(960.0, 540.0), scale_factor: 2.0 }]);
+    }
+
+    #[test]
+    fn resize_mid_hover_keeps_the_button() {
+        let mut test = UiTestApp::new();
+        let window = test.world_mut().spawn(Window { resolution: WindowResolution::new(1280.0, 720.0), ..default() }).id();
+        let button = test.spawn_button("Play");
+        let (image, text) = test.button_parts(button);
+        test.hover(button);
+        test.advance(0.5);
+
+        let size = test.world().get::<Dimension>(image).map(|dimension| dimension.0);
+        let colors = [test.world().get::<Sprite>(image).unwrap().color, test.world().get::<TextColor>(text).unwrap().0];
+        test.world_mut().get_mut::<Window>(window).unwrap().resolution.set(1600.0, 900.0);
+        test.world_mut().send_event(WindowResized { window, width: 1600.0, height: 900.0 });
+        test.advance(UiTestApp::FRAME);
+        assert_eq!(sent(&test).len(), 1, "The resize was not sent once");
+
+        // The root of the test does not follow the window, so the button must look exactly the same
+        test.advance(0.5);
+        let control = test.world().get::<MainButtonControl>(button).unwrap();
+        assert!(control.hovered && control.animation_direction > 0.0, "The resize left the hover");
+        assert_eq!(control.transition, 1.0);
+        assert_eq!(test.world().get::<Dimension>(image).map(|dimension| dimension.0), size, "The resize changed the size of the image");
+        test.assert_color_approx(image, colors[0]);
+        test.assert_color_approx(text, colors[1]);
+    }
+}
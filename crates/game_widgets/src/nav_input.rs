@@ -0,0 +1,125 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+#[cfg(feature = "leafwing")]
+use leafwing_input_manager::prelude::*;
+
+
+/// The navigation input of one frame
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct UiNavFrame {
+    /// The direction pressed this frame, moves the focus once
+    pub direction: Vec2,
+    /// The direction held down, moves the focus repeatedly
+    pub held: Vec2,
+    /// If the focused widget should be activated
+    pub activate: bool,
+    /// If the screen should go back
+    pub back: bool,
+}
+
+/// Reads the menu navigation. With the `leafwing` feature and an `ActionState` of `UiNavAction`
+/// the input comes from the actions, so menus are rebound like gameplay, otherwise from the built-in
+/// gamepad bindings: the d-pad and left stick move, South activates and East goes back.
+#[derive(SystemParam)]
+pub struct UiNavInput<'w, 's> {
+    gamepads: Query<'w, 's, &'static Gamepad>,
+    #[cfg(feature = "leafwing")]
+    actions: Option<Res<'w, ActionState<UiNavAction>>>,
+}
+impl UiNavInput<'_, '_> {
+    /// If the input comes from the `UiNavAction` state instead of the built-in bindings
+    pub fn uses_actions(&self) -> bool {
+        #[cfg(feature = "leafwing")]
+        if self.actions.is_some() { return true; }
+        false
+    }
+    /// Returns the navigation input of this frame
+    pub fn read(&self) -> UiNavFrame {
+        #[cfg(feature = "leafwing")]
+        if let Some(actions) = &self.actions { return UiNavAction::read(actions); }
+
+        let mut frame = UiNavFrame::default();
+        for gamepad in &self.gamepads {
+            for (button, direction) in [(GamepadButton::DPadUp, Vec2::Y), (GamepadButton::DPadDown, Vec2::NEG_Y), (GamepadButton::DPadLeft, Vec2::NEG_X), (GamepadButton::DPadRight, Vec2::X)] {
+                if gamepad.just_pressed(button) { frame.direction = direction; }
+            }
+            if gamepad.left_stick().length() > 0.5 { frame.held = gamepad.left_stick(); }
+            frame.activate |= gamepad.just_pressed(GamepadButton::South);
+            frame.back |= gamepad.just_pressed(GamepadButton::East);
+        }
+        frame
+    }
+}
+
+/// The menu navigation actions for leafwing-input-manager. Insert your own [`InputMap`] resource to
+/// rebind them, otherwise [`UiNavAction::default_input_map`] is used.
+#[cfg(feature = "leafwing")]
+#[derive(Actionlike, Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UiNavAction {
+    MoveFocusUp,
+    MoveFocusDown,
+    MoveFocusLeft,
+    MoveFocusRight,
+    /// Activates the focused widget
+    Activate,
+    /// Sends [`UiBack`](crate::UiBack)
+    Back,
+    /// Not used by the widgets, bound here so the game reads its console key from the same map
+    ToggleConsole,
+}
+#[cfg(feature = "leafwing")]
+impl UiNavAction {
+    /// The directions of the move actions
+    const MOVES: [(UiNavAction, Vec2); 4] = [
+        (UiNavAction::MoveFocusUp, Vec2::Y),
+        (UiNavAction::MoveFocusDown, Vec2::NEG_Y),
+        (UiNavAction::MoveFocusLeft, Vec2::NEG_X),
+        (UiNavAction::MoveFocusRight, Vec2::X),
+    ];
+    /// The built-in bindings plus the arrow keys, Enter, Escape and the backquote for the console
+    pub fn default_input_map() -> InputMap<Self> {
+        InputMap::default()
+            .with(UiNavAction::MoveFocusUp, GamepadButton::DPadUp)
+            .with(UiNavAction::MoveFocusUp, GamepadControlDirection::LEFT_UP)
+            .with(UiNavAction::MoveFocusUp, KeyCode::ArrowUp)
+            .with(UiNavAction::MoveFocusDown, GamepadButton::DPadDown)
+            .with(UiNavAction::MoveFocusDown, GamepadControlDirection::LEFT_DOWN)
+            .with(UiNavAction::MoveFocusDown, KeyCode::ArrowDown)
+            .with(UiNavAction::MoveFocusLeft, GamepadButton::DPadLeft)
+            .with(UiNavAction::MoveFocusLeft, GamepadControlDirection::LEFT_LEFT)
+            .with(UiNavAction::MoveFocusLeft, KeyCode::ArrowLeft)
+            .with(UiNavAction::MoveFocusRight, GamepadButton::DPadRight)
+            .with(UiNavAction::MoveFocusRight, GamepadControlDirection::LEFT_RIGHT)
+            .with(UiNavAction::MoveFocusRight, KeyCode::ArrowRight)
+            .with(UiNavAction::Activate, GamepadButton::South)
+            .with(UiNavAction::Activate, KeyCode::Enter)
+            .with(UiNavAction::Activate, KeyCode::NumpadEnter)
+            .with(UiNavAction::Back, GamepadButton::East)
+            .with(UiNavAction::Back, KeyCode::Escape)
+            .with(UiNavAction::ToggleConsole, KeyCode::Backquote)
+    }
+    /// Returns the navigation input of the action state, a held move repeats like the stick
+    fn read(actions: &ActionState<UiNavAction>) -> UiNavFrame {
+        let mut frame = UiNavFrame::default();
+        for (action, direction) in UiNavAction::MOVES {
+            if actions.just_pressed(&action) { frame.direction = direction; }
+            if actions.pressed(&action) { frame.held = direction; }
+        }
+        frame.activate = actions.just_pressed(&UiNavAction::Activate);
+        frame.back = actions.just_pressed(&UiNavAction::Back);
+        frame
+    }
+}
+
+
+/// Plugin reading the menu navigation from leafwing-input-manager
+#[cfg(feature = "leafwing")]
+pub(crate) struct UiNavActionPlugin;
+#[cfg(feature = "leafwing")]
+impl Plugin for UiNavActionPlugin {
+    fn build(&self, app: &mut App) {
+        // The game may already run the actions with its own plugin settings
+        if !app.is_plugin_added::<InputManagerPlugin<UiNavAction>>() { app.add_plugins(InputManagerPlugin::<UiNavAction>::default()); }
+        app.init_resource::<ActionState<UiNavAction>>();
+        if !app.world().contains_resource::<InputMap<UiNavAction>>() { app.insert_resource(UiNavAction::default_input_map()); }
+    }
+}
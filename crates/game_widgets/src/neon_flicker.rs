@@ -0,0 +1,162 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use bevy::prelude::*;
+use game_vfx::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::*;
+
+
+/// Insert this on a sprite node, like the logo, or on a [`MainButton`] to make it flicker now and then like a failing neon sign.
+/// Every burst dips the alpha for 1 to 3 frames a few times in a row. The pattern comes from the [`NeonFlickerSeed`]
+/// and the [`Name`] of the entity and starts over with every recording and replay, so a replay flickers
+/// exactly the same. Buttons stay lit while their hover animates and everything stays lit under reduced motion.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct NeonFlicker {
+    /// How deep the dips go in range 0.0 to 1.0
+    pub intensity: f32,
+    /// Bursts per second on average
+    pub frequency: f32,
+}
+impl Default for NeonFlicker {
+    fn default() -> Self {
+        Self { intensity: 0.6, frequency: 0.25 }
+    }
+}
+impl NeonFlicker {
+    /// Creates new instance
+    pub fn new(intensity: f32, frequency: f32) -> Self {
+        Self { intensity: intensity.clamp(0.0, 1.0), frequency }
+    }
+    /// Draws the frames until the next burst, also counted in frames at the usual 60 per second
+    fn wait(&self, rng: &mut StdRng) -> u32 {
+        (rng.random_range(0.3..1.7) * 60.0 / self.frequency.max(0.01)) as u32
+    }
+    /// This system seeds the flicker of new entities, and of all of them again once the seed changed.
+    fn seed_system(
+        mut commands: Commands,
+        query: Query<(Entity, &NeonFlicker, Option<&Name>, Option<&NeonFlickerState>)>,
+        added: Query<(), Added<NeonFlicker>>,
+        seed: Res<NeonFlickerSeed>,
+    ) {
+        for (entity, flicker, name, state) in &query {
+            if state.is_some() && !seed.is_changed() && !added.contains(entity) { continue; }
+            // The name is stable across runs, unlike the entity id
+            let mut hasher = DefaultHasher::new();
+            seed.0.hash(&mut hasher);
+            match name {
+                Some(name) => name.as_str().hash(&mut hasher),
+                None => entity.index().hash(&mut hasher),
+            }
+            let mut rng = StdRng::seed_from_u64(hasher.finish());
+            let wait = flicker.wait(&mut rng);
+            // A node reseeded mid dip is lit again in the next frame
+            let lit = state.and_then(|state| state.lit);
+            commands.entity(entity).insert(NeonFlickerState { rng, wait, dips: 0, frames: lit.map_or(0, |_| 1), lit });
+        }
+    }
+    /// This system plays the bursts one frame at a time and keeps the nodes lit while they must not flicker.
+    fn system(
+        mut query: Query<(Entity, &NeonFlicker, &mut NeonFlickerState)>,
+        controls: Query<&MainButtonControl>,
+        mut sprites: Query<&mut Sprite>,
+        reduced: Res<ReducedMotion>,
+    ) {
+        for (entity, flicker, mut state) in &mut query {
+            let state = &mut *state;
+            let control = controls.get(entity).ok();
+            let target = control.map_or(entity, |control| control.image());
+            let Ok(mut sprite) = sprites.get_mut(target) else { continue; };
+
+            if motion_scale(&reduced) == 0.0 || control.is_some_and(|control| control.hovered || control.transition > 0.0) {
+                if let Some(alpha) = state.lit.take() { sprite.color.set_alpha(alpha); }
+                state.dips = 0;
+                state.frames = 0;
+                continue;
+            }
+
+            // Counted in frames, so the dips stay 1 to 3 frames at any frame rate
+            if state.frames > 0 {
+                state.frames -= 1;
+                if state.frames > 0 { continue; }
+                if let Some(alpha) = state.lit.take() {
+                    sprite.color.set_alpha(alpha);
+                    if state.dips > 0 { state.frames = state.rng.random_range(1..=2); }
+                    continue;
+                }
+            }
+            if state.dips > 0 {
+                state.dips -= 1;
+                state.frames = state.rng.random_range(1..=3);
+                let alpha = sprite.color.alpha();
+                state.lit = Some(alpha);
+                sprite.color.set_alpha(alpha * (1.0 - flicker.intensity * state.rng.random_range(0.4..1.0)));
+                continue;
+            }
+
+            if state.wait > 0 {
+                state.wait -= 1;
+                continue;
+            }
+            state.dips = state.rng.random_range(1..=3);
+            state.wait = flicker.wait(&mut state.rng);
+        }
+    }
+    /// This system starts the patterns over when a recording or a replay starts, so both flicker the same.
+    fn recorder_system(recorder: Res<UiRecorder>, mut seed: ResMut<NeonFlickerSeed>, mut was_idle: Local<bool>) {
+        let idle = matches!(*recorder, UiRecorder::Idle);
+        if *was_idle && !idle { seed.set_changed(); }
+        *was_idle = idle;
+    }
+    /// This system lights the nodes again whose flicker was removed mid dip.
+    fn removed_system(
+        mut commands: Commands,
+        query: Query<(Entity, &NeonFlickerState), Without<NeonFlicker>>,
+        controls: Query<&MainButtonControl>,
+        mut sprites: Query<&mut Sprite>,
+    ) {
+        for (entity, state) in &query {
+            let target = controls.get(entity).map_or(entity, |control| control.image());
+            if let (Some(alpha), Ok(mut sprite)) = (state.lit, sprites.get_mut(target)) { sprite.color.set_alpha(alpha); }
+            commands.entity(entity).remove::<NeonFlickerState>();
+        }
+    }
+}
+
+/// The seed of every [`NeonFlicker`], changing it starts all the patterns over from the new seed
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NeonFlickerSeed(pub u64);
+impl Default for NeonFlickerSeed {
+    fn default() -> Self {
+        Self(0x4e45_4f4e)
+    }
+}
+
+/// The random stream and the burst in progress of a [`NeonFlicker`]
+#[derive(Component)]
+struct NeonFlickerState {
+    rng: StdRng,
+    /// Frames until the next burst
+    wait: u32,
+    /// Dips left in the burst
+    dips: u32,
+    /// Frames left of the current dip or the lit gap after it
+    frames: u32,
+    /// The alpha to go back to while dipped
+    lit: Option<f32>,
+}
+
+
+/// Plugin with the [`NeonFlicker`] bursts
+pub(crate) struct NeonFlickerPlugin;
+impl Plugin for NeonFlickerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NeonFlickerSeed>();
+        app.add_systems(Update, (
+            NeonFlicker::recorder_system.run_if(resource_exists::<UiRecorder>),
+            NeonFlicker::removed_system,
+            NeonFlicker::seed_system,
+            NeonFlicker::system,
+        ).chain());
+    }
+}
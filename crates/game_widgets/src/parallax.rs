@@ -0,0 +1,106 @@
+use bevy::{prelude::*, sprite::Anchor, window::PrimaryWindow};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Insert this on a full-window node of a menu screen to show the images as layers shifting with the cursor.
+/// Each layer moves by its depth factor times how far the cursor is from the window center, deeper layers
+/// with a larger factor move more. The layers are over-scanned so their edges never show. They rest
+/// centered under reduced motion and while there is no cursor, like with a gamepad or touch.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ParallaxBackground {
+    /// The images from back to front, each with its depth factor in range 0.0 to 1.0
+    pub layers: Vec<(Handle<Image>, f32)>,
+    /// The smoothed cursor offset in range -1.0 to 1.0 from the window center
+    offset: Vec2,
+    /// The spawned layer nodes and the image size their layout was fitted to
+    nodes: Vec<(Entity, Option<UVec2>)>,
+}
+impl ParallaxBackground {
+    /// How much larger than the window the layers are, in percent on each side
+    const OVERSCAN: f32 = 5.0;
+    /// The largest shift of a layer as a fraction of its size, it stays within the over-scan
+    const SHIFT: f32 = 0.04;
+    /// How fast the layers catch up with the cursor, higher is snappier
+    const CATCH_UP: f32 = 5.0;
+    /// Creates new instance
+    pub fn new(layers: Vec<(Handle<Image>, f32)>) -> Self {
+        Self { layers, offset: Vec2::ZERO, nodes: Vec::new() }
+    }
+    /// This system spawns the layer nodes of new backgrounds.
+    fn build_system(mut commands: Commands, mut query: Query<(Entity, &mut ParallaxBackground), Added<ParallaxBackground>>) {
+        for (entity, mut background) in &mut query {
+            let mut nodes = Vec::new();
+            commands.entity(entity).with_children(|ui| {
+                for (index, (image, _)) in background.layers.iter().enumerate() {
+                    // The boundary gives the over-scan, the layer is fitted to the image once it loads
+                    ui.spawn((
+                        UiLayout::window().pos(Rl(-ParallaxBackground::OVERSCAN)).size(Rl(100.0 + ParallaxBackground::OVERSCAN * 2.0)).pack(),
+                        UiDepth::Add(index as f32 * 0.1),
+                    )).with_children(|ui| {
+                        nodes.push((ui.spawn((
+                            Name::new("Parallax Layer"),
+                            UiLayout::solid().size((1920.0, 1080.0)).scaling(Scaling::Fill).pack(),
+                            Sprite::from_image(image.clone()),
+                            PickingBehavior::IGNORE,
+                        )).id(), None));
+                    });
+                }
+            });
+            background.nodes = nodes;
+            commands.trigger(RecomputeUiLayout);
+        }
+    }
+    /// This system eases the layers towards the cursor, or the center when it freezes, and fits them to their images.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        mut query: Query<&mut ParallaxBackground>,
+        mut sprites: Query<&mut Sprite>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        reduced: Res<ReducedMotion>,
+        touch: Res<TouchMode>,
+        images: Res<Assets<Image>>,
+        time: Res<Time>,
+    ) {
+        // Measured from the window size every frame, so a resize needs nothing extra
+        let cursor = windows.get_single().ok().and_then(|window| {
+            let half = window.size() / 2.0;
+            window.cursor_position().map(|cursor| ((cursor - half) / half.max(Vec2::ONE)).clamp(Vec2::NEG_ONE, Vec2::ONE))
+        });
+        let target = cursor.filter(|_| motion_scale(&reduced) > 0.0 && !touch.is_active()).unwrap_or(Vec2::ZERO);
+
+        for mut background in &mut query {
+            let background = &mut *background;
+            background.offset = background.offset.lerp(target, 1.0 - (-ParallaxBackground::CATCH_UP * time.delta_secs()).exp());
+            let offset = background.offset;
+
+            for ((node, fitted), (_, depth)) in background.nodes.iter_mut().zip(&background.layers) {
+                let Ok(mut sprite) = sprites.get_mut(*node) else { continue; };
+                if let Some(size) = images.get(&sprite.image).map(|image| image.size()).filter(|size| *fitted != Some(*size)) {
+                    *fitted = Some(size);
+                    commands.entity(*node).insert(UiLayout::solid().size((size.x as f32, size.y as f32)).scaling(Scaling::Fill).pack());
+                }
+
+                // Screen y points down, the layers move away from the cursor
+                let shift = Vec2::new(offset.x, -offset.y) * depth.clamp(0.0, 1.0) * ParallaxBackground::SHIFT;
+                let anchor = Anchor::Custom(shift);
+                if sprite.anchor != anchor { sprite.anchor = anchor; }
+            }
+        }
+    }
+}
+
+
+/// Plugin with the [`ParallaxBackground`] layers
+pub(crate) struct ParallaxPlugin;
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            ParallaxBackground::build_system,
+            ParallaxBackground::system,
+        ).chain().run_if(any_with_component::<ParallaxBackground>));
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+
+/// Keeps the entities of released nodes for reuse, so screens rebuilding many rows do not spawn and
+/// despawn them every time. Released nodes are stripped of every component, so nothing of their
+/// previous life, like a hover tint or an observer, comes back with them.
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct UiNodePool {
+    free: Vec<Entity>,
+}
+impl UiNodePool {
+    /// The number of nodes waiting for reuse
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+    /// If no node is waiting for reuse
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+    /// Spawns empty nodes ahead of time, like before opening a long list
+    pub fn prewarm(&mut self, commands: &mut Commands, count: usize) {
+        self.free.extend((0..count).map(|_| commands.spawn(PooledNode).id()));
+    }
+    /// Returns a node with the bundle inserted, reused if any is free
+    pub fn checkout(&mut self, commands: &mut Commands, bundle: impl Bundle) -> Entity {
+        match self.free.pop() {
+            Some(entity) => {
+                commands.entity(entity).remove::<PooledNode>().insert(bundle);
+                entity
+            },
+            None => commands.spawn(bundle).id(),
+        }
+    }
+    /// Returns the node and all its descendants to the pool, instead of despawning them
+    pub fn release(commands: &mut Commands, entity: Entity) {
+        commands.queue(move |world: &mut World| {
+            let Ok(mut root) = world.get_entity_mut(entity) else { return; };
+            root.remove_parent();
+
+            // Collect the subtree first, detaching a node changes the children of its parent
+            let mut subtree = vec![entity];
+            let mut index = 0;
+            while let Some(node) = subtree.get(index).copied() {
+                if let Some(children) = world.get::<Children>(node) { subtree.extend(children.iter().copied()); }
+                index += 1;
+            }
+            for node in subtree.iter().rev() {
+                let Ok(mut node) = world.get_entity_mut(*node) else { continue; };
+                node.retain::<()>().insert(PooledNode);
+            }
+            world.resource_mut::<UiNodePool>().free.extend(subtree);
+        });
+    }
+}
+
+/// Marks a node waiting in the [`UiNodePool`]
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct PooledNode;
+
+
+/// Plugin with the [`UiNodePool`]
+pub(crate) struct UiNodePoolPlugin;
+impl Plugin for UiNodePoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiNodePool>();
+        app.register_type::<PooledNode>();
+    }
+}
@@ -0,0 +1,277 @@
+use std::path::Path;
+
+use bevy::{
+    app::AppExit,
+    picking::pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput, PressDirection},
+    prelude::*,
+    render::camera::NormalizedRenderTarget,
+    time::TimeUpdateStrategy,
+    window::PrimaryWindow,
+};
+use bevy_lunex::*;
+use game_preferences::platform;
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+
+/// A recorded session of user interface input, saved as RON
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct UiRecording {
+    /// The frames in which the input changed, in order
+    pub frames: Vec<UiRecordedFrame>,
+}
+impl UiRecording {
+    /// Parses the recording from a RON string
+    pub fn parse(source: &str) -> Result<Self, String> {
+        ron::from_str(source).map_err(|error| error.to_string())
+    }
+    /// Serializes the recording into RON
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap_or_default()
+    }
+}
+
+/// The input that changed in one frame of a [`UiRecording`]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct UiRecordedFrame {
+    /// Seconds since the recording started
+    pub time: f32,
+    /// The new position of the cursor in the window, if it moved
+    #[serde(default)]
+    pub cursor: Option<Vec2>,
+    #[serde(default)]
+    pub pressed: Vec<UiRecordedButton>,
+    #[serde(default)]
+    pub released: Vec<UiRecordedButton>,
+}
+
+/// A button pressed or released in a [`UiRecordedFrame`]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UiRecordedButton {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+/// Records the input with F11 and replays recordings, see [`UiRecorderPlugin`]
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub enum UiRecorder {
+    #[default]
+    Idle,
+    /// Capturing the input since the time
+    Recording { start: f32, recording: UiRecording, cursor: Option<Vec2> },
+    /// Feeding the recording back, the frame index is the next one to replay and the cursor is where the pointer was moved
+    Replaying { recording: UiRecording, time: f32, next: usize, cursor: Option<Vec2> },
+}
+impl UiRecorder {
+    /// The key starting and stopping the recording
+    const KEY: KeyCode = KeyCode::F11;
+    /// Starts replaying the recording from the beginning
+    pub fn replay(&mut self, recording: UiRecording) {
+        *self = UiRecorder::Replaying { recording, time: 0.0, next: 0, cursor: None };
+    }
+    /// If a recording is being replayed
+    pub fn is_replaying(&self) -> bool {
+        matches!(self, UiRecorder::Replaying { .. })
+    }
+    /// This system starts and stops the recording with F11 and writes the finished recording to a file.
+    fn toggle_system(mut recorder: ResMut<UiRecorder>, keys: Res<ButtonInput<KeyCode>>, time: Res<Time<Real>>) {
+        if !keys.just_pressed(UiRecorder::KEY) { return; }
+        match &*recorder {
+            UiRecorder::Idle => {
+                info!("Recording the UI input, press F11 again to stop");
+                *recorder = UiRecorder::Recording { start: time.elapsed_secs(), recording: UiRecording::default(), cursor: None };
+            },
+            UiRecorder::Recording { recording, .. } => {
+                let path = format!("ui_session_{}.ron", platform::unix_seconds());
+                match platform::export(&path, &recording.to_ron()) {
+                    Ok(()) => info!("Saved {} recorded frames to '{path}'", recording.frames.len()),
+                    Err(error) => warn!("Failed to save the UI recording to '{path}': {error}"),
+                }
+                *recorder = UiRecorder::Idle;
+            },
+            UiRecorder::Replaying { .. } => {},
+        }
+    }
+    /// This system captures the input that changed this frame.
+    fn record_system(
+        mut recorder: ResMut<UiRecorder>,
+        time: Res<Time<Real>>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        keys: Res<ButtonInput<KeyCode>>,
+        mouse: Res<ButtonInput<MouseButton>>,
+        gamepads: Query<&Gamepad>,
+    ) {
+        let UiRecorder::Recording { start, recording, cursor } = &mut *recorder else { return; };
+        let position = windows.get_single().ok().and_then(|window| window.cursor_position());
+        let mut frame = UiRecordedFrame { time: time.elapsed_secs() - *start, cursor: position.filter(|position| Some(*position) != *cursor), ..default() };
+        if frame.cursor.is_some() { *cursor = position; }
+
+        // The toggle key itself is left out, the replay would stop its own recording
+        frame.pressed.extend(keys.get_just_pressed().filter(|key| **key != UiRecorder::KEY).map(|key| UiRecordedButton::Key(*key)));
+        frame.released.extend(keys.get_just_released().filter(|key| **key != UiRecorder::KEY).map(|key| UiRecordedButton::Key(*key)));
+        frame.pressed.extend(mouse.get_just_pressed().map(|button| UiRecordedButton::Mouse(*button)));
+        frame.released.extend(mouse.get_just_released().map(|button| UiRecordedButton::Mouse(*button)));
+        for gamepad in &gamepads {
+            frame.pressed.extend(gamepad.get_just_pressed().map(|button| UiRecordedButton::Gamepad(*button)));
+            frame.released.extend(gamepad.get_just_released().map(|button| UiRecordedButton::Gamepad(*button)));
+        }
+
+        if frame.cursor.is_some() || !frame.pressed.is_empty() || !frame.released.is_empty() { recording.frames.push(frame); }
+    }
+    /// This system replaces the physical input with the recorded one. Every replayed frame advances the time
+    /// by exactly one fixed timestep, so the replay runs the same on every machine. The pointer is moved
+    /// in the render target of the UI camera, so replays also run headless without a window.
+    #[allow(clippy::too_many_arguments)]
+    fn replay_system(
+        mut commands: Commands,
+        mut recorder: ResMut<UiRecorder>,
+        fixed: Res<Time<Fixed>>,
+        mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+        cameras: Query<&Camera, With<UiSourceCamera<0>>>,
+        mut keys: ResMut<ButtonInput<KeyCode>>,
+        mut mouse: ResMut<ButtonInput<MouseButton>>,
+        mut gamepads: Query<&mut Gamepad, With<ReplayedGamepad>>,
+        mut pointer: EventWriter<PointerInput>,
+        mut finished: EventWriter<UiReplayFinished>,
+    ) {
+        let UiRecorder::Replaying { recording, time, next, cursor } = &mut *recorder else { return; };
+        // Clicks come only from the recording, the keys of the recording are pressed on top
+        mouse.reset_all();
+        let Ok(mut gamepad) = gamepads.get_single_mut() else {
+            commands.spawn((ReplayedGamepad, Gamepad::default()));
+            return;
+        };
+        gamepad.digital_mut().clear();
+        let mut window = windows.get_single_mut().ok();
+        let target: Option<NormalizedRenderTarget> = cameras.iter().find_map(|camera| camera.target.normalize(window.as_ref().map(|(entity, _)| *entity)));
+
+        while let Some(frame) = recording.frames.get(*next).filter(|frame| frame.time <= *time) {
+            if let Some(position) = frame.cursor {
+                // Picking moves the pointer like for the virtual cursor, the OS cursor follows only where it can be moved
+                let delta = cursor.map_or(Vec2::ZERO, |last| position - last);
+                *cursor = Some(position);
+                if let Some(target) = target.clone() { pointer.send(PointerInput::new(PointerId::Mouse, Location { target, position }, PointerAction::Moved { delta })); }
+                if let Some((_, window)) = window.as_mut().filter(|_| platform::CAN_MOVE_CURSOR) { window.set_cursor_position(Some(position)); }
+            }
+            for (buttons, direction) in [(&frame.pressed, PressDirection::Down), (&frame.released, PressDirection::Up)] {
+                for button in buttons {
+                    match (*button, direction) {
+                        (UiRecordedButton::Key(key), PressDirection::Down) => keys.press(key),
+                        (UiRecordedButton::Key(key), PressDirection::Up) => keys.release(key),
+                        (UiRecordedButton::Mouse(button), _) => {
+                            // Picking gets the click from the pointer, like from the virtual cursor
+                            let (Some(target), Some(position)) = (target.clone(), *cursor) else { continue; };
+                            let Some(button) = PointerButton::from_mouse_button(button) else { continue; };
+                            pointer.send(PointerInput::new(PointerId::Mouse, Location { target, position }, PointerAction::Pressed { direction, button }));
+                        },
+                        (UiRecordedButton::Gamepad(button), PressDirection::Down) => gamepad.digital_mut().press(button),
+                        (UiRecordedButton::Gamepad(button), PressDirection::Up) => gamepad.digital_mut().release(button),
+                    }
+                }
+            }
+            *next += 1;
+        }
+
+        *time += fixed.timestep().as_secs_f32();
+        if *next >= recording.frames.len() {
+            info!("Finished replaying {} frames", recording.frames.len());
+            *recorder = UiRecorder::Idle;
+            finished.send(UiReplayFinished);
+        }
+    }
+    /// This system steps the time by the fixed timestep while replaying and goes back to real time after.
+    fn time_system(
+        mut commands: Commands,
+        recorder: Res<UiRecorder>,
+        fixed: Res<Time<Fixed>>,
+        gamepads: Query<Entity, With<ReplayedGamepad>>,
+        mut replaying: Local<bool>,
+    ) {
+        if recorder.is_replaying() == *replaying { return; }
+        *replaying = recorder.is_replaying();
+        if *replaying {
+            commands.insert_resource(TimeUpdateStrategy::ManualDuration(fixed.timestep()));
+        } else {
+            commands.insert_resource(TimeUpdateStrategy::Automatic);
+            for entity in &gamepads { commands.entity(entity).despawn(); }
+        }
+    }
+    /// This system exits the app once the replay finished, for replays in CI.
+    fn exit_system(mut exit: EventWriter<AppExit>) {
+        exit.send(AppExit::Success);
+    }
+}
+
+/// Sent when a replay reached the end of its recording
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UiReplayFinished;
+
+/// The gamepad the replay presses the recorded gamepad buttons on
+#[derive(Component)]
+struct ReplayedGamepad;
+
+
+/// Plugin recording the user interface input with F11 and replaying recordings.
+/// Add it before the widgets plugin, replays need the [`UiAnimationTiming::Fixed`] animations to look the same as recorded.
+#[derive(Default)]
+pub struct UiRecorderPlugin {
+    /// The recording file to replay on startup
+    pub replay: Option<String>,
+    /// If the app exits once the replay finished
+    pub exit_after_replay: bool,
+}
+impl Plugin for UiRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        // The widgets plugin inserts the timing it chose, then it is too late to change it
+        match app.world().get_resource::<UiAnimationTiming>() {
+            Some(UiAnimationTiming::Variable) => warn!("UiRecorderPlugin was added after the widgets plugin, replays will not match the recorded animations"),
+            _ => { app.insert_resource(UiAnimationTiming::Fixed); },
+        }
+        app.add_event::<UiReplayFinished>();
+
+        let mut recorder = UiRecorder::Idle;
+        if let Some(path) = &self.replay {
+            match platform::read_file(Path::new(path)).ok_or("the file could not be read".to_string()).and_then(|source| UiRecording::parse(&source)) {
+                Ok(recording) => recorder.replay(recording),
+                Err(error) => warn!("Failed to load the UI recording '{path}': {error}"),
+            }
+        }
+        app.insert_resource(recorder);
+
+        app.add_systems(PreUpdate, (
+            UiRecorder::toggle_system,
+            UiRecorder::record_system,
+            UiRecorder::replay_system,
+        ).chain().after(bevy::input::InputSystem).before(bevy::picking::PickSet::Input));
+        app.add_systems(Last, UiRecorder::time_system);
+        if self.exit_after_replay { app.add_systems(Last, UiRecorder::exit_system.run_if(on_event::<UiReplayFinished>)); }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::{picking::pointer::{PointerId, PointerLocation}, prelude::*, render::camera::{ManualTextureViewHandle, RenderTarget}};
+    use bevy_lunex::UiSourceCamera;
+
+    use crate::*;
+
+    #[test]
+    fn replay_moves_the_pointer_without_a_window() {
+        let mut test = UiTestApp::new();
+        test.app.add_plugins(UiRecorderPlugin::default());
+        test.world_mut().spawn((Camera { target: RenderTarget::TextureView(ManualTextureViewHandle(0)), ..default() }, UiSourceCamera::<0>));
+        test.world_mut().resource_mut::<UiRecorder>().replay(UiRecording { frames: vec![
+            UiRecordedFrame { time: 0.0, cursor: Some(Vec2::new(320.0, 180.0)), ..default() },
+            UiRecordedFrame { time: 0.1, cursor: Some(Vec2::new(640.0, 360.0)), ..default() },
+        ]});
+
+        test.advance(0.5);
+        assert!(!test.world().resource::<UiRecorder>().is_replaying(), "The replay did not finish without a window");
+        let mut pointers = test.world_mut().query::<(&PointerId, &PointerLocation)>();
+        let location = pointers.iter(test.world()).find(|(id, _)| **id == PointerId::Mouse).and_then(|(_, pointer)| pointer.location.clone());
+        assert_eq!(location.map(|location| location.position), Some(Vec2::new(640.0, 360.0)), "The replay did not move the pointer");
+    }
+}
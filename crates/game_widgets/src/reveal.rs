@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, transform::TransformSystem, window::PrimaryWindow};
+use bevy_lunex::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// How one entry of the [`RevealSequence`] comes in
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RevealAnimation {
+    pub kind: EntranceKind,
+    /// Seconds the reveal takes
+    pub duration: f32,
+    pub easing: Easing,
+}
+impl RevealAnimation {
+    /// Creates new instance
+    pub fn new(kind: EntranceKind) -> Self {
+        Self { kind, duration: 0.3, easing: Easing::CubicOut }
+    }
+    /// Replace the default duration with a new one.
+    pub fn duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+    /// Replace the default easing with a new one.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/// Reveals widgets one after another across screens, like the HUD fading in piece by piece after gameplay starts.
+/// Unlike the [`EntranceAnimation`] of one container, any entities can be queued from any system. Each entry
+/// waits its delay after the previous one started and stays hidden and [`UiInert`] until its reveal is done.
+/// Entries despawned before their turn are skipped, [`RevealSequenceFinished`] is sent once all are shown.
+#[derive(Resource, Default)]
+pub struct RevealSequence {
+    entries: VecDeque<RevealEntry>,
+    skip: bool,
+}
+impl RevealSequence {
+    /// The size a [`EntranceKind::Scale`] entry starts at
+    const SCALE: f32 = 0.6;
+    /// Queues the entity to be revealed the delay in seconds after the previous entry started
+    pub fn enqueue(&mut self, entity: Entity, delay: f32, animation: RevealAnimation) {
+        let tween = Tween::new(0.0, 1.0, animation.duration).easing(animation.easing);
+        self.entries.push_back(RevealEntry { node: AnimatedNode::new(entity, tween), animation, wait: delay.max(0.0), started: false, armed: false });
+    }
+    /// Shows every queued entry at once in the next frame
+    pub fn skip_all(&mut self) {
+        self.skip = !self.entries.is_empty();
+    }
+    /// If no entry is waiting or playing
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// This system counts down the next entry, plays the started ones and holds the waiting ones hidden.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut commands: Commands,
+        mut sequence: ResMut<RevealSequence>,
+        entities: Query<()>,
+        descendants: Query<&Children>,
+        mut transforms: Query<&mut Transform>,
+        mut sprites: Query<&mut Sprite>,
+        mut texts: Query<&mut TextColor>,
+        windows: Query<&Window, With<PrimaryWindow>>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+        mut finished: EventWriter<RevealSequenceFinished>,
+    ) {
+        if sequence.entries.is_empty() { return; }
+        let width = windows.get_single().map_or(1920.0, |window| window.width());
+        let sequence = &mut *sequence;
+        let skip = std::mem::take(&mut sequence.skip);
+
+        // Despawned entries leave silently, their turn goes to the next one
+        sequence.entries.retain(|entry| entities.contains(entry.node.entity));
+
+        let mut delta = time.delta_secs();
+        let mut counting = true;
+        for entry in sequence.entries.iter_mut() {
+            if !entry.armed {
+                commands.entity(entry.node.entity).try_insert(UiInert);
+                entry.armed = true;
+            }
+
+            // Only the first waiting entry counts down, the time it did not need carries over to the next
+            let progress = if skip {
+                entry.node.tween.tick(f32::INFINITY)
+            } else if entry.started {
+                entry.node.tween.tick(time.delta_secs())
+            } else if counting && delta >= entry.wait {
+                delta -= entry.wait;
+                entry.started = true;
+                entry.node.tween.duration = reduced.duration(entry.node.tween.duration);
+                entry.node.tween.tick(delta)
+            } else {
+                if counting { entry.wait -= delta; }
+                counting = false;
+                0.0
+            };
+
+            // Under reduced motion every reveal is a fade
+            let kind = if motion_scale(&reduced) == 0.0 { EntranceKind::Fade } else { entry.animation.kind };
+            let (slide, alpha, scale) = match kind {
+                EntranceKind::SlideLeft => (-width * (1.0 - progress), 1.0, 1.0),
+                EntranceKind::Fade => (0.0, progress, 1.0),
+                EntranceKind::Scale => (0.0, progress, RevealSequence::SCALE.interpolate(&1.0, progress)),
+            };
+            entry.node.apply(slide, alpha, scale, &descendants, &mut transforms, &mut sprites, &mut texts);
+            if entry.node.tween.is_done() { commands.entity(entry.node.entity).remove::<UiInert>(); }
+        }
+
+        sequence.entries.retain(|entry| !entry.node.tween.is_done());
+        if sequence.entries.is_empty() { finished.send(RevealSequenceFinished); }
+    }
+}
+
+/// Sent when the last entry of the [`RevealSequence`] was revealed or skipped
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RevealSequenceFinished;
+
+/// One queued entity of the [`RevealSequence`]
+struct RevealEntry {
+    node: AnimatedNode,
+    animation: RevealAnimation,
+    /// Seconds left after the previous entry started
+    wait: f32,
+    started: bool,
+    /// If the entity was made inert
+    armed: bool,
+}
+
+
+/// Plugin playing the [`RevealSequence`]
+pub(crate) struct RevealSequencePlugin;
+impl Plugin for RevealSequencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RevealSequence>();
+        app.add_event::<RevealSequenceFinished>();
+        // After the layout wrote the transforms, so the waiting entries never flash at their place
+        app.add_systems(PostUpdate, RevealSequence::system.after(UiSystems::Compute).before(TransformSystem::TransformPropagate));
+    }
+}
@@ -0,0 +1,276 @@
+use bevy::{color::palettes::css, prelude::*, text::Update2dText};
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// The color of a [`RichSpan`], theme roles follow the theme
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RichColor {
+    Role(ThemeRole),
+    Fixed(Color),
+}
+impl RichColor {
+    /// Parses a theme role like `danger`, a basic color name like `yellow` or a hex code like `#ffcc00`
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(role) = ThemeRole::from_name(value) { return Some(RichColor::Role(role)); }
+        let named = match value.to_ascii_lowercase().as_str() {
+            "white" => css::WHITE,
+            "black" => css::BLACK,
+            "gray" | "grey" => css::GRAY,
+            "red" => css::RED,
+            "orange" => css::ORANGE,
+            "yellow" => css::YELLOW,
+            "green" => css::LIME,
+            "cyan" => css::AQUA,
+            "blue" => css::BLUE,
+            "magenta" => css::FUCHSIA,
+            _ => return Srgba::hex(value).ok().map(|color| RichColor::Fixed(color.into())),
+        };
+        Some(RichColor::Fixed(named.into()))
+    }
+    /// The color in the theme, with the alpha of the text it is in
+    fn resolve(&self, theme: &UiTheme, text: Color) -> Color {
+        let color = match self {
+            RichColor::Role(role) => theme.get(*role),
+            RichColor::Fixed(color) => *color,
+        };
+        color.with_alpha(color.alpha() * text.alpha())
+    }
+}
+
+/// One run of the [`RichText`] markup with the same style
+#[derive(Clone, PartialEq, Debug)]
+pub struct RichSpan {
+    pub text: String,
+    pub font: TextFont,
+    /// The color of the run, none keeps the color of the text
+    pub color: Option<RichColor>,
+}
+
+/// Insert this on a text node to show its [`Text2d`] as markup, like `Press <b>E</b> to <color=warning>interact</color>`.
+/// Supported are `<b>` for the bold font, `<color=...>` with a theme role, a basic color name or a hex code and
+/// `<size=150%>` relative to the font size. Unknown, broken and unbalanced tags are shown as they are.
+/// The runs are spawned as [`TextSpan`] children following the font and the color of the text, localized
+/// texts are parsed once their key is resolved.
+#[derive(Component, Clone, PartialEq, Debug)]
+#[require(Text2d)]
+pub struct RichText {
+    /// The font of the bold runs
+    pub bold: Handle<Font>,
+}
+impl RichText {
+    /// Creates new instance with the bold font of the typography
+    pub fn new(typography: &Typography) -> Self {
+        Self { bold: typography.bold.clone() }
+    }
+    /// Converts the markup into the runs of text in the fonts of the typography
+    pub fn parse(markup: &str, typography: &Typography) -> Vec<RichSpan> {
+        let base = typography.text_font();
+        RichText::runs(markup).into_iter().map(|(text, style)| RichSpan { text, font: style.font(&base, &typography.bold), color: style.color }).collect()
+    }
+    /// Splits the markup into runs of text with the same style
+    fn runs(markup: &str) -> Vec<(String, RichStyle)> {
+        let tokens = RichToken::tokenize(markup);
+
+        // Tags without their pair are literal, like the ones crossing another pair
+        let mut literal = vec![false; tokens.len()];
+        let mut open: Vec<usize> = Vec::new();
+        for (index, token) in tokens.iter().enumerate() {
+            match token {
+                RichToken::Open(..) => open.push(index),
+                RichToken::Close(name, _) => match open.iter().rposition(|opened| tokens[*opened].name() == Some(*name)) {
+                    Some(at) => for crossed in open.drain(at..).skip(1) { literal[crossed] = true; },
+                    None => literal[index] = true,
+                },
+                RichToken::Text(_) => {},
+            }
+        }
+        for unclosed in open { literal[unclosed] = true; }
+
+        let mut runs: Vec<(String, RichStyle)> = Vec::new();
+        let mut stack: Vec<RichTag> = Vec::new();
+        for (index, token) in tokens.iter().enumerate() {
+            let text = match token {
+                RichToken::Open(tag, _) if !literal[index] => { stack.push(*tag); continue; },
+                RichToken::Close(..) if !literal[index] => { stack.pop(); continue; },
+                RichToken::Text(text) | RichToken::Open(_, text) | RichToken::Close(_, text) => *text,
+            };
+            let style = RichStyle::from_tags(&stack);
+            match runs.last_mut() {
+                Some((run, last)) if *last == style => run.push_str(text),
+                _ => runs.push((text.to_string(), style)),
+            }
+        }
+        runs
+    }
+    /// This system moves the markup of changed texts into span children, the text itself is left empty.
+    #[allow(clippy::type_complexity)]
+    fn build_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &RichText, &mut Text2d, &TextFont, &TextColor, Option<&RichTextSpans>), Or<(Changed<Text2d>, Changed<RichText>)>>,
+        resolver: ThemeResolver,
+    ) {
+        for (entity, rich, mut text, font, color, spans) in &mut query {
+            // Emptying the text is not a new markup
+            let markup = if text.is_changed() { std::mem::take(&mut text.bypass_change_detection().0) } else { spans.map_or(String::new(), |spans| spans.markup.clone()) };
+            if let Some(spans) = spans {
+                for (span, _) in &spans.spans { commands.entity(*span).despawn_recursive(); }
+            }
+
+            let theme = resolver.theme_for(entity);
+            let mut spawned = Vec::new();
+            commands.entity(entity).with_children(|ui| {
+                for (run, style) in RichText::runs(&markup) {
+                    let run_color = style.color.map_or(color.0, |tint| tint.resolve(&theme, color.0));
+                    spawned.push((ui.spawn((TextSpan::new(run), style.font(font, &rich.bold), TextColor(run_color))).id(), style));
+                }
+            });
+            commands.entity(entity).insert(RichTextSpans { markup, spans: spawned });
+        }
+    }
+    /// This system keeps the spans in the font, the size and the color of their text, like during a hover or a theme switch.
+    #[allow(clippy::type_complexity)]
+    fn system(
+        query: Query<(Entity, &RichText, Ref<TextFont>, Ref<TextColor>, &RichTextSpans)>,
+        mut spans: Query<(&mut TextFont, &mut TextColor), (With<TextSpan>, Without<RichTextSpans>)>,
+        resolver: ThemeResolver,
+    ) {
+        for (entity, rich, font, color, rig) in &query {
+            if !font.is_changed() && !color.is_changed() && !resolver.is_changed() { continue; }
+            let theme = resolver.theme_for(entity);
+            for (span, style) in &rig.spans {
+                let Ok((mut span_font, mut span_color)) = spans.get_mut(*span) else { continue; };
+                if font.is_changed() { *span_font = style.font(&font, &rich.bold); }
+                let target = style.color.map_or(color.0, |tint| tint.resolve(&theme, color.0));
+                if span_color.0 != target { span_color.0 = target; }
+            }
+        }
+    }
+    /// This system puts the markup back into the text once the component was removed.
+    fn cleanup_system(mut commands: Commands, mut query: Query<(Entity, &RichTextSpans, &mut Text2d), Without<RichText>>) {
+        for (entity, spans, mut text) in &mut query {
+            for (span, _) in &spans.spans { commands.entity(*span).despawn_recursive(); }
+            text.0.clone_from(&spans.markup);
+            commands.entity(entity).remove::<RichTextSpans>();
+        }
+    }
+}
+
+/// The markup of a [`RichText`] and its span children
+#[derive(Component)]
+struct RichTextSpans {
+    markup: String,
+    spans: Vec<(Entity, RichStyle)>,
+}
+
+/// The style of one run of [`RichText`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct RichStyle {
+    bold: bool,
+    color: Option<RichColor>,
+    /// The font size relative to the text
+    scale: f32,
+}
+impl RichStyle {
+    /// The style inside the open tags, the innermost color and size win
+    fn from_tags(tags: &[RichTag]) -> Self {
+        tags.iter().fold(Self { bold: false, color: None, scale: 1.0 }, |style, tag| match tag {
+            RichTag::Bold => Self { bold: true, ..style },
+            RichTag::Color(color) => Self { color: Some(*color), ..style },
+            RichTag::Size(scale) => Self { scale: *scale, ..style },
+        })
+    }
+    /// The font of the run in the text with the font
+    fn font(&self, base: &TextFont, bold: &Handle<Font>) -> TextFont {
+        TextFont {
+            font: if self.bold { bold.clone() } else { base.font.clone() },
+            font_size: base.font_size * self.scale,
+            ..base.clone()
+        }
+    }
+}
+
+/// A tag of the [`RichText`] markup
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RichTag {
+    Bold,
+    Color(RichColor),
+    /// The font size relative to the text
+    Size(f32),
+}
+impl RichTag {
+    /// The name closing the tag
+    fn name(&self) -> &'static str {
+        match self {
+            RichTag::Bold => "b",
+            RichTag::Color(_) => "color",
+            RichTag::Size(_) => "size",
+        }
+    }
+    /// Parses the inside of an opening tag, like `color=danger`
+    fn parse(inner: &str) -> Option<Self> {
+        let (name, value) = inner.split_once('=').map_or((inner, None), |(name, value)| (name, Some(value)));
+        match (name, value) {
+            ("b", None) => Some(RichTag::Bold),
+            ("color", Some(value)) => RichColor::parse(value).map(RichTag::Color),
+            ("size", Some(value)) => value.strip_suffix('%').unwrap_or(value).parse::<f32>().ok()
+                .filter(|percent| percent.is_finite() && *percent > 0.0)
+                .map(|percent| RichTag::Size(percent / 100.0)),
+            _ => None,
+        }
+    }
+}
+
+/// A piece of the [`RichText`] markup, tags keep their source in case they are shown literally
+enum RichToken<'a> {
+    Text(&'a str),
+    Open(RichTag, &'a str),
+    Close(&'a str, &'a str),
+}
+impl<'a> RichToken<'a> {
+    /// The name of the tag, if the token is an opening one
+    fn name(&self) -> Option<&'static str> {
+        if let RichToken::Open(tag, _) = self { Some(tag.name()) } else { None }
+    }
+    /// Splits the markup into text and tags, anything in angle brackets that is not a known tag stays text
+    fn tokenize(markup: &'a str) -> Vec<RichToken<'a>> {
+        let mut tokens = Vec::new();
+        let mut text = 0;
+        let mut search = 0;
+        while let Some(start) = markup[search..].find('<').map(|at| search + at) {
+            let Some(end) = markup[start..].find('>').map(|at| start + at) else { break; };
+            let source = &markup[start..=end];
+            let inner = &source[1..source.len() - 1];
+            let token = match inner.strip_prefix('/') {
+                Some(name) if ["b", "color", "size"].contains(&name) => Some(RichToken::Close(name, source)),
+                Some(_) => None,
+                None => RichTag::parse(inner).map(|tag| RichToken::Open(tag, source)),
+            };
+            // A bracket that opens no tag is text, the next one may
+            let Some(token) = token else { search = start + 1; continue; };
+            if text < start { tokens.push(RichToken::Text(&markup[text..start])); }
+            tokens.push(token);
+            text = end + 1;
+            search = end + 1;
+        }
+        if text < markup.len() { tokens.push(RichToken::Text(&markup[text..])); }
+        tokens
+    }
+}
+
+
+/// Plugin showing the [`RichText`] markup
+pub(crate) struct RichTextPlugin;
+impl Plugin for RichTextPlugin {
+    fn build(&self, app: &mut App) {
+        // After the keys are resolved, so localized markup is parsed too, and before the text is laid out
+        app.add_systems(PostUpdate, (
+            RichText::cleanup_system,
+            RichText::build_system,
+            RichText::system,
+        ).chain().after(LocalizationSystems).before(Update2dText).before(UiSystems::Compute));
+    }
+}
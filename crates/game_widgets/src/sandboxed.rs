@@ -0,0 +1,294 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::{EntityCommands, ReadOnlySystemParam, StaticSystemParam, SystemParam, SystemParamItem}, picking::{backend::HitData, pointer::{Location, PointerId}}, prelude::*, render::view::VisibilitySystems, transform::TransformSystem};
+use game_loading::*;
+
+use crate::*;
+
+
+/// A widget spawned on an entity with [`UiLayout`](bevy_lunex::UiLayout) that builds its own internals.
+/// Register it with [`SandboxedUiAppExt::register_sandboxed_ui`] to get the teardown for free.
+pub trait SandboxedUi: Component {
+    /// The component the built widget keeps its internals in
+    type Control: Component;
+    /// The root entities of the internals, despawned when the widget is removed
+    fn internals(control: &Self::Control) -> Vec<Entity>;
+    /// Removes the components the build inserted on the widget entity itself
+    fn teardown(entity: &mut EntityCommands) {
+        let _ = entity;
+    }
+}
+
+/// A [`SandboxedUi`] widget with the usual lifecycle, register it with [`SandboxWidgetAppExt::register_widget`]
+/// and the build system, the hover observers and the update system are generated for it.
+pub trait SandboxWidget: SandboxedUi {
+    /// What the build reads or writes besides the widget, like resources or the optional components of the widget
+    type BuildParam: SystemParam + 'static;
+    /// What the update reads besides the widget and its control, the widgets are updated in parallel
+    type UpdateParam: ReadOnlySystemParam + 'static;
+    /// Spawns the internals once the assets are loaded or failed and returns the control keeping them
+    fn build(&self, ui: &mut WidgetBuilder, param: &mut SystemParamItem<Self::BuildParam>, assets: &AssetCache) -> Self::Control;
+    /// Attaches the observers of the widget besides the hover ones, once, they stay when the internals are rebuilt
+    fn observers(widget: &mut EntityCommands) {
+        let _ = widget;
+    }
+    /// Called when the pointer enters or leaves the widget, entering is skipped under a blocking [`UiLayer`]
+    fn on_hover(&self, control: &mut Self::Control, hover: &WidgetHover) {
+        let _ = (control, hover);
+    }
+    /// Called every update after the build, in `FixedUpdate` under the fixed [`UiAnimationTiming`]
+    fn update(&self, widget: &WidgetUpdate, control: &mut Self::Control, param: &SystemParamItem<Self::UpdateParam>) {
+        let _ = (widget, control, param);
+    }
+}
+
+/// Spawns the internals of a [`SandboxWidget`] under its entity
+pub struct WidgetBuilder<'a, 'w, 's> {
+    commands: &'a mut Commands<'w, 's>,
+    entity: Entity,
+}
+impl WidgetBuilder<'_, '_, '_> {
+    /// The widget entity
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+    /// Inserts the bundle on the widget entity itself, remove it again in [`SandboxedUi::teardown`]
+    pub fn insert(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.commands.entity(self.entity).insert(bundle);
+        self
+    }
+    /// Spawns a child of the widget entity, or of an internal when a parent is given
+    pub fn spawn(&mut self, parent: Option<Entity>, bundle: impl Bundle) -> EntityCommands {
+        let mut child = self.commands.spawn(bundle);
+        child.set_parent(parent.unwrap_or(self.entity));
+        child
+    }
+}
+
+/// The pointer entering or leaving a [`SandboxWidget`]
+#[derive(Clone, Debug)]
+pub struct WidgetHover {
+    /// If the pointer entered, false when it left
+    pub hovered: bool,
+    pub pointer: PointerId,
+    pub location: Location,
+    /// What the pointer hit on the widget
+    pub hit: HitData,
+}
+
+/// The widget a [`SandboxWidget::update`] is called for
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WidgetUpdate {
+    /// The widget entity
+    pub entity: Entity,
+    /// If the widget component changed since the last update
+    pub changed: bool,
+    /// The delta of the update schedule in seconds
+    pub delta: f32,
+}
+
+/// Marks a [`SandboxWidget`] whose observers are attached, rebuilding it does not attach them again
+#[derive(Component)]
+struct SandboxWidgetObserved<T: SandboxWidget>(PhantomData<T>);
+
+/// Spawn the internals of a widget with this and [`Visibility::Hidden`], they are shown once the
+/// layout placed them, so the first frame does not show them at the wrong size or position.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct UiReveal;
+impl UiReveal {
+    /// This system reveals the internals after the first layout and transform pass, before the frame is drawn.
+    fn system(mut commands: Commands, mut query: Query<(Entity, &mut Visibility), With<UiReveal>>) {
+        for (entity, mut visibility) in &mut query {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<UiReveal>();
+        }
+    }
+}
+
+/// Systems building the sandboxed widgets, run after the cleanup of the removed ones
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SandboxedUiSystems;
+
+/// Systems updating the [`SandboxWidget`] widgets, in `FixedUpdate` under the fixed [`UiAnimationTiming`]
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SandboxWidgetSystems;
+
+/// Registers [`SandboxedUi`] widgets
+pub trait SandboxedUiAppExt {
+    /// Installs the build system of the widget and the cleanup once it is removed or despawned.
+    /// The cleanup despawns the internals, removes the control and releases the focus.
+    fn register_sandboxed_ui<T: SandboxedUi, M>(&mut self, build: impl IntoSystemConfigs<M>) -> &mut Self;
+}
+impl SandboxedUiAppExt for App {
+    fn register_sandboxed_ui<T: SandboxedUi, M>(&mut self, build: impl IntoSystemConfigs<M>) -> &mut Self {
+        self.add_systems(Update, (
+            sandboxed_cleanup_system::<T>,
+            build.in_set(SandboxedUiSystems),
+        ).chain())
+    }
+}
+
+/// Registers [`SandboxWidget`] widgets
+pub trait SandboxWidgetAppExt {
+    /// Registers the widget as [`SandboxedUi`] with the generated build system, the hover observers and the update system.
+    /// The update follows the [`UiAnimationTiming`], so insert it before registering.
+    fn register_widget<T: SandboxWidget>(&mut self) -> &mut Self where for<'w, 's> SystemParamItem<'w, 's, T::UpdateParam>: Sync;
+}
+impl SandboxWidgetAppExt for App {
+    fn register_widget<T: SandboxWidget>(&mut self) -> &mut Self where for<'w, 's> SystemParamItem<'w, 's, T::UpdateParam>: Sync {
+        // Widgets spawned while loading are built once the assets arrive, failed ones use placeholders
+        self.register_sandboxed_ui::<T, _>(widget_build_system::<T>.run_if(any_with_component::<T>.and(ui_assets_ready.or(ui_assets_failed))));
+        // One pair of hover observers serves every widget of the type, the widgets do not get their own
+        self.add_observer(widget_over_observer::<T>);
+        self.add_observer(widget_out_observer::<T>);
+        let update = widget_update_system::<T>.in_set(SandboxWidgetSystems).run_if(any_with_component::<T::Control>);
+        match self.world().get_resource::<UiAnimationTiming>().copied().unwrap_or_default() {
+            UiAnimationTiming::Variable => self.add_systems(Update, update.after(SandboxedUiSystems)),
+            UiAnimationTiming::Fixed => self.add_systems(FixedUpdate, update),
+        }
+    }
+}
+
+/// This system builds the internals of the [`SandboxWidget`] widgets that are not built yet.
+fn widget_build_system<T: SandboxWidget>(
+    mut commands: Commands,
+    query: Query<(Entity, &T, Has<SandboxWidgetObserved<T>>), Without<T::Control>>,
+    assets: Option<Res<AssetCache>>,
+    param: StaticSystemParam<T::BuildParam>,
+    mut warned: Local<bool>,
+) {
+    // Unbuilt widgets stay in the query, so they are built once the cache appears
+    let Some(assets) = assets else {
+        if !*warned && !query.is_empty() {
+            let name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+            warn!("{name} can not be built without the AssetCache resource, it is built once the resource is inserted");
+            *warned = true;
+        }
+        return;
+    };
+    let mut param = param.into_inner();
+    for (entity, widget, observed) in &query {
+        let control = widget.build(&mut WidgetBuilder { commands: &mut commands, entity }, &mut param, &assets);
+        let mut widget = commands.entity(entity);
+        widget.insert(control);
+        // The observers stay when the internals are rebuilt, so nothing is reported twice
+        if !observed {
+            widget.insert(SandboxWidgetObserved::<T>(PhantomData));
+            T::observers(&mut widget);
+        }
+    }
+}
+
+/// This observer forwards the enter of the pointer to the [`SandboxWidget`], unless it is covered by a blocking layer.
+fn widget_over_observer<T: SandboxWidget>(trigger: Trigger<Pointer<Over>>, mut query: Query<(&T, &mut T::Control)>, layers: UiLayers) {
+    if layers.is_blocked(trigger.entity()) { return; }
+    let Ok((widget, mut control)) = query.get_mut(trigger.entity()) else { return; };
+    widget.on_hover(&mut control, &WidgetHover { hovered: true, pointer: trigger.pointer_id, location: trigger.pointer_location.clone(), hit: trigger.hit.clone() });
+}
+
+/// This observer forwards the leave of the pointer to the [`SandboxWidget`], a blocking layer opening keeps no widget hovered.
+fn widget_out_observer<T: SandboxWidget>(trigger: Trigger<Pointer<Out>>, mut query: Query<(&T, &mut T::Control)>) {
+    let Ok((widget, mut control)) = query.get_mut(trigger.entity()) else { return; };
+    widget.on_hover(&mut control, &WidgetHover { hovered: false, pointer: trigger.pointer_id, location: trigger.pointer_location.clone(), hit: trigger.hit.clone() });
+}
+
+/// This system updates the built [`SandboxWidget`] widgets in parallel.
+fn widget_update_system<T: SandboxWidget>(time: Res<Time>, param: StaticSystemParam<T::UpdateParam>, mut query: Query<(Entity, Ref<T>, &mut T::Control)>)
+where for<'w, 's> SystemParamItem<'w, 's, T::UpdateParam>: Sync {
+    let delta = time.delta_secs();
+    let param = &*param;
+    query.par_iter_mut().for_each(|(entity, widget, mut control)| {
+        widget.update(&WidgetUpdate { entity, changed: widget.is_changed(), delta }, &mut control, param);
+    });
+}
+
+/// This system tears down the widgets whose component was removed, despawned widgets take their internals with them.
+fn sandboxed_cleanup_system<T: SandboxedUi>(
+    mut commands: Commands,
+    mut removed: RemovedComponents<T>,
+    controls: Query<&T::Control, Without<T>>,
+) {
+    for entity in removed.read() {
+        let Ok(control) = controls.get(entity) else { continue; };
+        for internal in T::internals(control) {
+            if let Some(internal) = commands.get_entity(internal) { internal.despawn_recursive(); }
+        }
+        // Removing the focusable makes the focus move on to the next widget
+        let mut widget = commands.entity(entity);
+        widget.remove::<(T::Control, Focusable)>();
+        T::teardown(&mut widget);
+    }
+}
+
+
+/// Plugin revealing the [`UiReveal`] internals
+pub(crate) struct SandboxedUiPlugin;
+impl Plugin for SandboxedUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<UiReveal>();
+        app.add_systems(PostUpdate, UiReveal::system.after(TransformSystem::TransformPropagate).before(VisibilitySystems::VisibilityPropagate).run_if(any_with_component::<UiReveal>));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+    use bevy_lunex::{Dimension, Rl, UiLayout, UiLayoutRoot};
+    use game_loading::AssetCache;
+    use game_vfx::TextAnimator;
+
+    use crate::*;
+
+    #[test]
+    fn widget_is_built_once_the_asset_cache_is_inserted() {
+        let mut test = UiTestApp::new();
+        let cache = test.world_mut().remove_resource::<AssetCache>().expect("The test app has no AssetCache");
+        let button = test.world_mut().spawn((UiLayout::window().full().pack(), MainButton::new("Play"))).id();
+
+        // The button waits unbuilt for three frames without the cache
+        for frame in 1..=3 {
+            test.advance(UiTestApp::FRAME);
+            assert!(test.world().get::<MainButtonControl>(button).is_none(), "MainButton was built without the AssetCache on frame {frame}");
+        }
+
+        test.world_mut().insert_resource(cache);
+        test.advance(UiTestApp::FRAME);
+        assert!(test.world().get::<MainButtonControl>(button).is_some(), "MainButton was not built once the AssetCache was inserted");
+        let (image, text) = test.button_parts(button);
+        assert_eq!(test.world().get::<Parent>(image).map(Parent::get), Some(button), "The internals are not under the button");
+        assert!(test.world().get::<TextAnimator>(text).is_some_and(|label| label.text() == "PLAY"), "The label is missing");
+
+        // The observers are attached once, so one click is reported once
+        test.click(button);
+        assert_eq!(test.assert_event::<MainButtonClicked>(), button);
+        test.assert_no_event::<MainButtonClicked>();
+    }
+
+    #[test]
+    fn internals_are_hidden_until_laid_out() {
+        let mut test = UiTestApp::new();
+        let root = test.world_mut().spawn((UiLayoutRoot::new_2d(), Dimension(Vec2::new(1280.0, 720.0)))).id();
+        let button = test.world_mut().spawn((
+            UiLayout::window().pos(Rl((10.0, 10.0))).size(Rl((20.0, 10.0))).pack(),
+            MainButton::new("Play"),
+        )).set_parent(root).id();
+
+        // Whatever the first three frames draw is already at its place and size
+        for frame in 1..=3 {
+            test.advance(UiTestApp::FRAME);
+            let Some(control) = test.world().get::<MainButtonControl>(button) else { continue; };
+            let image = test.world().entity(control.image());
+            if image.get::<Visibility>() == Some(&Visibility::Hidden) { continue; }
+            let size = image.get::<Dimension>().map_or(Vec2::ZERO, |dimension| dimension.0);
+            assert!(size.x > 0.0 && size.y > 0.0, "The image is shown at size {size} on frame {frame}");
+            let position = image.get::<GlobalTransform>().map_or(Vec3::ZERO, GlobalTransform::translation);
+            assert_ne!(position.truncate(), Vec2::ZERO, "The image is shown before it was placed on frame {frame}");
+        }
+
+        let (image, _) = test.button_parts(button);
+        assert_eq!(test.world().get::<Visibility>(image), Some(&Visibility::Inherited), "The image is still hidden after three frames");
+        assert!(test.world().get::<UiReveal>(image).is_none(), "The image is still waiting to be revealed");
+    }
+}
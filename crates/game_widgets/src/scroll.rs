@@ -0,0 +1,144 @@
+use bevy::{ecs::system::SystemParam, input::mouse::{MouseScrollUnit, MouseWheel}, picking::{focus::HoverMap, pointer::PointerId}, prelude::*};
+use bevy_lunex::*;
+use game_vfx::*;
+
+
+/// Marks a scrollable viewport. The widget owning it applies the [`ScrollDelta`] it receives,
+/// the offset and the limit are kept here so the wheel can fall through to the parent at the edge.
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug, Default)]
+#[reflect(Component)]
+pub struct Scrollable {
+    /// The scrolled distance in pixels
+    pub offset: Vec2,
+    /// The largest offset, the content size minus the viewport size
+    pub max: Vec2,
+}
+impl Scrollable {
+    /// How far one line of the wheel scrolls in pixels
+    const LINE_HEIGHT: f32 = 40.0;
+    /// Creates new instance
+    pub fn new(max: Vec2) -> Self {
+        Self { offset: Vec2::ZERO, max }
+    }
+    /// If any part of the delta moves the offset before it hits the limit
+    pub fn can_scroll(&self, delta: Vec2) -> bool {
+        self.clamp(delta) != Vec2::ZERO
+    }
+    /// The part of the delta that moves the offset before it hits the limit
+    pub fn clamp(&self, delta: Vec2) -> Vec2 {
+        (self.offset + delta).clamp(Vec2::ZERO, self.max.max(Vec2::ZERO)) - self.offset
+    }
+    /// This system routes the wheel to the topmost scrollable under the cursor, the parent scrollables
+    /// get it only when the inner ones are already at their limit in that direction.
+    fn route_system(
+        mut commands: Commands,
+        mut wheel: EventReader<MouseWheel>,
+        hover: Res<HoverMap>,
+        scrollables: Query<&Scrollable>,
+        parents: Query<&Parent>,
+    ) {
+        let delta: Vec2 = wheel.read().map(|event| match event.unit {
+            MouseScrollUnit::Line => Vec2::new(event.x, event.y) * Scrollable::LINE_HEIGHT,
+            MouseScrollUnit::Pixel => Vec2::new(event.x, event.y),
+        }).sum();
+        if delta == Vec2::ZERO { return; }
+        // Wheel up moves the content down, so the offset goes back
+        let delta = -delta;
+
+        let Some(hovered) = hover.get(&PointerId::Mouse) else { return; };
+        let Some((topmost, _)) = hovered.iter().min_by(|(_, a), (_, b)| a.depth.total_cmp(&b.depth)) else { return; };
+
+        // Walk up from the topmost node, the innermost scrollable with room wins
+        let target = std::iter::once(*topmost).chain(parents.iter_ancestors(*topmost))
+            .find(|entity| scrollables.get(*entity).is_ok_and(|scrollable| scrollable.can_scroll(delta)));
+        if let Some(target) = target {
+            commands.trigger_targets(ScrollDelta(delta), target);
+        }
+    }
+}
+
+/// This event is triggered on the [`Scrollable`] under the cursor when the wheel turns, in pixels
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct ScrollDelta(pub Vec2);
+
+/// Insert this on a [`Scrollable`] to scroll it by the delta over time, it triggers [`ScrollDelta`]
+/// on it every frame like the wheel does and removes itself once done. A new one replaces the rest.
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct ScrollAnimation {
+    tween: Tween<Vec2>,
+    /// The part of the delta already triggered
+    sent: Vec2,
+}
+impl ScrollAnimation {
+    /// Creates new instance scrolling by the delta in pixels over the duration in seconds
+    pub fn new(delta: Vec2, duration: f32) -> Self {
+        Self { tween: Tween::new(Vec2::ZERO, delta, duration).easing(Easing::CubicOut), sent: Vec2::ZERO }
+    }
+    /// This system triggers the part of the delta each animation moved by in this frame.
+    fn system(mut commands: Commands, mut query: Query<(Entity, &mut ScrollAnimation)>, time: Res<Time>) {
+        for (entity, mut animation) in &mut query {
+            let value = animation.tween.tick(time.delta_secs());
+            let delta = value - animation.sent;
+            animation.sent = value;
+            if delta != Vec2::ZERO { commands.trigger_targets(ScrollDelta(delta), entity); }
+            if animation.tween.is_done() { commands.entity(entity).remove::<ScrollAnimation>(); }
+        }
+    }
+}
+
+/// Looks up the [`Scrollable`] viewports a widget is inside of, from the innermost one out
+#[derive(SystemParam)]
+pub struct ScrollContainers<'w, 's> {
+    scrollables: Query<'w, 's, (&'static Scrollable, &'static GlobalTransform, &'static Dimension)>,
+    parents: Query<'w, 's, &'static Parent>,
+}
+impl ScrollContainers<'_, '_> {
+    /// Returns the scrollable ancestors of the entity, the innermost first
+    pub fn of(&self, entity: Entity) -> Vec<Entity> {
+        self.parents.iter_ancestors(entity).filter(|ancestor| self.scrollables.contains(*ancestor)).collect()
+    }
+    /// Returns how far each scrollable ancestor has to scroll, the innermost first, so the rectangle is fully
+    /// visible with the margin around it. Each scrolls the least it can, the outer ones take what the inner
+    /// ones could not and the rectangle moves along with every inner scroll.
+    pub fn reveal(&self, entity: Entity, mut rect: Rect, margin: f32) -> Vec<(Entity, Vec2)> {
+        let mut deltas = Vec::new();
+        for container in self.of(entity) {
+            let Ok((scrollable, transform, dimension)) = self.scrollables.get(container) else { continue; };
+            let viewport = Rect::from_center_size(transform.translation().truncate(), dimension.0).inflate(-margin);
+            let delta = scrollable.clamp(ScrollContainers::distance(viewport, rect));
+            if delta == Vec2::ZERO { continue; }
+            // The offset grows to the right and down, so the content moves left and up in the world
+            rect = Rect::from_corners(rect.min + Vec2::new(-delta.x, delta.y), rect.max + Vec2::new(-delta.x, delta.y));
+            deltas.push((container, delta));
+        }
+        deltas
+    }
+    /// The offset change bringing the rectangle into the viewport, a larger one keeps its top left corner in
+    fn distance(viewport: Rect, rect: Rect) -> Vec2 {
+        let x = if rect.min.x < viewport.min.x {
+            rect.min.x - viewport.min.x
+        } else if rect.max.x > viewport.max.x {
+            (rect.max.x - viewport.max.x).min(rect.min.x - viewport.min.x)
+        } else { 0.0 };
+        // The world goes up while the offset goes down
+        let y = if rect.max.y > viewport.max.y {
+            viewport.max.y - rect.max.y
+        } else if rect.min.y < viewport.min.y {
+            (viewport.min.y - rect.min.y).min(viewport.max.y - rect.max.y)
+        } else { 0.0 };
+        Vec2::new(x, y)
+    }
+}
+
+
+/// Plugin routing the mouse wheel to [`Scrollable`] nodes
+pub(crate) struct ScrollPlugin;
+impl Plugin for ScrollPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Scrollable>();
+        app.add_systems(Update, (
+            Scrollable::route_system.run_if(any_with_component::<Scrollable>),
+            ScrollAnimation::system.run_if(any_with_component::<ScrollAnimation>),
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_lunex::*;
+use game_vfx::*;
+
+
+/// Insert this on a widget root to shake it sideways, the default feedback for a rejected input like a click
+/// on a disabled [`MainButton`]. The offset decays over the duration, then the component removes itself and
+/// the widget is back exactly where its layout puts it. Inserting it again while shaking starts over.
+/// Under reduced motion the widget stays in place.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct Shake {
+    /// The largest offset in pixels
+    pub amplitude: f32,
+    /// Seconds the shake lasts
+    pub duration: f32,
+}
+impl Default for Shake {
+    fn default() -> Self {
+        Self { amplitude: 8.0, duration: 0.35 }
+    }
+}
+impl Shake {
+    /// Swings per second
+    const FREQUENCY: f32 = 14.0;
+    /// Creates new instance
+    pub fn new(amplitude: f32, duration: f32) -> Self {
+        Self { amplitude, duration }
+    }
+    /// Returns the offset at the seconds into the shake
+    fn offset(&self, time: f32) -> f32 {
+        let t = (time / self.duration.max(f32::EPSILON)).min(1.0);
+        self.amplitude * (1.0 - t).powi(2) * (std::f32::consts::TAU * Shake::FREQUENCY * time).sin()
+    }
+    /// This system moves the shaking widgets on top of their layout position and puts them back once done.
+    fn system(
+        mut commands: Commands,
+        mut query: Query<(Entity, Ref<Shake>, Option<&mut ShakeState>, &mut Transform)>,
+        reduced: Res<ReducedMotion>,
+        time: Res<Time>,
+    ) {
+        let scale = motion_scale(&reduced);
+        for (entity, shake, state, mut transform) in &mut query {
+            let Some(mut state) = state else {
+                commands.entity(entity).insert(ShakeState { time: 0.0, applied: 0.0, written: transform.translation });
+                continue;
+            };
+
+            // The layout put the widget somewhere new, the offset starts from there
+            let base = if transform.translation == state.written { state.written.x - state.applied } else { transform.translation.x };
+
+            // A shake inserted again restarts instead of adding up
+            if shake.is_changed() && !shake.is_added() { state.time = 0.0; } else { state.time += time.delta_secs(); }
+
+            if state.time >= shake.duration {
+                transform.translation.x = base;
+                commands.entity(entity).remove::<(Shake, ShakeState)>();
+                continue;
+            }
+            state.applied = shake.offset(state.time) * scale;
+            transform.translation.x = base + state.applied;
+            state.written = transform.translation;
+        }
+    }
+    /// This system puts the widgets back whose shake was removed by someone else meanwhile.
+    fn removed_system(
+        mut commands: Commands,
+        mut query: Query<(Entity, &ShakeState, &mut Transform), Without<Shake>>,
+    ) {
+        for (entity, state, mut transform) in &mut query {
+            if transform.translation == state.written { transform.translation.x -= state.applied; }
+            commands.entity(entity).remove::<ShakeState>();
+        }
+    }
+}
+
+/// What a [`Shake`] did to the transform, kept apart so inserting the shake again does not lose it
+#[derive(Component)]
+struct ShakeState {
+    /// Seconds into the shake
+    time: f32,
+    /// The offset added in the last frame
+    applied: f32,
+    /// The translation written in the last frame, a different one means the layout moved the widget
+    written: Vec3,
+}
+
+
+/// Plugin with the [`Shake`] feedback
+pub(crate) struct ShakePlugin;
+impl Plugin for ShakePlugin {
+    fn build(&self, app: &mut App) {
+        // After the layout wrote the transforms, so the offset is never overwritten mid shake
+        app.add_systems(PostUpdate, (
+            Shake::removed_system,
+            Shake::system,
+        ).chain().after(UiSystems::Compute).before(TransformSystem::TransformPropagate));
+    }
+}
@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+
+use bevy::{asset::{io::Reader, AssetLoader, LoadContext}, prelude::*};
+use game_preferences::platform;
+use game_vfx::*;
+use serde::Deserialize;
+
+use crate::*;
+
+
+/// Font sizes rasterized for each [`Typography`](crate::Typography) preset
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TypographySizes {
+    pub heading: f32,
+    pub body: f32,
+    pub hint: f32,
+    pub mono: f32,
+}
+impl Default for TypographySizes {
+    fn default() -> Self {
+        Self { heading: 64.0, body: 64.0, hint: 48.0, mono: 48.0 }
+    }
+}
+
+/// Timing and movement of the [`MainButton`](crate::MainButton) hover animation
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ButtonMotion {
+    /// Seconds it takes to fully highlight the button
+    pub hover_in: f32,
+    /// Seconds it takes to fade the highlight out
+    pub hover_out: f32,
+    /// How far the image slides on hover, in percent of the button width
+    pub slide: f32,
+}
+impl Default for ButtonMotion {
+    fn default() -> Self {
+        Self { hover_in: 0.05, hover_out: 0.25, slide: 10.0 }
+    }
+}
+impl ButtonMotion {
+    /// Transition speed towards the hover state per second
+    pub fn forward_speed(&self) -> f32 {
+        1.0 / self.hover_in.max(f32::EPSILON)
+    }
+    /// Transition speed back to the base state per second
+    pub fn backward_speed(&self) -> f32 {
+        1.0 / self.hover_out.max(f32::EPSILON)
+    }
+    /// The motion with the durations scaled, like by [`motion_scale`] where 0.0 makes the hover instant
+    pub fn scaled(mut self, scale: f32) -> Self {
+        self.hover_in *= scale;
+        self.hover_out *= scale;
+        self
+    }
+}
+
+/// Designer facing style of the user interface, loaded from `assets/ui.style.ron`.
+/// Anything the file leaves out keeps the built-in Bevypunk value.
+#[derive(Asset, Resource, TypePath, Clone, PartialEq, Debug, Default)]
+pub struct UiStyle {
+    /// The colors of the dark palette
+    pub theme: UiTheme,
+    pub typography: TypographySizes,
+    pub button: ButtonMotion,
+    /// The rules of the [`StyleSheet`]
+    pub rules: StyleSheet,
+}
+impl UiStyle {
+    /// Parses the style from a RON string
+    pub fn parse(source: &str) -> Result<Self, UiStyleError> {
+        let source: UiStyleSource = ron::from_str(source).map_err(|error| UiStyleError::Parse(error.to_string()))?;
+        source.resolve()
+    }
+    /// Serializes the style into the format of the style file
+    pub fn to_ron(&self) -> String {
+        let mut ron = String::from("(\n    theme: (\n");
+        for role in ThemeRole::ALL {
+            ron += &format!("        {}: \"{}\",\n", role.name(), self.theme.get(role).to_srgba().to_hex());
+        }
+        let (typography, button) = (self.typography, self.button);
+        ron += &format!("    ),\n    typography: (\n        heading: {:?},\n        body: {:?},\n        hint: {:?},\n        mono: {:?},\n    ),\n", typography.heading, typography.body, typography.hint, typography.mono);
+        ron += &format!("    button: (\n        hover_in: {:?},\n        hover_out: {:?},\n        slide: {:?},\n    ),\n", button.hover_in, button.hover_out, button.slide);
+        ron += "    rules: [\n";
+        for (selector, rule) in &self.rules.rules {
+            let mut fields = Vec::new();
+            if !rule.colors.is_empty() {
+                let colors: Vec<String> = rule.colors.iter().map(|(role, color)| format!("\"{}\": \"{}\"", role.name(), color.to_srgba().to_hex())).collect();
+                fields.push(format!("colors: {{{}}}", colors.join(", ")));
+            }
+            if let Some(font) = rule.font { fields.push(format!("font: \"{}\"", font.name())); }
+            if let Some(hover_in) = rule.hover_in { fields.push(format!("hover_in: {hover_in:?}")); }
+            if let Some(hover_out) = rule.hover_out { fields.push(format!("hover_out: {hover_out:?}")); }
+            if let Some(slide) = rule.slide { fields.push(format!("slide: {slide:?}")); }
+            ron += &format!("        (\"{selector}\", ({})),\n", fields.join(", "));
+        }
+        ron += "    ],\n)\n";
+        ron
+    }
+    /// Writes the style file to disk, platforms without a file system get it logged instead
+    pub fn export(&self, path: &str) {
+        let ron = self.to_ron();
+        match platform::export(path, &ron) {
+            Ok(()) => info!("Exported the UI style to '{path}'"),
+            Err(error) => warn!("Failed to export the UI style to '{path}': {error}\n{ron}"),
+        }
+    }
+    /// This system starts loading the style file.
+    fn load_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+        commands.insert_resource(UiStyleHandle(asset_server.load("ui.style.ron")));
+    }
+    /// This system applies the style each time the file is loaded or edited on disk.
+    fn apply_system(
+        mut events: EventReader<AssetEvent<UiStyle>>,
+        handle: Res<UiStyleHandle>,
+        styles: Res<Assets<UiStyle>>,
+        mut style: ResMut<UiStyle>,
+        mut crossfade: ResMut<ThemeCrossfade>,
+        mut sheet: ResMut<StyleSheet>,
+        theme: Res<UiTheme>,
+    ) {
+        let reloaded = events.read().any(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id == handle.0.id(),
+            _ => false,
+        });
+        if !reloaded { return; }
+        let Some(loaded) = styles.get(&handle.0) else { return; };
+        if *style != *loaded {
+            info!("Applied the UI style");
+            *style = loaded.clone();
+            crossfade.set_dark(style.theme.clone(), &theme);
+            if *sheet != style.rules { *sheet = style.rules.clone(); }
+        }
+    }
+}
+
+/// Errors produced when the style file is invalid
+#[derive(Clone, PartialEq, Debug)]
+pub enum UiStyleError {
+    /// The file could not be read
+    Io(String),
+    /// The file is not valid RON or has an unknown field
+    Parse(String),
+    /// The field does not hold a hex color
+    InvalidColor { field: &'static str, value: String },
+    /// The field must be a positive number
+    InvalidNumber { field: &'static str, value: f32 },
+    /// The rule of the selector is invalid
+    InvalidRule { selector: String, message: String },
+}
+impl std::fmt::Display for UiStyleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UiStyleError::Io(error) => write!(f, "UI style could not be read: {error}"),
+            UiStyleError::Parse(error) => write!(f, "UI style could not be parsed: {error}"),
+            UiStyleError::InvalidColor { field, value } => write!(f, "UI style field '{field}' is not a hex color like \"#FF6251\": '{value}'"),
+            UiStyleError::InvalidNumber { field, value } => write!(f, "UI style field '{field}' must be a positive number: {value}"),
+            UiStyleError::InvalidRule { selector, message } => write!(f, "UI style rule '{selector}' is invalid: {message}"),
+        }
+    }
+}
+impl std::error::Error for UiStyleError {}
+
+/// The style file as written, every field is optional
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct UiStyleSource {
+    theme: ThemeSource,
+    typography: TypographySource,
+    button: ButtonSource,
+    rules: Vec<(String, RuleSource)>,
+}
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ThemeSource {
+    primary: Option<String>,
+    primary_hover: Option<String>,
+    text: Option<String>,
+    text_dim: Option<String>,
+    accent: Option<String>,
+    background: Option<String>,
+    danger: Option<String>,
+    warning: Option<String>,
+}
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct TypographySource {
+    heading: Option<f32>,
+    body: Option<f32>,
+    hint: Option<f32>,
+    mono: Option<f32>,
+}
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ButtonSource {
+    hover_in: Option<f32>,
+    hover_out: Option<f32>,
+    slide: Option<f32>,
+}
+#[derive(Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RuleSource {
+    colors: BTreeMap<String, String>,
+    font: Option<String>,
+    hover_in: Option<f32>,
+    hover_out: Option<f32>,
+    slide: Option<f32>,
+}
+impl RuleSource {
+    /// Validates the values of the rule
+    fn resolve(self, selector: &str) -> Result<(StyleSelector, StyleRule), UiStyleError> {
+        let error = |message: String| UiStyleError::InvalidRule { selector: selector.to_string(), message };
+        let parsed = StyleSelector::parse(selector).ok_or_else(|| error("expected 'Widget', '.class' or 'Widget.class'".into()))?;
+        let mut rule = StyleRule::new();
+        for (role, value) in self.colors {
+            let role = ThemeRole::from_name(&role).ok_or_else(|| error(format!("unknown color role '{role}'")))?;
+            let color = Srgba::hex(&value).map_err(|_| error(format!("'{value}' is not a hex color like \"#FF6251\"")))?;
+            rule = rule.color(role, Color::Srgba(color));
+        }
+        if let Some(font) = self.font {
+            rule.font = Some(TypographyRole::from_name(&font).ok_or_else(|| error(format!("unknown font '{font}', expected heading, body, hint or mono")))?);
+        }
+        for (field, value) in [("hover_in", self.hover_in), ("hover_out", self.hover_out), ("slide", self.slide)] {
+            if value.is_some_and(|value| value < 0.0 || !value.is_finite()) { return Err(error(format!("'{field}' must be a positive number"))); }
+        }
+        rule.hover_in = self.hover_in;
+        rule.hover_out = self.hover_out;
+        rule.slide = self.slide;
+        Ok((parsed, rule))
+    }
+}
+impl UiStyleSource {
+    /// Validates the values and fills in the defaults
+    fn resolve(self) -> Result<UiStyle, UiStyleError> {
+        let color = |field: &'static str, value: Option<String>, default: Color| match value {
+            Some(value) => Srgba::hex(&value).map(Color::Srgba).map_err(|_| UiStyleError::InvalidColor { field, value }),
+            None => Ok(default),
+        };
+        let number = |field: &'static str, value: Option<f32>, default: f32| match value {
+            Some(value) if value < 0.0 || !value.is_finite() => Err(UiStyleError::InvalidNumber { field, value }),
+            Some(value) => Ok(value),
+            None => Ok(default),
+        };
+
+        let rules = self.rules.into_iter().map(|(selector, rule)| rule.resolve(&selector)).collect::<Result<_, _>>()?;
+        let (theme, typography, button) = (UiTheme::default(), TypographySizes::default(), ButtonMotion::default());
+        Ok(UiStyle {
+            theme: UiTheme {
+                primary: color("theme.primary", self.theme.primary, theme.primary)?,
+                primary_hover: color("theme.primary_hover", self.theme.primary_hover, theme.primary_hover)?,
+                text: color("theme.text", self.theme.text, theme.text)?,
+                text_dim: color("theme.text_dim", self.theme.text_dim, theme.text_dim)?,
+                accent: color("theme.accent", self.theme.accent, theme.accent)?,
+                background: color("theme.background", self.theme.background, theme.background)?,
+                danger: color("theme.danger", self.theme.danger, theme.danger)?,
+                warning: color("theme.warning", self.theme.warning, theme.warning)?,
+            },
+            typography: TypographySizes {
+                heading: number("typography.heading", self.typography.heading, typography.heading)?,
+                body: number("typography.body", self.typography.body, typography.body)?,
+                hint: number("typography.hint", self.typography.hint, typography.hint)?,
+                mono: number("typography.mono", self.typography.mono, typography.mono)?,
+            },
+            button: ButtonMotion {
+                hover_in: number("button.hover_in", self.button.hover_in, button.hover_in)?,
+                hover_out: number("button.hover_out", self.button.hover_out, button.hover_out)?,
+                slide: number("button.slide", self.button.slide, button.slide)?,
+            },
+            rules: StyleSheet { rules },
+        })
+    }
+}
+
+/// Handle to the loaded style file
+#[derive(Resource)]
+struct UiStyleHandle(Handle<UiStyle>);
+
+/// Loads [`UiStyle`] from RON files
+#[derive(Default)]
+struct UiStyleLoader;
+impl AssetLoader for UiStyleLoader {
+    type Asset = UiStyle;
+    type Settings = ();
+    type Error = UiStyleError;
+    async fn load(&self, reader: &mut dyn Reader, _settings: &(), _context: &mut LoadContext<'_>) -> Result<UiStyle, UiStyleError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(|error| UiStyleError::Io(error.to_string()))?;
+        UiStyle::parse(&String::from_utf8_lossy(&bytes))
+    }
+    fn extensions(&self) -> &[&str] {
+        &["style.ron"]
+    }
+}
+
+
+/// Plugin loading and hot-reloading the [`UiStyle`]
+pub struct UiStylePlugin;
+impl Plugin for UiStylePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<UiStyle>();
+        app.register_asset_loader(UiStyleLoader);
+        app.init_resource::<UiStyle>();
+        app.add_systems(Startup, UiStyle::load_system);
+        app.add_systems(PreUpdate, UiStyle::apply_system.run_if(on_event::<AssetEvent<UiStyle>>));
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// The classes of a widget, matched by the `.class` selectors of the [`StyleSheet`]
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct UiClass(pub Vec<String>);
+impl UiClass {
+    /// Creates new instance with one class
+    pub fn new(class: impl Into<String>) -> Self {
+        Self(vec![class.into()])
+    }
+    /// Add another class.
+    pub fn with(mut self, class: impl Into<String>) -> Self {
+        self.0.push(class.into());
+        self
+    }
+    /// If the widget has the class
+    pub fn has(&self, class: &str) -> bool {
+        self.0.iter().any(|own| own == class)
+    }
+}
+
+/// Which widgets a [`StyleRule`] applies to, written as `MainButton`, `.danger` or `MainButton.danger`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct StyleSelector {
+    /// The type name of the widget component
+    pub widget: Option<String>,
+    /// The [`UiClass`] the widget must have
+    pub class: Option<String>,
+}
+impl StyleSelector {
+    /// Parses the selector, returns `None` if it is empty or has more than one class
+    pub fn parse(selector: &str) -> Option<Self> {
+        let (widget, class) = match selector.trim().split_once('.') {
+            Some((widget, class)) => (widget, Some(class)),
+            None => (selector.trim(), None),
+        };
+        if class.is_some_and(|class| class.is_empty() || class.contains('.')) || (widget.is_empty() && class.is_none()) { return None; }
+        Some(Self {
+            widget: (!widget.is_empty()).then(|| widget.to_string()),
+            class: class.map(str::to_string),
+        })
+    }
+    /// If the selector matches the widget with the classes
+    pub fn matches(&self, widget: &str, classes: Option<&UiClass>) -> bool {
+        self.widget.as_ref().is_none_or(|own| own == widget)
+            && self.class.as_ref().is_none_or(|class| classes.is_some_and(|classes| classes.has(class)))
+    }
+    /// Type rules apply first, then class rules and then rules with both
+    fn specificity(&self) -> u8 {
+        self.widget.is_some() as u8 + 2 * self.class.is_some() as u8
+    }
+}
+impl std::fmt::Display for StyleSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(widget) = &self.widget { write!(f, "{widget}")?; }
+        if let Some(class) = &self.class { write!(f, ".{class}")?; }
+        Ok(())
+    }
+}
+
+/// The properties a [`StyleSheet`] rule sets, everything left out comes from the less specific rules
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct StyleRule {
+    /// Colors replacing the [`UiTheme`] roles for the widget and its internals
+    pub colors: Vec<(ThemeRole, Color)>,
+    /// The typography preset of the label
+    pub font: Option<TypographyRole>,
+    /// Seconds it takes to fully highlight the widget
+    pub hover_in: Option<f32>,
+    /// Seconds it takes to fade the highlight out
+    pub hover_out: Option<f32>,
+    /// How far the image slides on hover, in percent of the widget width
+    pub slide: Option<f32>,
+}
+impl StyleRule {
+    /// Creates new instance setting nothing
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Set the color of the role.
+    pub fn color(mut self, role: ThemeRole, color: Color) -> Self {
+        self.colors.retain(|(own, _)| *own != role);
+        self.colors.push((role, color));
+        self
+    }
+    /// Set the typography preset of the label.
+    pub fn font(mut self, font: TypographyRole) -> Self {
+        self.font = Some(font);
+        self
+    }
+    /// Set the durations of the hover animation.
+    pub fn hover(mut self, hover_in: f32, hover_out: f32) -> Self {
+        self.hover_in = Some(hover_in);
+        self.hover_out = Some(hover_out);
+        self
+    }
+    /// Set the slide distance of the hover animation.
+    pub fn slide(mut self, slide: f32) -> Self {
+        self.slide = Some(slide);
+        self
+    }
+    /// Replaces the properties the other rule sets
+    fn merge(&mut self, other: &StyleRule) {
+        for (role, color) in &other.colors { *self = std::mem::take(self).color(*role, *color); }
+        self.font = other.font.or(self.font);
+        self.hover_in = other.hover_in.or(self.hover_in);
+        self.hover_out = other.hover_out.or(self.hover_out);
+        self.slide = other.slide.or(self.slide);
+    }
+}
+
+/// Rules styling the widgets by their type and [`UiClass`], like a small CSS. The effective style of a
+/// widget cascades from the [`UiStyle`] through the type rules, the class rules and the rules with
+/// both, later rules of the same kind win. The [`ThemeOverride`] of the entity is applied last.
+/// The rules are loaded from the `rules` of the style file, changing them restyles existing widgets.
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct StyleSheet {
+    pub rules: Vec<(StyleSelector, StyleRule)>,
+}
+impl StyleSheet {
+    /// Creates new instance without rules
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Add the rule for the selector, invalid selectors are skipped with a warning.
+    pub fn rule(mut self, selector: &str, rule: StyleRule) -> Self {
+        match StyleSelector::parse(selector) {
+            Some(selector) => self.rules.push((selector, rule)),
+            None => warn!("Style selector '{selector}' is invalid, expected 'Widget', '.class' or 'Widget.class'"),
+        }
+        self
+    }
+    /// Returns the properties of all rules matching the widget merged in the cascade order
+    pub fn resolve(&self, widget: &str, classes: Option<&UiClass>) -> StyleRule {
+        let mut matching: Vec<_> = self.rules.iter().filter(|(selector, _)| selector.matches(widget, classes)).collect();
+        matching.sort_by_key(|(selector, _)| selector.specificity());
+        matching.into_iter().fold(StyleRule::new(), |mut style, (_, rule)| {
+            style.merge(rule);
+            style
+        })
+    }
+    /// This system resolves the style of the widgets when they, their classes, the [`StyleSheet`] or the [`UiStyle`] change.
+    #[allow(clippy::type_complexity)]
+    fn apply_system<W: StyledWidget>(
+        mut commands: Commands,
+        sheet: Res<StyleSheet>,
+        style: Res<UiStyle>,
+        query: Query<(Entity, Option<&UiClass>, Option<&ResolvedStyle>, Has<ThemeRules>), With<W>>,
+        changed: Query<(), (With<W>, Or<(Added<W>, Changed<UiClass>)>)>,
+        mut removed: RemovedComponents<UiClass>,
+    ) {
+        let removed: Vec<Entity> = removed.read().collect();
+        let all = sheet.is_changed() || style.is_changed();
+        for (entity, classes, current, has_rules) in &query {
+            if !all && !changed.contains(entity) && !removed.contains(&entity) { continue; }
+            let rule = sheet.resolve(W::NAME, classes);
+
+            let resolved = ResolvedStyle {
+                motion: ButtonMotion {
+                    hover_in: rule.hover_in.unwrap_or(style.button.hover_in),
+                    hover_out: rule.hover_out.unwrap_or(style.button.hover_out),
+                    slide: rule.slide.unwrap_or(style.button.slide),
+                },
+                font: rule.font.unwrap_or(W::FONT),
+            };
+            if current != Some(&resolved) { commands.entity(entity).insert(resolved); }
+
+            if !rule.colors.is_empty() {
+                let colors = rule.colors.iter().fold(ThemeOverride::new(), |colors, (role, color)| colors.set(*role, *color));
+                commands.entity(entity).insert(ThemeRules(colors));
+            } else if has_rules {
+                commands.entity(entity).remove::<ThemeRules>();
+            }
+        }
+    }
+}
+
+/// A widget styled by the [`StyleSheet`], register it with [`StyleSheetAppExt::register_styled_widget`]
+pub trait StyledWidget: Component {
+    /// The type name matched by the selectors
+    const NAME: &'static str;
+    /// The typography preset of the label when no rule sets one
+    const FONT: TypographyRole = TypographyRole::Body;
+}
+impl StyledWidget for MainButton {
+    const NAME: &'static str = "MainButton";
+}
+impl StyledWidget for ColorPicker {
+    const NAME: &'static str = "ColorPicker";
+}
+
+/// The style of a widget after the cascade, the colors are in its [`ThemeRules`]
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct ResolvedStyle {
+    pub motion: ButtonMotion,
+    pub font: TypographyRole,
+}
+
+/// Registers widgets styled by the [`StyleSheet`]
+pub trait StyleSheetAppExt {
+    /// Resolves the style of the widget before the widgets are built
+    fn register_styled_widget<W: StyledWidget>(&mut self) -> &mut Self;
+}
+impl StyleSheetAppExt for App {
+    fn register_styled_widget<W: StyledWidget>(&mut self) -> &mut Self {
+        self.add_systems(Update, StyleSheet::apply_system::<W>.before(SandboxedUiSystems))
+    }
+}
+
+
+/// Plugin with the [`StyleSheet`] of the widgets
+pub(crate) struct StyleSheetPlugin;
+impl Plugin for StyleSheetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StyleSheet>();
+        app.register_type::<UiClass>();
+        app.register_styled_widget::<MainButton>();
+        app.register_styled_widget::<ColorPicker>();
+    }
+}
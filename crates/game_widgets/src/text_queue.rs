@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// Spreads text rewrites over several frames, so changing the text of many widgets at once does not
+/// relayout all of them in a single frame. Several rewrites of one entity become a single write.
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct UiTextUpdateQueue {
+    /// How many queued texts are written per frame, urgent ones do not count
+    pub budget: usize,
+    order: VecDeque<Entity>,
+    pending: HashMap<Entity, String>,
+    urgent: HashMap<Entity, String>,
+}
+impl Default for UiTextUpdateQueue {
+    fn default() -> Self {
+        Self {
+            budget: 16,
+            order: VecDeque::new(),
+            pending: HashMap::new(),
+            urgent: HashMap::new(),
+        }
+    }
+}
+impl UiTextUpdateQueue {
+    /// Queues the new text of the [`Text2d`] entity, written once its turn comes
+    pub fn queue(&mut self, entity: Entity, text: impl Into<String>) {
+        if self.urgent.contains_key(&entity) {
+            self.urgent.insert(entity, text.into());
+            return;
+        }
+        if self.pending.insert(entity, text.into()).is_none() { self.order.push_back(entity); }
+    }
+    /// Writes the new text of the [`Text2d`] entity this frame, like for the widget the user interacts with
+    pub fn urgent(&mut self, entity: Entity, text: impl Into<String>) {
+        self.pending.remove(&entity);
+        self.urgent.insert(entity, text.into());
+    }
+    /// The number of texts waiting to be written
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.urgent.len()
+    }
+    /// If no text is waiting to be written
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty() && self.urgent.is_empty()
+    }
+    /// This system writes the urgent texts and the next queued ones within the budget.
+    fn system(mut commands: Commands, mut queue: ResMut<UiTextUpdateQueue>, mut texts: Query<&mut Text2d>) {
+        let mut writes: Vec<(Entity, String)> = queue.urgent.drain().collect();
+        let mut taken = 0;
+        while taken < queue.budget {
+            let Some(entity) = queue.order.pop_front() else { break; };
+            // Entities moved to the urgent ones are no longer pending
+            if let Some(text) = queue.pending.remove(&entity) {
+                writes.push((entity, text));
+                taken += 1;
+            }
+        }
+
+        let mut changed = false;
+        for (entity, text) in writes {
+            let Ok(mut current) = texts.get_mut(entity) else { continue; };
+            if current.0 != text {
+                current.0 = text;
+                changed = true;
+            }
+        }
+        if changed { commands.trigger(RecomputeUiLayout); }
+    }
+}
+
+
+/// Plugin with the [`UiTextUpdateQueue`]
+pub(crate) struct UiTextUpdateQueuePlugin;
+impl Plugin for UiTextUpdateQueuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiTextUpdateQueue>();
+        app.add_systems(Update, UiTextUpdateQueue::system.after(SandboxedUiSystems).run_if(|queue: Res<UiTextUpdateQueue>| !queue.is_empty()));
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::{prelude::*, sprite::Anchor, window::SystemCursorIcon};
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Developer panel editing the [`UiTheme`] live, toggled with F8
+#[derive(Component)]
+pub struct ThemeEditorPanel;
+impl ThemeEditorPanel {
+    /// Where the exported style is written
+    const EXPORT_PATH: &str = "assets/ui.style.ron";
+    /// This system opens and closes the panel.
+    fn toggle_system(
+        mut commands: Commands,
+        keys: Res<ButtonInput<KeyCode>>,
+        panels: Query<Entity, With<ThemeEditorPanel>>,
+        assets: Res<AssetCache>,
+        style: Res<UiStyle>,
+    ) {
+        if !keys.just_pressed(KeyCode::F8) { return; }
+        if !panels.is_empty() {
+            for entity in &panels { commands.entity(entity).despawn_recursive(); }
+            return;
+        }
+
+        commands.spawn((
+            UiLayoutRoot::new_2d(),
+            UiFetchFromCamera::<0>,
+            ThemeEditorPanel,
+        )).with_children(|ui| {
+
+            // Spawn the panel on the right side
+            let mut panel = ui.spawn((
+                UiLayout::window().pos(Rl((70.0, 5.0))).size(Rl((28.0, 90.0))).pack(),
+                UiDepth::Add(50.0),
+                ThemedColor::alpha(ThemeRole::Background, 0.9),
+                Sprite::default(),
+            ));
+            let panel_entity = panel.id();
+            panel.with_children(|ui| {
+
+                ui.spawn((
+                    UiLayout::window().pos(Rl((5.0, 4.0))).anchor(Anchor::CenterLeft).pack(),
+                    UiTextSize::from(Rh(3.0)),
+                    Text2d::new("THEME EDITOR"),
+                    Typography::heading(&assets, &style).bundle(),
+                ));
+
+                // Spawn a row with a swatch for each role
+                for (i, role) in ThemeRole::ALL.into_iter().enumerate() {
+                    let y = 10.0 + i as f32 * 7.0;
+                    ui.spawn((
+                        UiLayout::window().pos(Rl((5.0, y + 2.5))).anchor(Anchor::CenterLeft).pack(),
+                        UiTextSize::from(Rh(2.5)),
+                        Text2d::new(role.name().to_ascii_uppercase()),
+                        Typography::body(&assets, &style).bundle(),
+                    ));
+                    ui.spawn((
+                        UiLayout::window().pos(Rl((70.0, y))).size(Rl((25.0, 5.0))).pack(),
+                        ThemedColor::from(role),
+                        Sprite::default(),
+                        OnHoverSetCursor::new(SystemCursorIcon::Pointer),
+                    )).observe(move |_: Trigger<Pointer<Click>>, mut commands: Commands, pickers: Query<Entity, With<EditorPicker>>| {
+                        // Only one picker at a time
+                        for picker in &pickers { commands.entity(picker).despawn_recursive(); }
+                        commands.entity(panel_entity).with_children(|ui| {
+                            ui.spawn((
+                                UiLayout::window().pos(Rl((5.0, 67.0))).size(Rl((90.0, 20.0))).pack(),
+                                ColorPicker::new(),
+                                EditorPicker,
+                            )).observe(move |trigger: Trigger<ColorPicked>, mut theme: ResMut<UiTheme>| {
+                                theme.set(role, trigger.event().0);
+                            });
+                        });
+                    });
+                }
+
+                // Spawn the export button
+                ui.spawn((
+                    UiLayout::window().pos(Rl((5.0, 90.0))).size(Rl((90.0, 6.0))).pack(),
+                    MainButton::new("Export").hint("ron"),
+                )).observe(|_: Trigger<MainButtonClicked>, theme: Res<UiTheme>, style: Res<UiStyle>| {
+                    UiStyle { theme: theme.clone(), ..style.clone() }.export(ThemeEditorPanel::EXPORT_PATH);
+                });
+            });
+        });
+    }
+}
+
+/// Marks the [`ColorPicker`] of the panel
+#[derive(Component)]
+struct EditorPicker;
+
+
+/// Plugin with the developer theme editor
+pub struct ThemeEditorPlugin;
+impl Plugin for ThemeEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, ThemeEditorPanel::toggle_system);
+    }
+}
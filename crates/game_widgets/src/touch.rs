@@ -0,0 +1,69 @@
+use bevy::{prelude::*, window::CursorMoved};
+use bevy_lunex::*;
+
+use crate::*;
+
+
+/// Switches the widgets to touch behavior. Detected from the last used input unless forced.
+#[derive(Resource, Reflect, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[reflect(Resource)]
+pub struct TouchMode {
+    /// Set to always or never use touch behavior
+    pub forced: Option<bool>,
+    detected: bool,
+}
+impl TouchMode {
+    /// Creates new instance forced on or off
+    pub fn forced(active: bool) -> Self {
+        Self { forced: Some(active), detected: active }
+    }
+    /// If the widgets should behave for touch now
+    pub fn is_active(&self) -> bool {
+        self.forced.unwrap_or(self.detected)
+    }
+    /// This system detects touch from the pointer presses and goes back on cursor movement. Touch drags
+    /// can be reported as mouse motion too, but they never move the cursor.
+    fn detect_system(mut mode: ResMut<TouchMode>, mut presses: EventReader<Pointer<Down>>, mut motion: EventReader<CursorMoved>) {
+        let touched = presses.read().any(|press| press.pointer_id.is_touch());
+        let moved = motion.read().count() > 0;
+        let detected = if touched { true } else if moved { false } else { mode.detected };
+        if mode.detected != detected { mode.detected = detected; }
+    }
+    /// This system makes a touch press count as hover too, as there is no pointer hovering before the tap.
+    /// The widgets get an [`Over`] along with the press and an [`Out`] along with the release.
+    fn hover_system(mut commands: Commands, mode: Res<TouchMode>, mut presses: EventReader<Pointer<Down>>, mut releases: EventReader<Pointer<Up>>) {
+        if !mode.is_active() {
+            presses.clear();
+            releases.clear();
+            return;
+        }
+        for press in presses.read().filter(|press| press.pointer_id.is_touch()) {
+            let over = Pointer::new(press.target, press.pointer_id, press.pointer_location.clone(), Over { hit: press.hit.clone() });
+            commands.trigger_targets(over, press.target);
+        }
+        for release in releases.read().filter(|release| release.pointer_id.is_touch()) {
+            let out = Pointer::new(release.target, release.pointer_id, release.pointer_location.clone(), Out { hit: release.hit.clone() });
+            commands.trigger_targets(out, release.target);
+        }
+    }
+    /// This system hides the software cursor while touch is used, nothing is pointing.
+    fn cursor_system(mode: Res<TouchMode>, mut cursors: Query<&mut Visibility, With<SoftwareCursor>>) {
+        let visibility = if mode.is_active() { Visibility::Hidden } else { Visibility::Inherited };
+        for mut cursor in &mut cursors { cursor.set_if_neq(visibility); }
+    }
+}
+
+
+/// Plugin with the [`TouchMode`] behavior
+pub(crate) struct TouchPlugin;
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchMode>();
+        app.register_type::<TouchMode>();
+        app.add_systems(PreUpdate, (
+            TouchMode::detect_system,
+            TouchMode::hover_system,
+            TouchMode::cursor_system.run_if(resource_changed::<TouchMode>.and(in_state(CursorMode::Menu))),
+        ).chain().after(bevy::picking::PickSet::Last));
+    }
+}
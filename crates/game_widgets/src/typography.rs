@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use bevy_lunex::*;
+use game_loading::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// Ready to use text styles with consistent fonts, sizes and colors.
+/// Widgets should start from these and only override what they need.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Typography {
+    /// The font to use
+    pub font: Handle<Font>,
+    /// The font of the bold [`RichText`] markup
+    pub bold: Handle<Font>,
+    /// The size of the glyphs rasterized by Bevy, the node size is controlled by [`UiTextSize`]
+    pub font_size: f32,
+    /// The base color of the text
+    pub color: ThemedColor,
+    /// The shadow keeping the text readable on the animated background
+    pub shadow: Option<TextShadow>,
+}
+impl Typography {
+    /// Large titles and headers
+    pub fn heading(assets: &AssetCache, style: &UiStyle) -> Self {
+        Self { font: assets.font_bold.clone(), bold: assets.font_bold.clone(), font_size: style.typography.heading, color: ThemedColor::from(ThemeRole::Text), shadow: None }
+    }
+    /// Labels of buttons and regular text
+    pub fn body(assets: &AssetCache, style: &UiStyle) -> Self {
+        Self { font: assets.font_medium.clone(), bold: assets.font_bold.clone(), font_size: style.typography.body, color: ThemedColor::from(ThemeRole::Text), shadow: Some(TextShadow::default()) }
+    }
+    /// Small secondary text
+    pub fn hint(assets: &AssetCache, style: &UiStyle) -> Self {
+        Self { font: assets.font_medium.clone(), bold: assets.font_bold.clone(), font_size: style.typography.hint, color: ThemedColor::alpha(ThemeRole::Accent, 0.2), shadow: None }
+    }
+    /// Numbers and text that needs to align
+    pub fn mono(assets: &AssetCache, style: &UiStyle) -> Self {
+        Self { font: assets.font_mono.clone(), bold: assets.font_bold.clone(), font_size: style.typography.mono, color: ThemedColor::from(ThemeRole::Text), shadow: None }
+    }
+    /// The preset of the role
+    pub fn role(role: TypographyRole, assets: &AssetCache, style: &UiStyle) -> Self {
+        match role {
+            TypographyRole::Heading => Typography::heading(assets, style),
+            TypographyRole::Body => Typography::body(assets, style),
+            TypographyRole::Hint => Typography::hint(assets, style),
+            TypographyRole::Mono => Typography::mono(assets, style),
+        }
+    }
+    /// Replace the font with a new one.
+    pub fn font(mut self, font: Handle<Font>) -> Self {
+        self.font = font;
+        self
+    }
+    /// Replace the font size with a new one.
+    pub fn font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+    /// Replace the color with a new one.
+    pub fn color(mut self, color: impl Into<ThemedColor>) -> Self {
+        self.color = color.into();
+        self
+    }
+    /// Replace the shadow with a new one.
+    pub fn shadow(mut self, shadow: Option<TextShadow>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+    /// Returns the font component
+    pub fn text_font(&self) -> TextFont {
+        TextFont {
+            font: self.font.clone(),
+            font_size: self.font_size,
+            ..default()
+        }
+    }
+    /// Returns the font and a static color, use this for text without hover states
+    pub fn bundle(&self) -> (TextFont, ThemedColor) {
+        (self.text_font(), self.color.clone())
+    }
+}
+
+/// Names one of the [`Typography`] presets, like in the rules of the [`StyleSheet`]
+#[derive(Reflect, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum TypographyRole {
+    Heading,
+    #[default]
+    Body,
+    Hint,
+    Mono,
+}
+impl TypographyRole {
+    /// All roles in the order of the [`TypographySizes`] fields
+    pub const ALL: [TypographyRole; 4] = [TypographyRole::Heading, TypographyRole::Body, TypographyRole::Hint, TypographyRole::Mono];
+    /// Returns the name used in the style file
+    pub fn name(&self) -> &'static str {
+        match self {
+            TypographyRole::Heading => "heading",
+            TypographyRole::Body => "body",
+            TypographyRole::Hint => "hint",
+            TypographyRole::Mono => "mono",
+        }
+    }
+    /// Returns the role with the name used in the style file
+    pub fn from_name(name: &str) -> Option<Self> {
+        TypographyRole::ALL.into_iter().find(|role| role.name() == name)
+    }
+}
@@ -0,0 +1,82 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use bevy::prelude::*;
+
+use crate::*;
+
+
+/// A payload widgets send as [`UiActionEvent`], usually an enum of the game like `MenuAction`
+pub trait UiAction: Clone + Send + Sync + 'static {}
+impl<T: Clone + Send + Sync + 'static> UiAction for T {}
+
+/// Makes the widget send [`UiActionEvent`] with the payload when it is activated, like a clicked [`MainButton`].
+/// The payload type must be registered with [`UiActionAppExt::add_ui_action`].
+#[derive(Component, Clone, Debug)]
+pub struct EmitsAction<T: UiAction>(pub T);
+
+/// Makes a widget carrying a value send [`UiActionEvent`] when it triggers the event, with the
+/// payload mapped from the event by the closure, like a [`ColorPicker`] sending `SetColor(color)`
+#[derive(Component)]
+pub struct EmitsValueAction<T: UiAction, E: Event> {
+    map: Arc<dyn Fn(&E) -> T + Send + Sync>,
+    marker: PhantomData<E>,
+}
+impl<T: UiAction, E: Event> EmitsValueAction<T, E> {
+    /// Creates new instance mapping the event into the payload
+    pub fn new(map: impl Fn(&E) -> T + Send + Sync + 'static) -> Self {
+        Self {
+            map: Arc::new(map),
+            marker: PhantomData,
+        }
+    }
+}
+impl<T: UiAction, E: Event> Clone for EmitsValueAction<T, E> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Sent when a widget with [`EmitsAction`] or [`EmitsValueAction`] is activated or changed, so game
+/// code reads one stream of its own actions instead of observing each widget
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct UiActionEvent<T: UiAction> {
+    /// The widget that sent it
+    pub entity: Entity,
+    pub action: T,
+}
+
+/// Registers the payload types of [`UiActionEvent`] and the widget events they are sent for
+pub trait UiActionAppExt {
+    /// Adds the [`UiActionEvent`] of the payload, sent for clicked [`MainButton`] and picked [`ColorPicker`] widgets.
+    /// Adding it again does nothing.
+    fn add_ui_action<T: UiAction>(&mut self) -> &mut Self;
+    /// Also sends the [`UiActionEvent`] of widgets with [`EmitsAction`] when the event is triggered on them, for your own widgets
+    fn add_ui_action_source<T: UiAction, E: Event>(&mut self) -> &mut Self;
+    /// Also sends the [`UiActionEvent`] of widgets with [`EmitsValueAction`] of the event when it is triggered on them
+    fn add_ui_value_source<T: UiAction, E: Event>(&mut self) -> &mut Self;
+}
+impl UiActionAppExt for App {
+    fn add_ui_action<T: UiAction>(&mut self) -> &mut Self {
+        if self.world().contains_resource::<Events<UiActionEvent<T>>>() { return self; }
+        self.add_event::<UiActionEvent<T>>();
+        self.add_ui_action_source::<T, MainButtonClicked>();
+        self.add_ui_value_source::<T, ColorPicked>()
+    }
+    fn add_ui_action_source<T: UiAction, E: Event>(&mut self) -> &mut Self {
+        self.add_ui_action::<T>();
+        self.add_observer(|trigger: Trigger<E>, query: Query<&EmitsAction<T>>, mut events: EventWriter<UiActionEvent<T>>| {
+            let Ok(emits) = query.get(trigger.entity()) else { return; };
+            events.send(UiActionEvent { entity: trigger.entity(), action: emits.0.clone() });
+        })
+    }
+    fn add_ui_value_source<T: UiAction, E: Event>(&mut self) -> &mut Self {
+        self.add_ui_action::<T>();
+        self.add_observer(|trigger: Trigger<E>, query: Query<&EmitsValueAction<T, E>>, mut events: EventWriter<UiActionEvent<T>>| {
+            let Ok(emits) = query.get(trigger.entity()) else { return; };
+            events.send(UiActionEvent { entity: trigger.entity(), action: (emits.map)(trigger.event()) });
+        })
+    }
+}
@@ -0,0 +1,242 @@
+use std::{any::type_name, marker::PhantomData, time::Duration};
+
+use bevy::{
+    a11y::AccessibilityPlugin,
+    input::InputPlugin,
+    picking::{backend::{HitData, PointerHits}, input::PointerInputPlugin, pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput, PressDirection}, DefaultPickingPlugins, PickSet},
+    prelude::*,
+    render::camera::{ManualTextureViewHandle, NormalizedRenderTarget},
+    state::app::StatesPlugin,
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+};
+use bevy_lunex::*;
+use game_loading::*;
+use game_preferences::*;
+use game_vfx::*;
+
+use crate::*;
+
+
+/// How far a color can be from the expected one in [`UiTestApp::assert_color_approx`], per sRGB channel
+const COLOR_TOLERANCE: f32 = 0.02;
+
+/// An app running the widgets without a window or rendering, for testing their behavior.
+/// The time moves by one 60 Hz frame per update unless [`UiTestApp::set_frame`] changed it, the pointer
+/// is moved by [`UiTestApp::hover`] and hits the hovered entity directly, and the assets are placeholders.
+pub struct UiTestApp {
+    pub app: App,
+    root: Option<Entity>,
+    /// The seconds one update advances the time by
+    frame: f32,
+}
+impl UiTestApp {
+    /// The seconds one update advances the time by
+    pub const FRAME: f32 = 1.0 / 60.0;
+    /// Creates the app with the widgets plugin and runs the startup
+    pub fn new() -> Self {
+        Self::with_timing(UiAnimationTiming::default())
+    }
+    /// Creates the app with the widgets plugin animating in the timing and runs the startup
+    pub fn with_timing(timing: UiAnimationTiming) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            StatesPlugin,
+            InputPlugin,
+            WindowPlugin { primary_window: None, exit_condition: ExitCondition::DontExit, close_when_requested: false },
+            AccessibilityPlugin,
+            DefaultPickingPlugins.set(PointerInputPlugin { is_mouse_enabled: false, is_touch_enabled: false }),
+            bevy_kira_audio::AudioPlugin,
+            UiLunexPlugin,
+        ));
+        app.init_asset::<Image>();
+        app.init_asset::<Font>();
+        app.init_asset::<TextureAtlasLayout>();
+
+        // What the loading and the preferences provide in the game
+        let world = app.world_mut();
+        let cache = world.resource_scope(|world, mut images: Mut<Assets<Image>>| AssetCache::placeholder(&mut images, &mut world.resource_mut::<Assets<Font>>()));
+        app.insert_resource(cache);
+        app.insert_state(UiAssetsState::Ready);
+        app.init_resource::<GameSettings>();
+        app.init_resource::<AudioSettings>();
+        app.init_resource::<ClearColor>();
+        app.insert_resource(timing);
+        app.add_plugins((VFXPlugin, WidgetsPlugin));
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(UiTestApp::FRAME)));
+        app.init_resource::<UiTestPointer>();
+        app.add_systems(PreUpdate, UiTestPointer::system.in_set(PickSet::Backend));
+
+        let camera = app.world_mut().spawn(Name::new("Test Camera")).id();
+        app.world_mut().resource_mut::<UiTestPointer>().camera = Some(camera);
+
+        let mut test = Self { app, root: None, frame: UiTestApp::FRAME };
+        test.watch::<MainButtonClicked>();
+        test.app.update();
+        test
+    }
+    /// The world of the app
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+    /// The world of the app
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+    /// Makes every following update advance the time by the seconds, like to test a low or a high frame rate
+    pub fn set_frame(&mut self, seconds: f32) {
+        self.frame = seconds;
+        self.world_mut().insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(seconds)));
+    }
+    /// Spawns a [`MainButton`] with the label into a 1280x720 root and builds it. Returns the button entity.
+    pub fn spawn_button(&mut self, text: impl std::fmt::Display) -> Entity {
+        let root = self.root();
+        let button = self.world_mut().spawn((
+            UiLayout::window().pos(Rl((40.0, 45.0))).size(Rl((20.0, 10.0))).pack(),
+            MainButton::new(text),
+        )).set_parent(root).id();
+
+        // The style is resolved in one update and the button is built with it in the next
+        self.advance(2.0 * self.frame);
+        assert!(self.world().get::<MainButtonControl>(button).is_some(), "MainButton {button} was not built");
+        button
+    }
+    /// Spawns the number of [`MainButton`]s in a grid filling the root and builds them, like for benchmarks. Returns the button entities.
+    pub fn spawn_buttons(&mut self, count: usize) -> Vec<Entity> {
+        let root = self.root();
+        let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+        let size = 100.0 / columns as f32;
+        let buttons: Vec<Entity> = (0..count).map(|i| self.world_mut().spawn((
+            UiLayout::window().pos(Rl(((i % columns) as f32 * size, (i / columns) as f32 * size))).size(Rl((size, size))).pack(),
+            MainButton::new(i),
+        )).set_parent(root).id()).collect();
+
+        self.advance(2.0 * self.frame);
+        for button in &buttons { assert!(self.world().get::<MainButtonControl>(*button).is_some(), "MainButton {button} was not built"); }
+        buttons
+    }
+    /// The 1280x720 root the buttons are spawned into, spawned with the first one
+    fn root(&mut self) -> Entity {
+        match self.root {
+            Some(root) => root,
+            None => {
+                let root = self.world_mut().spawn((UiLayoutRoot::new_2d(), Dimension(Vec2::new(1280.0, 720.0)))).id();
+                self.root = Some(root);
+                root
+            },
+        }
+    }
+    /// The image and the label entity of the built [`MainButton`]
+    pub fn button_parts(&self, button: Entity) -> (Entity, Entity) {
+        let control = self.world().get::<MainButtonControl>(button).unwrap_or_else(|| panic!("Entity {button} is not a built MainButton"));
+        (control.image(), control.text())
+    }
+    /// Moves the pointer onto the entity and runs one update, so picking sends the enter events.
+    pub fn hover(&mut self, entity: Entity) {
+        self.hover_all(&[entity]);
+    }
+    /// Moves the pointer onto all the entities and runs one update, like overlapping widgets. The first is hit nearest,
+    /// make the others hoverable below it with [`PickingBehavior::should_block_lower`].
+    pub fn hover_all(&mut self, entities: &[Entity]) {
+        let Some(entity) = entities.first().copied() else { return self.unhover(); };
+        self.world_mut().resource_mut::<UiTestPointer>().hovered = entities.to_vec();
+        let position = self.world().get::<GlobalTransform>(entity).map_or(Vec2::ZERO, |transform| transform.translation().truncate());
+        self.send_pointer(position, PointerAction::Moved { delta: Vec2::ZERO });
+        self.app.update();
+    }
+    /// Moves the pointer off everything and runs one update, so picking sends the leave events.
+    pub fn unhover(&mut self) {
+        self.world_mut().resource_mut::<UiTestPointer>().hovered.clear();
+        self.app.update();
+    }
+    /// Hovers the entity if it is not hovered yet, then presses and releases the primary button over it.
+    pub fn click(&mut self, entity: Entity) {
+        if self.world().resource::<UiTestPointer>().hovered != [entity] { self.hover(entity); }
+        let position = self.world().get::<GlobalTransform>(entity).map_or(Vec2::ZERO, |transform| transform.translation().truncate());
+        for direction in [PressDirection::Down, PressDirection::Up] {
+            self.send_pointer(position, PointerAction::Pressed { direction, button: PointerButton::Primary });
+            self.app.update();
+        }
+    }
+    /// Runs updates until the seconds passed, at least one
+    pub fn advance(&mut self, seconds: f32) {
+        let frames = (seconds / self.frame).round().max(1.0) as usize;
+        for _ in 0..frames { self.app.update(); }
+    }
+    /// Records the triggers of the event, so [`UiTestApp::assert_event`] can check them. [`MainButtonClicked`] is always recorded.
+    pub fn watch<E: Event>(&mut self) -> &mut Self {
+        if self.world().contains_resource::<UiTestEvents<E>>() { return self; }
+        self.world_mut().insert_resource(UiTestEvents::<E>::default());
+        self.world_mut().add_observer(|trigger: Trigger<E>, mut events: ResMut<UiTestEvents<E>>| {
+            events.targets.push(trigger.entity());
+        });
+        self
+    }
+    /// Panics if the color of the [`Sprite`] or the [`TextColor`] of the entity is not close to the color
+    pub fn assert_color_approx(&self, entity: Entity, color: impl Into<Color>) {
+        let actual = self.world().get::<Sprite>(entity).map(|sprite| sprite.color)
+            .or_else(|| self.world().get::<TextColor>(entity).map(|color| color.0))
+            .unwrap_or_else(|| panic!("Entity {entity} has neither a Sprite nor a TextColor"));
+        let (actual, expected) = (actual.to_srgba(), color.into().to_srgba());
+        let close = actual.to_f32_array().iter().zip(expected.to_f32_array()).all(|(actual, expected)| (actual - expected).abs() <= COLOR_TOLERANCE);
+        assert!(close, "The color of entity {entity} is {actual:?}, expected about {expected:?}");
+    }
+    /// Panics if the watched event was not triggered since the last check, returns the entity it targeted first
+    pub fn assert_event<E: Event>(&mut self) -> Entity {
+        let mut events = self.world_mut().get_resource_mut::<UiTestEvents<E>>()
+            .unwrap_or_else(|| panic!("{} is not watched, call UiTestApp::watch first", type_name::<E>()));
+        assert!(!events.targets.is_empty(), "{} was not triggered", type_name::<E>());
+        events.targets.remove(0)
+    }
+    /// Panics if the watched event was triggered since the last check
+    pub fn assert_no_event<E: Event>(&mut self) {
+        let events = self.world().get_resource::<UiTestEvents<E>>()
+            .unwrap_or_else(|| panic!("{} is not watched, call UiTestApp::watch first", type_name::<E>()));
+        assert!(events.targets.is_empty(), "{} was triggered on {:?}", type_name::<E>(), events.targets);
+    }
+    /// Sends the input of the test pointer, there is no window so it targets a texture view
+    fn send_pointer(&mut self, position: Vec2, action: PointerAction) {
+        let location = Location { target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)), position };
+        self.world_mut().send_event(PointerInput::new(PointerId::Mouse, location, action));
+    }
+}
+impl Default for UiTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The picking backend of the [`UiTestApp`], the hovered entity is hit without looking at the layout
+#[derive(Resource, Default)]
+struct UiTestPointer {
+    /// The entity standing in for the camera of the hits
+    camera: Option<Entity>,
+    /// The hit entities, the nearest first
+    hovered: Vec<Entity>,
+}
+impl UiTestPointer {
+    /// This system reports the hovered entities as hit by the mouse pointer.
+    fn system(pointer: Res<UiTestPointer>, mut hits: EventWriter<PointerHits>) {
+        let Some(camera) = pointer.camera else { return; };
+        if pointer.hovered.is_empty() { return; }
+        let picks = pointer.hovered.iter().enumerate().map(|(depth, entity)| (*entity, HitData::new(camera, depth as f32, None, None))).collect();
+        hits.send(PointerHits::new(PointerId::Mouse, picks, 0.0));
+    }
+}
+
+/// The targets of the triggers of a watched event, oldest first
+#[derive(Resource)]
+struct UiTestEvents<E: Event> {
+    targets: Vec<Entity>,
+    marker: PhantomData<E>,
+}
+impl<E: Event> Default for UiTestEvents<E> {
+    fn default() -> Self {
+        Self { targets: Vec::new(), marker: PhantomData }
+    }
+}
@@ -0,0 +1,127 @@
+use bevy::{
+    input::mouse::MouseMotion,
+    picking::pointer::{Location, PointerAction, PointerButton, PointerId, PointerInput, PressDirection},
+    prelude::*,
+    render::camera::NormalizedRenderTarget,
+    window::{PrimaryWindow, WindowRef},
+};
+
+use crate::*;
+
+
+/// Moves the cursor with the right gamepad stick, for menus that need a pointer like the color picker.
+/// The mode turns on when the stick moves and off when the physical mouse moves.
+#[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct VirtualCursor {
+    /// If the gamepad is driving the cursor right now
+    pub active: bool,
+    /// Speed at full tilt in pixels per second, for a window 1080 pixels high
+    pub speed: f32,
+    /// How much faster the cursor gets each second the stick is held, 1.0 doubles the speed after a second
+    pub acceleration: f32,
+    /// The speed multiplier near pickable nodes
+    pub magnetism: f32,
+    /// The distance in pixels at which the nodes start slowing the cursor down, for a window 1080 pixels high
+    pub magnet_radius: f32,
+    held: f32,
+    /// The window height relative to the reference height
+    scale: f32,
+}
+impl Default for VirtualCursor {
+    fn default() -> Self {
+        Self {
+            active: false,
+            speed: 900.0,
+            acceleration: 1.5,
+            magnetism: 0.35,
+            magnet_radius: 20.0,
+            held: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+impl VirtualCursor {
+    /// Stick deflection below this is ignored
+    const DEADZONE: f32 = 0.2;
+    /// The window height the speed and the radius are given for
+    const REFERENCE_HEIGHT: f32 = 1080.0;
+    /// This system rescales the speed when the primary window changes size, so the cursor feels the same.
+    fn metrics_system(mut cursor: ResMut<VirtualCursor>, mut events: EventReader<WindowMetricsChanged>, primary: Query<(), With<PrimaryWindow>>) {
+        if let Some(event) = events.read().filter(|event| primary.contains(event.window)).last() {
+            cursor.scale = event.size.y.max(1.0) / VirtualCursor::REFERENCE_HEIGHT;
+        }
+    }
+    /// This system switches the mode, moves the cursor and synthesizes the clicks.
+    #[allow(clippy::too_many_arguments)]
+    fn system(
+        mut cursor: ResMut<VirtualCursor>,
+        mut windows: Query<(Entity, &mut Window), With<PrimaryWindow>>,
+        cameras: UiCameras,
+        gamepads: Query<&Gamepad>,
+        mut motion: EventReader<MouseMotion>,
+        mut pointer: EventWriter<PointerInput>,
+        focus: Res<UiFocus>,
+        touch: Res<TouchMode>,
+        time: Res<Time>,
+    ) {
+        if touch.is_active() { return; }
+        let Ok((window_entity, mut window)) = windows.get_single_mut() else { return; };
+        let stick = gamepads.iter().map(|gamepad| gamepad.right_stick()).find(|stick| stick.length() > VirtualCursor::DEADZONE);
+
+        // Raw motion comes only from the physical mouse, warping the cursor does not produce it
+        if motion.read().count() > 0 && cursor.active { cursor.active = false; }
+        if stick.is_some() && !cursor.active { cursor.active = true; }
+        if !cursor.active { return; }
+
+        let size = window.size();
+        let position = window.cursor_position().unwrap_or(size / 2.0);
+        let target = WindowRef::Primary.normalize(Some(window_entity)).map(NormalizedRenderTarget::Window);
+        match stick {
+            Some(stick) => {
+                cursor.held += time.delta_secs();
+                let mut speed = cursor.speed * cursor.scale * (1.0 + cursor.acceleration * cursor.held);
+
+                // Slow down over and near the nodes, so they are easy to hit
+                if let Some((camera, transform)) = target.as_ref().and_then(|target| cameras.for_target(target)) {
+                    if let Ok(world) = camera.viewport_to_world_2d(transform, position) {
+                        let scale = transform.compute_transform().scale.x.max(f32::EPSILON);
+                        let near = focus.rects().iter().any(|(_, rect)| {
+                            let outside = (world - rect.center()).abs() - rect.half_size();
+                            outside.max(Vec2::ZERO).length() / scale <= cursor.magnet_radius * cursor.scale
+                        });
+                        if near { speed *= cursor.magnetism; }
+                    }
+                }
+
+                // The window has y pointing down, the stick up
+                let delta = Vec2::new(stick.x, -stick.y) * speed * time.delta_secs();
+                let position = (position + delta).clamp(Vec2::ZERO, size - Vec2::ONE);
+                window.set_cursor_position(Some(position));
+            },
+            None => cursor.held = 0.0,
+        }
+
+        // Press and release the primary button, so picking sees a mouse click
+        let Some(target) = target else { return; };
+        let location = Location { target, position };
+        let pressed = gamepads.iter().any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+        let released = gamepads.iter().any(|gamepad| gamepad.just_released(GamepadButton::South));
+        for (changed, direction) in [(pressed, PressDirection::Down), (released, PressDirection::Up)] {
+            if changed {
+                pointer.send(PointerInput::new(PointerId::Mouse, location.clone(), PointerAction::Pressed { direction, button: PointerButton::Primary }));
+            }
+        }
+    }
+}
+
+
+/// Plugin with the gamepad driven [`VirtualCursor`]
+pub(crate) struct VirtualCursorPlugin;
+impl Plugin for VirtualCursorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VirtualCursor>();
+        app.register_type::<VirtualCursor>();
+        app.add_systems(PreUpdate, (VirtualCursor::metrics_system, VirtualCursor::system).chain().after(bevy::input::InputSystem).before(bevy::picking::PickSet::Input));
+    }
+}
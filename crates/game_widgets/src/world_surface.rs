@@ -0,0 +1,204 @@
+use bevy::{
+    picking::{mesh_picking::ray_cast::{MeshRayCast, RayCastSettings}, pointer::{Location, PointerAction, PointerId, PointerInput, PointerInteraction, PointerLocation, PointerPress}, PickSet},
+    prelude::*,
+    render::{camera::RenderTarget, view::RenderLayers},
+    utils::HashMap,
+    window::{PrimaryWindow, WindowRef},
+};
+use bevy_lunex::*;
+use uuid::Uuid;
+
+use crate::*;
+
+
+/// Puts the UI children of this entity on a quad in the 3D world, like on a computer screen in the game.
+/// The children are moved under a 2D root laid out in the pixels of the resolution and rendered into a
+/// texture shown on the quad. The mouse is raycast onto the quad and drives a pointer of its own, so
+/// widgets like [`MainButton`] hover and click the same as on the screen. Every surface renders on its own
+/// layer, up to 15 surfaces can exist at once.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+#[require(Transform, Visibility)]
+pub struct UiWorldSurface {
+    /// The size of the quad in world units
+    pub size: Vec2,
+    /// The size of the texture and of the layout in pixels
+    pub resolution: UVec2,
+}
+impl UiWorldSurface {
+    /// Creates new instance with 1000 pixels along the longer side
+    pub fn new(size: Vec2) -> Self {
+        let scale = 1000.0 / size.max_element().max(f32::EPSILON);
+        Self { size, resolution: (size * scale).as_uvec2().max(UVec2::ONE) }
+    }
+    /// Set the resolution of the texture.
+    pub fn resolution(mut self, resolution: UVec2) -> Self {
+        self.resolution = resolution.max(UVec2::ONE);
+        self
+    }
+    /// This system builds the quad, the texture camera and the root of new surfaces.
+    fn build_system(
+        mut commands: Commands,
+        mut surfaces: ResMut<UiWorldSurfaces>,
+        query: Query<(Entity, &UiWorldSurface), Added<UiWorldSurface>>,
+        mut images: ResMut<Assets<Image>>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+    ) {
+        for (entity, surface) in &query {
+            // The layers of removed surfaces are reused, the capture layer stays free
+            let Some(layer) = (UiWorldSurfaces::FIRST_LAYER..CAPTURE_LAYER).find(|layer| surfaces.rigs.values().all(|rig| rig.layer != *layer)) else {
+                warn!("No render layer is left for the world surface {entity}, it stays blank");
+                continue;
+            };
+            let image = CaptureToTexture::create_target(&mut images, surface.resolution);
+
+            // Far away from the screen UI and the other surfaces, so the sprite picking never mixes them
+            let origin = Vec3::new(0.0, -100_000.0 * (layer - UiWorldSurfaces::FIRST_LAYER + 1) as f32, 0.0);
+            let camera = commands.spawn((
+                Name::new("World Surface Camera"),
+                Camera2d,
+                Camera::clear_render_to(image.clone()).with_order(-1),
+                RenderLayers::layer(layer),
+                Transform::from_translation(origin + Vec3::Z * 1000.0),
+            )).id();
+            let root = commands.spawn((
+                Name::new("World Surface Root"),
+                UiLayoutRoot::new_2d(),
+                Dimension(surface.resolution.as_vec2()),
+                RenderLayers::layer(layer),
+                Transform::from_translation(origin),
+            )).id();
+            let pointer = PointerId::Custom(Uuid::from_u128(UiWorldSurfaces::POINTER_BASE + layer as u128));
+            commands.spawn((Name::new("World Surface Pointer"), pointer, PointerLocation::default(), PointerPress::default(), PointerInteraction::default()));
+
+            commands.entity(entity).insert((
+                Mesh3d(meshes.add(Rectangle::from_size(surface.size))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color_texture: Some(image.clone()),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                })),
+            ));
+            surfaces.rigs.insert(entity, UiWorldSurfaceRig { root, camera, layer, pointer, image, hit: false });
+        }
+    }
+    /// This system moves the UI children of the surfaces under their root and keeps the whole tree on the layer.
+    fn adopt_system(
+        mut commands: Commands,
+        surfaces: Res<UiWorldSurfaces>,
+        children: Query<&Children>,
+        layouts: Query<(), With<UiLayout>>,
+        unlayered: Query<(), Without<RenderLayers>>,
+    ) {
+        for (surface, rig) in &surfaces.rigs {
+            let adopted: Vec<Entity> = children.get(*surface).map(|children| children.iter().copied().filter(|child| layouts.contains(*child)).collect()).unwrap_or_default();
+            if !adopted.is_empty() { commands.entity(rig.root).add_children(&adopted); }
+            for entity in children.iter_descendants(rig.root).filter(|entity| unlayered.contains(*entity)) {
+                commands.entity(entity).insert(RenderLayers::layer(rig.layer));
+            }
+        }
+    }
+    /// This system casts the window cursor onto the meshes and forwards its movement and presses to the pointer of the surface hit nearest.
+    fn pointer_system(
+        mut surfaces: ResMut<UiWorldSurfaces>,
+        query: Query<(&UiWorldSurface, &GlobalTransform)>,
+        mut ray_cast: MeshRayCast,
+        windows: Query<(Entity, &Window), With<PrimaryWindow>>,
+        cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+        mut inputs: ParamSet<(EventReader<PointerInput>, EventWriter<PointerInput>)>,
+    ) {
+        let presses: Vec<PointerAction> = inputs.p0().read()
+            .filter(|input| input.pointer_id == PointerId::Mouse && matches!(input.action, PointerAction::Pressed { .. }))
+            .map(|input| input.action)
+            .collect();
+        let Ok((window, window_data)) = windows.get_single() else { return; };
+        let cursor = window_data.cursor_position();
+
+        // The frontmost 3D camera on the window sees the surfaces
+        let window_target = RenderTarget::Window(WindowRef::Primary).normalize(Some(window));
+        let camera = cameras.iter()
+            .filter(|(camera, _)| camera.is_active && camera.target.normalize(Some(window)) == window_target)
+            .max_by_key(|(camera, _)| camera.order);
+        let ray = camera.zip(cursor).and_then(|((camera, transform), cursor)| camera.viewport_to_world(transform, cursor).ok());
+
+        // Only the nearest mesh is hit, so a surface behind a wall or behind another surface is missed
+        let hit = ray.and_then(|ray| ray_cast.cast_ray(ray, &RayCastSettings::default()).first().map(|(entity, hit)| (*entity, hit.point)));
+
+        let mut writer = inputs.p1();
+        for (surface_entity, rig) in surfaces.rigs.iter_mut() {
+            let Ok((surface, transform)) = query.get(*surface_entity) else { continue; };
+            let Some(target) = RenderTarget::Image(rig.image.clone().into()).normalize(None) else { continue; };
+
+            // Convert the hit on the quad into the pixels of the texture, y pointing down like on a window
+            let position = hit.filter(|(entity, _)| entity == surface_entity).and_then(|(_, point)| {
+                let local = transform.affine().inverse().transform_point3(point).truncate();
+                let uv = Vec2::new(local.x / surface.size.x + 0.5, 0.5 - local.y / surface.size.y);
+                (uv.cmpge(Vec2::ZERO).all() && uv.cmple(Vec2::ONE).all()).then(|| uv * surface.resolution.as_vec2())
+            });
+
+            // A miss moves the pointer off the texture, so the widgets see it leave
+            if position.is_none() && !rig.hit { continue; }
+            rig.hit = position.is_some();
+            let location = Location { target, position: position.unwrap_or(Vec2::NEG_ONE) };
+            writer.send(PointerInput::new(rig.pointer, location.clone(), PointerAction::Moved { delta: Vec2::ZERO }));
+            if position.is_none() { continue; }
+            for action in &presses {
+                writer.send(PointerInput::new(rig.pointer, location.clone(), *action));
+            }
+        }
+    }
+    /// This system despawns the root, the camera and the pointer of removed surfaces.
+    fn cleanup_system(
+        mut commands: Commands,
+        mut surfaces: ResMut<UiWorldSurfaces>,
+        query: Query<(), With<UiWorldSurface>>,
+        pointers: Query<(Entity, &PointerId)>,
+    ) {
+        let removed: Vec<Entity> = surfaces.rigs.keys().copied().filter(|entity| !query.contains(*entity)).collect();
+        for entity in removed {
+            let Some(rig) = surfaces.rigs.remove(&entity) else { continue; };
+            commands.entity(rig.root).despawn_recursive();
+            commands.entity(rig.camera).despawn_recursive();
+            for (pointer, _) in pointers.iter().filter(|(_, id)| **id == rig.pointer) { commands.entity(pointer).despawn(); }
+        }
+    }
+}
+
+/// The render texture setup of every [`UiWorldSurface`]
+#[derive(Resource, Default)]
+struct UiWorldSurfaces {
+    rigs: HashMap<Entity, UiWorldSurfaceRig>,
+}
+impl UiWorldSurfaces {
+    /// The render layer of the first surface, every surface gets its own free one below [`CAPTURE_LAYER`]
+    const FIRST_LAYER: usize = 16;
+    /// The pointer ids of the surfaces count up from this
+    const POINTER_BASE: u128 = 0x7569_776f_726c_6473_7572_6661_6365_0000;
+}
+
+/// The entities rendering one [`UiWorldSurface`]
+struct UiWorldSurfaceRig {
+    root: Entity,
+    camera: Entity,
+    layer: usize,
+    pointer: PointerId,
+    image: Handle<Image>,
+    /// If the cursor was on the surface in the last frame
+    hit: bool,
+}
+
+
+/// Plugin with the [`UiWorldSurface`] panels
+pub(crate) struct UiWorldSurfacePlugin;
+impl Plugin for UiWorldSurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UiWorldSurfaces>();
+        app.add_systems(Update, (
+            UiWorldSurface::cleanup_system,
+            UiWorldSurface::build_system,
+            UiWorldSurface::adopt_system,
+        ).chain().before(SandboxedUiSystems));
+        app.add_systems(PreUpdate, UiWorldSurface::pointer_system.in_set(PickSet::Input));
+    }
+}